@@ -0,0 +1,337 @@
+use bevy::audio::{AudioBundle, AudioSource, PlaybackSettings, SpatialListener, Volume};
+use bevy::prelude::*;
+use oldtimes_core::{components::Position, events::*};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Which category a sound cue belongs to, for per-category volume control.
+/// Kept as a plain enum (rather than the cue's own `HashMap` key) so the
+/// same small set of categories can be shared by several cues.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoundCategory {
+    Placement,
+    Hauling,
+    Production,
+    Alert,
+}
+
+/// The gameplay event a sound cue fires in response to. Also the key
+/// `AudioCooldowns` throttles on, so a wave of identical events (e.g. many
+/// auto-distribution hauls landing in the same tick) can only retrigger the
+/// cue once per `SoundCue::cooldown_secs`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AudioCueKind {
+    BuildingPlaced,
+    HaulStarted,
+    DeliveryCompleted,
+    PathfindingFailed,
+}
+
+/// One entry in the event -> sound-asset mapping: which file to play, which
+/// category's volume slider controls it, and the per-event-type throttle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SoundCue {
+    /// File name under `assets/sounds/`.
+    pub asset: String,
+    category: String,
+    pub cooldown_secs: f32,
+}
+
+/// On-disk shape of `config/audio.toml`. Mirrors `InputConfigFile`: explicit
+/// fields for the fixed set of cues this crate knows about, tokens (here,
+/// category names) as plain strings since TOML can't key on an enum.
+#[derive(Debug, Serialize, Deserialize)]
+struct AudioConfigFile {
+    building_placed: SoundCue,
+    haul_started: SoundCue,
+    delivery_completed: SoundCue,
+    pathfinding_failed: SoundCue,
+    /// Category name -> volume multiplier. Only the categories actually
+    /// listed need to be present; anything missing keeps its default of 1.0.
+    #[serde(default)]
+    category_volume: HashMap<String, f32>,
+}
+
+/// Maps each `AudioCueKind` to its `SoundCue` and each `SoundCategory` to its
+/// volume multiplier. Loaded from a TOML config file at startup with a
+/// hardcoded fallback - same missing/invalid-file-falls-back-to-default
+/// contract as `InputMap::load_or_default`.
+#[derive(Resource, Debug, Clone)]
+pub struct AudioFeedbackConfig {
+    cues: HashMap<AudioCueKind, SoundCue>,
+    category_volume: HashMap<SoundCategory, f32>,
+}
+
+impl AudioFeedbackConfig {
+    pub fn load_or_default<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Self::default();
+        }
+
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                log::warn!("Failed to read audio config {:?}, using defaults: {e}", path);
+                return Self::default();
+            }
+        };
+
+        match toml::from_str::<AudioConfigFile>(&content) {
+            Ok(file) => Self::from_file(file),
+            Err(e) => {
+                log::warn!("Failed to parse audio config {:?}, using defaults: {e}", path);
+                Self::default()
+            }
+        }
+    }
+
+    fn from_file(file: AudioConfigFile) -> Self {
+        let mut cues = HashMap::new();
+        cues.insert(AudioCueKind::BuildingPlaced, file.building_placed);
+        cues.insert(AudioCueKind::HaulStarted, file.haul_started);
+        cues.insert(AudioCueKind::DeliveryCompleted, file.delivery_completed);
+        cues.insert(AudioCueKind::PathfindingFailed, file.pathfinding_failed);
+
+        let mut category_volume = HashMap::from([
+            (SoundCategory::Placement, 1.0),
+            (SoundCategory::Hauling, 1.0),
+            (SoundCategory::Production, 1.0),
+            (SoundCategory::Alert, 1.0),
+        ]);
+        for (name, volume) in file.category_volume {
+            match parse_category(&name) {
+                Some(category) => {
+                    category_volume.insert(category, volume);
+                }
+                None => log::warn!("Unknown sound category '{}' in audio config, ignoring", name),
+            }
+        }
+
+        Self { cues, category_volume }
+    }
+
+    fn cue(&self, kind: AudioCueKind) -> Option<&SoundCue> {
+        self.cues.get(&kind)
+    }
+
+    fn volume_for(&self, cue: &SoundCue) -> f32 {
+        match parse_category(&cue.category) {
+            Some(category) => self.category_volume.get(&category).copied().unwrap_or(1.0),
+            None => 1.0,
+        }
+    }
+
+    /// The asset name of every cue, repeated when several cues share a sound
+    /// file; `load_sfx_assets` still loads each distinct file only once.
+    fn asset_names(&self) -> impl Iterator<Item = &str> {
+        self.cues.values().map(|cue| cue.asset.as_str())
+    }
+}
+
+impl Default for AudioFeedbackConfig {
+    fn default() -> Self {
+        Self::from_file(AudioConfigFile {
+            building_placed: SoundCue {
+                asset: "building_placed.ogg".to_string(),
+                category: "placement".to_string(),
+                cooldown_secs: 0.05,
+            },
+            haul_started: SoundCue {
+                asset: "haul_started.ogg".to_string(),
+                category: "hauling".to_string(),
+                cooldown_secs: 0.5,
+            },
+            delivery_completed: SoundCue {
+                asset: "delivery_completed.ogg".to_string(),
+                category: "production".to_string(),
+                cooldown_secs: 0.3,
+            },
+            pathfinding_failed: SoundCue {
+                asset: "pathfinding_failed.ogg".to_string(),
+                category: "alert".to_string(),
+                cooldown_secs: 1.0,
+            },
+            category_volume: HashMap::new(),
+        })
+    }
+}
+
+fn parse_category(name: &str) -> Option<SoundCategory> {
+    Some(match name {
+        "placement" => SoundCategory::Placement,
+        "hauling" => SoundCategory::Hauling,
+        "production" => SoundCategory::Production,
+        "alert" => SoundCategory::Alert,
+        _ => return None,
+    })
+}
+
+/// Loaded sound handles, keyed by the asset name cues reference in
+/// `config/audio.toml`. Kept separate from `GameAssets` since sounds and
+/// sprites are loaded from unrelated directories and have no shared fields.
+#[derive(Resource, Debug, Default)]
+pub struct SfxAssets {
+    sounds: HashMap<String, Handle<AudioSource>>,
+}
+
+impl SfxAssets {
+    fn get(&self, name: &str) -> Option<Handle<AudioSource>> {
+        self.sounds.get(name).cloned()
+    }
+}
+
+pub fn load_sfx_assets(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<AudioFeedbackConfig>,
+) {
+    let mut sounds = HashMap::new();
+    for name in config.asset_names() {
+        sounds
+            .entry(name.to_string())
+            .or_insert_with(|| asset_server.load(format!("sounds/{name}")));
+    }
+
+    commands.insert_resource(SfxAssets { sounds });
+    log::info!("Sound effect assets loaded");
+}
+
+/// Tick (in seconds, via `Time::elapsed_seconds`) each `AudioCueKind` was
+/// last actually played, so `audio_feedback_system` can throttle a burst of
+/// same-kind events down to one cue per `SoundCue::cooldown_secs`.
+#[derive(Resource, Debug, Default)]
+pub struct AudioCooldowns {
+    last_played: HashMap<AudioCueKind, f32>,
+}
+
+/// Listens for events that should give the player audible feedback and
+/// plays a positioned cue at the relevant tile, throttled per
+/// `AudioCueKind` so e.g. a wave of auto-distribution transfers can't stack
+/// into noise. Spawned sounds use `PlaybackSettings::DESPAWN` so they clean
+/// themselves up once finished.
+pub fn audio_feedback_system(
+    mut commands: Commands,
+    mut placements: EventReader<PlaceBuildingEvent>,
+    mut transfers: EventReader<TransferResourceEvent>,
+    mut completions: EventReader<TaskCompletedEvent>,
+    mut failures: EventReader<PathfindingFailedEvent>,
+    positions: Query<&Position>,
+    config: Res<AudioFeedbackConfig>,
+    assets: Option<Res<SfxAssets>>,
+    mut cooldowns: ResMut<AudioCooldowns>,
+    time: Res<Time>,
+) {
+    let Some(assets) = assets else {
+        return;
+    };
+
+    for event in placements.read() {
+        play_cue(
+            &mut commands,
+            &config,
+            &assets,
+            &mut cooldowns,
+            &time,
+            AudioCueKind::BuildingPlaced,
+            event.position,
+        );
+    }
+
+    for event in transfers.read() {
+        if let Ok(position) = positions.get(event.to) {
+            play_cue(
+                &mut commands,
+                &config,
+                &assets,
+                &mut cooldowns,
+                &time,
+                AudioCueKind::HaulStarted,
+                *position,
+            );
+        }
+    }
+
+    for event in completions.read() {
+        if event.task_type != "delivery" {
+            continue;
+        }
+        let Some(destination) = event.destination else {
+            continue;
+        };
+        if let Ok(position) = positions.get(destination) {
+            play_cue(
+                &mut commands,
+                &config,
+                &assets,
+                &mut cooldowns,
+                &time,
+                AudioCueKind::DeliveryCompleted,
+                *position,
+            );
+        }
+    }
+
+    for event in failures.read() {
+        play_cue(
+            &mut commands,
+            &config,
+            &assets,
+            &mut cooldowns,
+            &time,
+            AudioCueKind::PathfindingFailed,
+            event.from,
+        );
+    }
+}
+
+fn play_cue(
+    commands: &mut Commands,
+    config: &AudioFeedbackConfig,
+    assets: &SfxAssets,
+    cooldowns: &mut AudioCooldowns,
+    time: &Time,
+    kind: AudioCueKind,
+    position: Position,
+) {
+    let Some(cue) = config.cue(kind) else {
+        return;
+    };
+
+    let now = time.elapsed_seconds();
+    if let Some(&last_played) = cooldowns.last_played.get(&kind) {
+        if now - last_played < cue.cooldown_secs {
+            return;
+        }
+    }
+
+    let Some(source) = assets.get(&cue.asset) else {
+        return;
+    };
+
+    commands.spawn((
+        AudioBundle {
+            source,
+            settings: PlaybackSettings::DESPAWN
+                .with_spatial(true)
+                .with_volume(Volume::new(config.volume_for(cue))),
+        },
+        TransformBundle::from_transform(Transform::from_xyz(
+            position.x as f32 * crate::rendering::TILE_SIZE,
+            position.y as f32 * crate::rendering::TILE_SIZE,
+            0.0,
+        )),
+    ));
+
+    cooldowns.last_played.insert(kind, now);
+}
+
+/// Attaches the spatial audio listener to the main camera so cue panning and
+/// distance falloff are relative to what the player is looking at.
+pub fn setup_audio_listener(mut commands: Commands, camera: Query<Entity, Added<Camera2d>>) {
+    for entity in camera.iter() {
+        commands.entity(entity).insert(SpatialListener::new(4.0));
+    }
+}
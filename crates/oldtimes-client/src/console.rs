@@ -0,0 +1,170 @@
+use crate::{BuildingPlacer, DebugOverlay};
+use bevy::{ecs::system::SystemState, prelude::*};
+use bevy_egui::{egui, EguiContexts};
+use oldtimes_core::{
+    components::{Position, Stockpile},
+    events::{MapChangeType, MapChangedEvent, PlaceBuildingEvent},
+    map,
+    resources::{GameConfig, GameTick, MapData, Visibility},
+};
+
+/// State for the in-game developer console, toggled with the backtick key.
+#[derive(Resource, Default)]
+pub struct ConsoleState {
+    pub open: bool,
+    pub input: String,
+    pub history: Vec<String>,
+}
+
+pub fn console_toggle_system(keyboard: Res<ButtonInput<KeyCode>>, mut state: ResMut<ConsoleState>) {
+    if keyboard.just_pressed(KeyCode::Backquote) {
+        state.open = !state.open;
+    }
+}
+
+/// Draws the console window and, on Enter, hands the typed line to
+/// `run_console_command`. Runs as an exclusive system so commands can reach
+/// across whichever resources/entities they need without a giant query list.
+pub fn console_ui_system(world: &mut World) {
+    if !world.resource::<ConsoleState>().open {
+        return;
+    }
+
+    let mut egui_state: SystemState<EguiContexts> = SystemState::new(world);
+    let ctx = egui_state.get_mut(world).ctx_mut().clone();
+
+    let mut input = std::mem::take(&mut world.resource_mut::<ConsoleState>().input);
+    let history = world.resource::<ConsoleState>().history.clone();
+    let mut submit = false;
+
+    egui::Window::new("Console").show(&ctx, |ui| {
+        ui.horizontal(|ui| {
+            ui.label(">");
+            let response = ui.text_edit_singleline(&mut input);
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                submit = true;
+            }
+        });
+        ui.separator();
+        ui.label(
+            "map demo | map generate [seed] | spawn <building> | grant <resource> <amount> | tps <n> | debug <pathfinding|performance>",
+        );
+        egui::ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
+            for line in history.iter().rev().take(20) {
+                ui.label(line);
+            }
+        });
+    });
+
+    if submit {
+        let command = input.trim().to_string();
+        input.clear();
+        if !command.is_empty() {
+            let output = run_console_command(world, &command);
+            let mut state = world.resource_mut::<ConsoleState>();
+            state.history.push(format!("> {command}"));
+            state.history.push(output);
+        }
+    }
+
+    world.resource_mut::<ConsoleState>().input = input;
+}
+
+/// Parses a single console line and applies it directly to the `World`.
+fn run_console_command(world: &mut World, command: &str) -> String {
+    let tokens: Vec<&str> = command.split_whitespace().collect();
+
+    match tokens.as_slice() {
+        ["map", "demo"] => {
+            let (width, height) = {
+                let mut map = world.resource_mut::<MapData>();
+                map::generate_demo_map(&mut map);
+                (map.width, map.height)
+            };
+            *world.resource_mut::<Visibility>() = Visibility::new(width, height);
+            world.send_event(MapChangedEvent {
+                position: Position::new(0, 0),
+                change_type: MapChangeType::TerrainChanged,
+                // Empty means "everything changed" - a full regen, so every
+                // cached path and flow field is stale, not just one tile.
+                affected_tiles: Vec::new(),
+            });
+            "Regenerated demo map".to_string()
+        }
+        ["map", "generate"] | ["map", "generate", _] => {
+            let mut config = world.resource::<GameConfig>().map_generation.clone();
+            if let Some(seed) = tokens.get(2).and_then(|s| s.parse::<u64>().ok()) {
+                config.seed = seed;
+            }
+            let new_map = map::generate_map(&config);
+            let (width, height) = (new_map.width, new_map.height);
+            *world.resource_mut::<MapData>() = new_map;
+            *world.resource_mut::<Visibility>() = Visibility::new(width, height);
+            world.send_event(MapChangedEvent {
+                position: Position::new(0, 0),
+                change_type: MapChangeType::TerrainChanged,
+                affected_tiles: Vec::new(),
+            });
+            format!("Regenerated map with seed {}", config.seed)
+        }
+        ["spawn", building_type] => {
+            let Some(position) = world.resource::<BuildingPlacer>().preview_position else {
+                return "No tile highlighted - hover the map first".to_string();
+            };
+            if !world
+                .resource::<GameConfig>()
+                .buildings
+                .contains_key(*building_type)
+            {
+                return format!("Unknown building '{building_type}'");
+            }
+            world.send_event(PlaceBuildingEvent {
+                building_type: building_type.to_string(),
+                position,
+            });
+            format!("Spawning {building_type} at {position:?}")
+        }
+        ["grant", resource, amount] => {
+            let Ok(amount) = amount.parse::<u32>() else {
+                return format!("Invalid amount '{amount}'");
+            };
+            let Some(position) = world.resource::<BuildingPlacer>().preview_position else {
+                return "No tile highlighted - hover the stockpile first".to_string();
+            };
+
+            let mut query = world.query::<(&Position, &mut Stockpile)>();
+            for (pos, mut stockpile) in query.iter_mut(world) {
+                if *pos == position {
+                    let granted = stockpile.add_item(resource.to_string(), amount);
+                    return format!("Granted {granted} {resource}");
+                }
+            }
+            format!("No stockpile at {position:?}")
+        }
+        ["tps", value] => {
+            let Ok(tps) = value.parse::<u32>() else {
+                return format!("Invalid tick rate '{value}'");
+            };
+            world.resource_mut::<GameTick>().target_tps = tps;
+            format!("Set target TPS to {tps}")
+        }
+        ["debug", "pathfinding"] => {
+            let mut overlay = world.resource_mut::<DebugOverlay>();
+            overlay.show_pathfinding = !overlay.show_pathfinding;
+            format!(
+                "Pathfinding overlay: {}",
+                if overlay.show_pathfinding { "ON" } else { "OFF" }
+            )
+        }
+        ["debug", "performance"] => {
+            let mut overlay = world.resource_mut::<DebugOverlay>();
+            overlay.show_performance = !overlay.show_performance;
+            format!(
+                "Performance overlay: {}",
+                if overlay.show_performance { "ON" } else { "OFF" }
+            )
+        }
+        [] => String::new(),
+        _ => format!("Unknown command: {command}"),
+    }
+}
@@ -0,0 +1,64 @@
+use bevy::prelude::*;
+use sysinfo::{Pid, System};
+
+/// Host resource usage, refreshed on a throttled timer rather than every
+/// frame since `sysinfo`'s refresh calls are comparatively expensive.
+#[derive(Resource)]
+pub struct SystemDiagnostics {
+    pub cpu_usage_percent: f32,
+    pub process_memory_bytes: u64,
+    pub system_used_memory_bytes: u64,
+    pub system_total_memory_bytes: u64,
+    system: System,
+    pid: Pid,
+    refresh_timer: Timer,
+}
+
+impl SystemDiagnostics {
+    const REFRESH_INTERVAL_SECS: f32 = 0.5;
+
+    pub fn new() -> Self {
+        let pid = sysinfo::get_current_pid().unwrap_or(Pid::from(0));
+
+        Self {
+            cpu_usage_percent: 0.0,
+            process_memory_bytes: 0,
+            system_used_memory_bytes: 0,
+            system_total_memory_bytes: 0,
+            system: System::new_all(),
+            pid,
+            refresh_timer: Timer::from_seconds(Self::REFRESH_INTERVAL_SECS, TimerMode::Repeating),
+        }
+    }
+}
+
+impl Default for SystemDiagnostics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Samples CPU and memory usage on a throttled timer and stores the results
+/// in `SystemDiagnostics` for the debug overlay to display.
+pub fn update_system_diagnostics_system(
+    time: Res<Time>,
+    mut diagnostics: ResMut<SystemDiagnostics>,
+) {
+    if !diagnostics.refresh_timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    diagnostics.system.refresh_cpu_usage();
+    diagnostics.system.refresh_memory();
+    let pid = diagnostics.pid;
+    diagnostics.system.refresh_process(pid);
+
+    diagnostics.cpu_usage_percent = diagnostics.system.global_cpu_usage();
+    diagnostics.process_memory_bytes = diagnostics
+        .system
+        .process(pid)
+        .map(|process| process.memory())
+        .unwrap_or(0);
+    diagnostics.system_used_memory_bytes = diagnostics.system.used_memory();
+    diagnostics.system_total_memory_bytes = diagnostics.system.total_memory();
+}
@@ -1,9 +1,359 @@
 use crate::{BuildingPlacer, CameraController, DebugOverlay};
 use bevy::prelude::*;
 use oldtimes_core::{components::*, events::*};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
 
-pub fn camera_movement_system(
+/// A player-facing action, decoupled from whatever physical key, mouse
+/// button, or gamepad button triggers it so `InputMap` can rebind it without
+/// touching the systems that react to it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum InputAction {
+    PanUp,
+    PanDown,
+    PanLeft,
+    PanRight,
+    CancelSelection,
+    ConfirmPlacement,
+    ToggleDebug,
+    TogglePathfindingViz,
+    TogglePerformance,
+    /// Selects the building type keyed by this id in `GameConfig::buildings`.
+    SelectBuilding(String),
+}
+
+/// One or more physical inputs that all trigger the same `InputAction`.
+#[derive(Debug, Clone, Default)]
+pub struct Binding {
+    pub keys: Vec<KeyCode>,
+    pub mouse_buttons: Vec<MouseButton>,
+    pub gamepad_buttons: Vec<GamepadButtonType>,
+}
+
+/// On-disk shape of `config/input.toml`. Tokens are plain strings (e.g.
+/// `"KeyW"`, `"Mouse:Left"`, `"Gamepad:South"`) rather than `InputAction`
+/// itself, since TOML has no way to key a table on an enum - mirrors how
+/// `GridFile` in `oldtimes_core::data` wraps a type that isn't a valid
+/// document root on its own.
+#[derive(Debug, Serialize, Deserialize)]
+struct InputConfigFile {
+    pan_up: Vec<String>,
+    pan_down: Vec<String>,
+    pan_left: Vec<String>,
+    pan_right: Vec<String>,
+    cancel_selection: Vec<String>,
+    confirm_placement: Vec<String>,
+    toggle_debug: Vec<String>,
+    toggle_pathfinding_viz: Vec<String>,
+    toggle_performance: Vec<String>,
+    /// Building id -> hotkey tokens. A plain map (rather than fixed fields)
+    /// so new building hotkeys can be added by editing the config, not the
+    /// code.
+    #[serde(default)]
+    building_hotkeys: HashMap<String, Vec<String>>,
+}
+
+/// Maps every `InputAction` to the physical inputs that trigger it. Loaded
+/// from a TOML config file at startup with a hardcoded fallback: a missing,
+/// unreadable or unparsable file falls back to the default bindings, so a
+/// bad or absent config can never stop the game from starting.
+#[derive(Resource, Debug, Clone)]
+pub struct InputMap {
+    bindings: HashMap<InputAction, Binding>,
+}
+
+impl InputMap {
+    pub fn load_or_default<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Self::default();
+        }
+
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                log::warn!(
+                    "Failed to read input config {:?}, using defaults: {e}",
+                    path
+                );
+                return Self::default();
+            }
+        };
+
+        match toml::from_str::<InputConfigFile>(&content) {
+            Ok(file) => Self::from_file(file),
+            Err(e) => {
+                log::warn!(
+                    "Failed to parse input config {:?}, using defaults: {e}",
+                    path
+                );
+                Self::default()
+            }
+        }
+    }
+
+    fn from_file(file: InputConfigFile) -> Self {
+        let mut bindings = HashMap::new();
+        bindings.insert(InputAction::PanUp, parse_binding(&file.pan_up));
+        bindings.insert(InputAction::PanDown, parse_binding(&file.pan_down));
+        bindings.insert(InputAction::PanLeft, parse_binding(&file.pan_left));
+        bindings.insert(InputAction::PanRight, parse_binding(&file.pan_right));
+        bindings.insert(
+            InputAction::CancelSelection,
+            parse_binding(&file.cancel_selection),
+        );
+        bindings.insert(
+            InputAction::ConfirmPlacement,
+            parse_binding(&file.confirm_placement),
+        );
+        bindings.insert(InputAction::ToggleDebug, parse_binding(&file.toggle_debug));
+        bindings.insert(
+            InputAction::TogglePathfindingViz,
+            parse_binding(&file.toggle_pathfinding_viz),
+        );
+        bindings.insert(
+            InputAction::TogglePerformance,
+            parse_binding(&file.toggle_performance),
+        );
+        for (building_id, tokens) in file.building_hotkeys {
+            bindings.insert(
+                InputAction::SelectBuilding(building_id),
+                parse_binding(&tokens),
+            );
+        }
+
+        Self { bindings }
+    }
+
+    /// Building ids with a configured hotkey, for
+    /// `building_placement_input_system` to poll each frame.
+    pub fn building_hotkeys(&self) -> impl Iterator<Item = &str> {
+        self.bindings.keys().filter_map(|action| match action {
+            InputAction::SelectBuilding(id) => Some(id.as_str()),
+            _ => None,
+        })
+    }
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        Self::from_file(InputConfigFile {
+            pan_up: vec!["KeyW".to_string(), "ArrowUp".to_string()],
+            pan_down: vec!["KeyS".to_string(), "ArrowDown".to_string()],
+            pan_left: vec!["KeyA".to_string(), "ArrowLeft".to_string()],
+            pan_right: vec!["KeyD".to_string(), "ArrowRight".to_string()],
+            cancel_selection: vec!["Escape".to_string()],
+            confirm_placement: vec!["Mouse:Left".to_string()],
+            toggle_debug: vec!["F1".to_string()],
+            toggle_pathfinding_viz: vec!["F2".to_string()],
+            toggle_performance: vec!["F3".to_string()],
+            building_hotkeys: [
+                ("lumberjack".to_string(), vec!["KeyQ".to_string()]),
+                ("sawmill".to_string(), vec!["KeyE".to_string()]),
+                ("farm".to_string(), vec!["KeyR".to_string()]),
+                ("mill".to_string(), vec!["KeyT".to_string()]),
+                ("bakery".to_string(), vec!["KeyY".to_string()]),
+                ("quarry".to_string(), vec!["KeyU".to_string()]),
+            ]
+            .into(),
+        })
+    }
+}
+
+fn parse_binding(tokens: &[String]) -> Binding {
+    let mut binding = Binding::default();
+    for token in tokens {
+        if let Some(name) = token.strip_prefix("Mouse:") {
+            match parse_mouse_button(name) {
+                Some(button) => binding.mouse_buttons.push(button),
+                None => log::warn!("Unknown mouse binding '{}', ignoring", token),
+            }
+        } else if let Some(name) = token.strip_prefix("Gamepad:") {
+            match parse_gamepad_button(name) {
+                Some(button) => binding.gamepad_buttons.push(button),
+                None => log::warn!("Unknown gamepad binding '{}', ignoring", token),
+            }
+        } else {
+            match parse_key_code(token) {
+                Some(key) => binding.keys.push(key),
+                None => log::warn!("Unknown key binding '{}', ignoring", token),
+            }
+        }
+    }
+    binding
+}
+
+fn parse_mouse_button(name: &str) -> Option<MouseButton> {
+    Some(match name {
+        "Left" => MouseButton::Left,
+        "Right" => MouseButton::Right,
+        "Middle" => MouseButton::Middle,
+        _ => return None,
+    })
+}
+
+fn parse_gamepad_button(name: &str) -> Option<GamepadButtonType> {
+    Some(match name {
+        "South" => GamepadButtonType::South,
+        "East" => GamepadButtonType::East,
+        "West" => GamepadButtonType::West,
+        "North" => GamepadButtonType::North,
+        "LeftTrigger" => GamepadButtonType::LeftTrigger,
+        "LeftTrigger2" => GamepadButtonType::LeftTrigger2,
+        "RightTrigger" => GamepadButtonType::RightTrigger,
+        "RightTrigger2" => GamepadButtonType::RightTrigger2,
+        "Select" => GamepadButtonType::Select,
+        "Start" => GamepadButtonType::Start,
+        "DPadUp" => GamepadButtonType::DPadUp,
+        "DPadDown" => GamepadButtonType::DPadDown,
+        "DPadLeft" => GamepadButtonType::DPadLeft,
+        "DPadRight" => GamepadButtonType::DPadRight,
+        _ => return None,
+    })
+}
+
+fn parse_key_code(name: &str) -> Option<KeyCode> {
+    Some(match name {
+        "Escape" => KeyCode::Escape,
+        "Space" => KeyCode::Space,
+        "Enter" => KeyCode::Enter,
+        "Tab" => KeyCode::Tab,
+        "ArrowUp" => KeyCode::ArrowUp,
+        "ArrowDown" => KeyCode::ArrowDown,
+        "ArrowLeft" => KeyCode::ArrowLeft,
+        "ArrowRight" => KeyCode::ArrowRight,
+        "F1" => KeyCode::F1,
+        "F2" => KeyCode::F2,
+        "F3" => KeyCode::F3,
+        "F4" => KeyCode::F4,
+        "F5" => KeyCode::F5,
+        _ => {
+            if let Some(letter) = name.strip_prefix("Key") {
+                return parse_key_letter(letter);
+            }
+            if let Some(digit) = name.strip_prefix("Digit") {
+                return parse_key_digit(digit);
+            }
+            return None;
+        }
+    })
+}
+
+fn parse_key_letter(letter: &str) -> Option<KeyCode> {
+    Some(match letter {
+        "A" => KeyCode::KeyA,
+        "B" => KeyCode::KeyB,
+        "C" => KeyCode::KeyC,
+        "D" => KeyCode::KeyD,
+        "E" => KeyCode::KeyE,
+        "F" => KeyCode::KeyF,
+        "G" => KeyCode::KeyG,
+        "H" => KeyCode::KeyH,
+        "I" => KeyCode::KeyI,
+        "J" => KeyCode::KeyJ,
+        "K" => KeyCode::KeyK,
+        "L" => KeyCode::KeyL,
+        "M" => KeyCode::KeyM,
+        "N" => KeyCode::KeyN,
+        "O" => KeyCode::KeyO,
+        "P" => KeyCode::KeyP,
+        "Q" => KeyCode::KeyQ,
+        "R" => KeyCode::KeyR,
+        "S" => KeyCode::KeyS,
+        "T" => KeyCode::KeyT,
+        "U" => KeyCode::KeyU,
+        "V" => KeyCode::KeyV,
+        "W" => KeyCode::KeyW,
+        "X" => KeyCode::KeyX,
+        "Y" => KeyCode::KeyY,
+        "Z" => KeyCode::KeyZ,
+        _ => return None,
+    })
+}
+
+fn parse_key_digit(digit: &str) -> Option<KeyCode> {
+    Some(match digit {
+        "0" => KeyCode::Digit0,
+        "1" => KeyCode::Digit1,
+        "2" => KeyCode::Digit2,
+        "3" => KeyCode::Digit3,
+        "4" => KeyCode::Digit4,
+        "5" => KeyCode::Digit5,
+        "6" => KeyCode::Digit6,
+        "7" => KeyCode::Digit7,
+        "8" => KeyCode::Digit8,
+        "9" => KeyCode::Digit9,
+        _ => return None,
+    })
+}
+
+/// Per-frame snapshot of which `InputAction`s are active, resolved from
+/// `InputMap` against the raw keyboard/mouse/gamepad state by
+/// `update_action_state_system`. The three input systems below query this
+/// instead of polling `ButtonInput` directly, so rebinding `InputMap` is all
+/// it takes to change what triggers them.
+#[derive(Resource, Debug, Default)]
+pub struct ActionState {
+    pressed: HashSet<InputAction>,
+    just_pressed: HashSet<InputAction>,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: &InputAction) -> bool {
+        self.pressed.contains(action)
+    }
+
+    pub fn just_pressed(&self, action: &InputAction) -> bool {
+        self.just_pressed.contains(action)
+    }
+}
+
+pub fn update_action_state_system(
+    input_map: Res<InputMap>,
     keyboard: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut action_state: ResMut<ActionState>,
+) {
+    action_state.pressed.clear();
+    action_state.just_pressed.clear();
+
+    for (action, binding) in &input_map.bindings {
+        let pressed = binding.keys.iter().any(|key| keyboard.pressed(*key))
+            || binding
+                .mouse_buttons
+                .iter()
+                .any(|button| mouse.pressed(*button))
+            || binding.gamepad_buttons.iter().any(|button_type| {
+                gamepads.iter().any(|gamepad| {
+                    gamepad_buttons.pressed(GamepadButton::new(gamepad, *button_type))
+                })
+            });
+        let just_pressed = binding.keys.iter().any(|key| keyboard.just_pressed(*key))
+            || binding
+                .mouse_buttons
+                .iter()
+                .any(|button| mouse.just_pressed(*button))
+            || binding.gamepad_buttons.iter().any(|button_type| {
+                gamepads.iter().any(|gamepad| {
+                    gamepad_buttons.just_pressed(GamepadButton::new(gamepad, *button_type))
+                })
+            });
+
+        if pressed {
+            action_state.pressed.insert(action.clone());
+        }
+        if just_pressed {
+            action_state.just_pressed.insert(action.clone());
+        }
+    }
+}
+
+pub fn camera_movement_system(
+    action_state: Res<ActionState>,
     mut camera_query: Query<&mut Transform, With<Camera>>,
     controller: Res<CameraController>,
     time: Res<Time>,
@@ -11,16 +361,16 @@ pub fn camera_movement_system(
     let mut camera_transform = camera_query.single_mut();
     let mut movement = Vec3::ZERO;
 
-    if keyboard.pressed(KeyCode::KeyW) || keyboard.pressed(KeyCode::ArrowUp) {
+    if action_state.pressed(&InputAction::PanUp) {
         movement.y += 1.0;
     }
-    if keyboard.pressed(KeyCode::KeyS) || keyboard.pressed(KeyCode::ArrowDown) {
+    if action_state.pressed(&InputAction::PanDown) {
         movement.y -= 1.0;
     }
-    if keyboard.pressed(KeyCode::KeyA) || keyboard.pressed(KeyCode::ArrowLeft) {
+    if action_state.pressed(&InputAction::PanLeft) {
         movement.x -= 1.0;
     }
-    if keyboard.pressed(KeyCode::KeyD) || keyboard.pressed(KeyCode::ArrowRight) {
+    if action_state.pressed(&InputAction::PanRight) {
         movement.x += 1.0;
     }
 
@@ -31,41 +381,24 @@ pub fn camera_movement_system(
 }
 
 pub fn building_placement_input_system(
-    keyboard: Res<ButtonInput<KeyCode>>,
-    mouse: Res<ButtonInput<MouseButton>>,
+    action_state: Res<ActionState>,
+    input_map: Res<InputMap>,
     windows: Query<&Window>,
     camera_query: Query<(&Camera, &GlobalTransform)>,
     mut placer: ResMut<BuildingPlacer>,
     mut placement_events: EventWriter<PlaceBuildingEvent>,
 ) {
     // Building selection hotkeys
-    if keyboard.just_pressed(KeyCode::KeyQ) {
-        placer.selected_building = Some("lumberjack".to_string());
-        log::info!("Selected: Lumberjack");
-    }
-    if keyboard.just_pressed(KeyCode::KeyE) {
-        placer.selected_building = Some("sawmill".to_string());
-        log::info!("Selected: Sawmill");
-    }
-    if keyboard.just_pressed(KeyCode::KeyR) {
-        placer.selected_building = Some("farm".to_string());
-        log::info!("Selected: Farm");
-    }
-    if keyboard.just_pressed(KeyCode::KeyT) {
-        placer.selected_building = Some("mill".to_string());
-        log::info!("Selected: Mill");
-    }
-    if keyboard.just_pressed(KeyCode::KeyY) {
-        placer.selected_building = Some("bakery".to_string());
-        log::info!("Selected: Bakery");
-    }
-    if keyboard.just_pressed(KeyCode::KeyU) {
-        placer.selected_building = Some("quarry".to_string());
-        log::info!("Selected: Quarry");
+    for building_id in input_map.building_hotkeys() {
+        if action_state.just_pressed(&InputAction::SelectBuilding(building_id.to_string())) {
+            placer.selected_building = Some(building_id.to_string());
+            log::info!("Selected: {}", building_id);
+            break;
+        }
     }
 
     // Cancel selection
-    if keyboard.just_pressed(KeyCode::Escape) {
+    if action_state.just_pressed(&InputAction::CancelSelection) {
         placer.selected_building = None;
         placer.preview_position = None;
         log::info!("Building selection cancelled");
@@ -85,8 +418,8 @@ pub fn building_placement_input_system(
 
                 placer.preview_position = Some(tile_pos);
 
-                // Place building on left click
-                if mouse.just_pressed(MouseButton::Left) {
+                // Place building on confirm
+                if action_state.just_pressed(&InputAction::ConfirmPlacement) {
                     placement_events.send(PlaceBuildingEvent {
                         building_type: building_type.clone(),
                         position: tile_pos,
@@ -99,12 +432,9 @@ pub fn building_placement_input_system(
     }
 }
 
-pub fn ui_input_system(
-    keyboard: Res<ButtonInput<KeyCode>>,
-    mut debug_overlay: ResMut<DebugOverlay>,
-) {
+pub fn ui_input_system(action_state: Res<ActionState>, mut debug_overlay: ResMut<DebugOverlay>) {
     // Toggle debug overlay
-    if keyboard.just_pressed(KeyCode::F1) {
+    if action_state.just_pressed(&InputAction::ToggleDebug) {
         debug_overlay.enabled = !debug_overlay.enabled;
         log::info!(
             "Debug overlay: {}",
@@ -113,7 +443,7 @@ pub fn ui_input_system(
     }
 
     // Toggle pathfinding visualization
-    if keyboard.just_pressed(KeyCode::F2) {
+    if action_state.just_pressed(&InputAction::TogglePathfindingViz) {
         debug_overlay.show_pathfinding = !debug_overlay.show_pathfinding;
         log::info!(
             "Pathfinding debug: {}",
@@ -126,7 +456,7 @@ pub fn ui_input_system(
     }
 
     // Toggle performance metrics
-    if keyboard.just_pressed(KeyCode::F3) {
+    if action_state.just_pressed(&InputAction::TogglePerformance) {
         debug_overlay.show_performance = !debug_overlay.show_performance;
         log::info!(
             "Performance debug: {}",
@@ -0,0 +1,193 @@
+use bevy::{ecs::system::SystemState, prelude::*};
+use bevy_egui::{egui, EguiContexts};
+use oldtimes_core::components::*;
+
+/// Tracks whether the reflection-based inspector panel is open and which
+/// entity it's currently showing, toggled with F4 and populated by clicking
+/// a tile while the panel is open.
+#[derive(Resource, Default)]
+pub struct InspectorState {
+    pub enabled: bool,
+    pub selected_entity: Option<Entity>,
+}
+
+pub fn inspector_toggle_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut state: ResMut<InspectorState>,
+) {
+    if keyboard.just_pressed(KeyCode::F4) {
+        state.enabled = !state.enabled;
+        log::info!(
+            "Inspector panel: {}",
+            if state.enabled { "ON" } else { "OFF" }
+        );
+    }
+}
+
+/// Picks whichever entity with a `Position` sits under the cursor's tile,
+/// using the same cursor-to-tile math `building_placement_input_system` uses.
+pub fn inspector_pick_system(
+    mouse: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    positioned_entities: Query<(Entity, &Position)>,
+    mut state: ResMut<InspectorState>,
+) {
+    if !state.enabled || !mouse.just_pressed(MouseButton::Left) {
+        return;
+    }
+
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+    let Ok((camera, camera_transform)) = camera_query.get_single() else {
+        return;
+    };
+    let Some(cursor_pos) = window.cursor_position() else {
+        return;
+    };
+    let Some(world_pos) = camera.viewport_to_world_2d(camera_transform, cursor_pos) else {
+        return;
+    };
+
+    let tile_pos = Position::new(
+        (world_pos.x / 32.0).floor() as i32,
+        (world_pos.y / 32.0).floor() as i32,
+    );
+
+    state.selected_entity = positioned_entities
+        .iter()
+        .find(|(_, pos)| **pos == tile_pos)
+        .map(|(entity, _)| entity);
+}
+
+/// Renders every registered component on the selected entity as an editable
+/// reflected tree, and writes edits straight back into the live `World`.
+pub fn inspector_ui_system(world: &mut World) {
+    if !world.resource::<InspectorState>().enabled {
+        return;
+    }
+
+    let mut egui_state: SystemState<EguiContexts> = SystemState::new(world);
+    let ctx = egui_state.get_mut(world).ctx_mut().clone();
+
+    let Some(entity) = world.resource::<InspectorState>().selected_entity else {
+        egui::Window::new("Inspector").show(&ctx, |ui| {
+            ui.label("Click a tile's entity to inspect it.");
+        });
+        return;
+    };
+
+    if world.get_entity(entity).is_none() {
+        world.resource_mut::<InspectorState>().selected_entity = None;
+        return;
+    }
+
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+
+    egui::Window::new(format!("Inspector - {entity:?}")).show(&ctx, |ui| {
+        // Power satisfaction lives on `PowerGrid`, keyed by entity, rather
+        // than as a reflected component, so it needs its own line instead
+        // of falling out of the generic reflection loop below.
+        if world.get::<Building>(entity).is_some() {
+            let satisfaction = world
+                .resource::<oldtimes_core::resources::PowerGrid>()
+                .satisfaction(entity);
+            let status = if satisfaction >= 1.0 {
+                "Powered"
+            } else if satisfaction > 0.0 {
+                "Underpowered"
+            } else {
+                "No power"
+            };
+            ui.label(format!("Power: {status} ({:.0}%)", satisfaction * 100.0));
+            ui.separator();
+        }
+
+        for registration in registry.iter() {
+            let Some(reflect_component) = registration.data::<ReflectComponent>() else {
+                continue;
+            };
+            let Some(mut reflected) = reflect_component.reflect_mut(world, entity) else {
+                continue;
+            };
+
+            let short_name = registration.type_info().type_path_table().short_path();
+            ui.collapsing(short_name, |ui| {
+                draw_reflect_ui(ui, reflected.as_reflect_mut());
+            });
+        }
+    });
+}
+
+/// Draws a single reflected field as a widget matching its `ReflectMut`
+/// variant: structs/enums expand into their named fields, lists into
+/// indexed entries, and leaf values into a typed input.
+fn draw_reflect_ui(ui: &mut egui::Ui, value: &mut dyn Reflect) {
+    match value.reflect_mut() {
+        ReflectMut::Struct(s) => {
+            for i in 0..s.field_len() {
+                let name = s.name_at(i).unwrap_or("?").to_string();
+                if let Some(field) = s.field_at_mut(i) {
+                    ui.horizontal(|ui| {
+                        ui.label(name);
+                        draw_reflect_ui(ui, field);
+                    });
+                }
+            }
+        }
+        ReflectMut::TupleStruct(s) => {
+            for i in 0..s.field_len() {
+                if let Some(field) = s.field_mut(i) {
+                    draw_reflect_ui(ui, field);
+                }
+            }
+        }
+        ReflectMut::Enum(e) => {
+            ui.label(e.variant_name());
+            for i in 0..e.field_len() {
+                if let Some(field) = e.field_at_mut(i) {
+                    draw_reflect_ui(ui, field);
+                }
+            }
+        }
+        ReflectMut::List(list) => {
+            for i in 0..list.len() {
+                if let Some(item) = list.get_mut(i) {
+                    draw_reflect_ui(ui, item);
+                }
+            }
+        }
+        ReflectMut::Map(map) => {
+            for i in 0..map.len() {
+                if let Some((key, val)) = map.get_at_mut(i) {
+                    ui.horizontal(|ui| {
+                        ui.label(format!("{key:?}"));
+                        draw_reflect_ui(ui, val);
+                    });
+                }
+            }
+        }
+        ReflectMut::Value(value) => {
+            if let Some(v) = value.downcast_mut::<f32>() {
+                ui.add(egui::DragValue::new(v).speed(0.1));
+            } else if let Some(v) = value.downcast_mut::<u32>() {
+                ui.add(egui::DragValue::new(v));
+            } else if let Some(v) = value.downcast_mut::<i32>() {
+                ui.add(egui::DragValue::new(v));
+            } else if let Some(v) = value.downcast_mut::<u8>() {
+                ui.add(egui::DragValue::new(v));
+            } else if let Some(v) = value.downcast_mut::<bool>() {
+                ui.checkbox(v, "");
+            } else if let Some(v) = value.downcast_mut::<String>() {
+                ui.text_edit_singleline(v);
+            } else {
+                ui.label(format!("{value:?}"));
+            }
+        }
+        _ => {
+            ui.label("<unsupported>");
+        }
+    }
+}
@@ -1,14 +1,27 @@
 #![allow(dead_code, clippy::type_complexity, clippy::useless_format)]
 
 use bevy::prelude::*;
+use bevy_egui::EguiPlugin;
 use oldtimes_core::{components::*, events::*, resources::*};
 
+mod audio;
+mod console;
+mod diagnostics;
 mod input;
+mod inspector;
+mod menu;
 mod rendering;
+mod state;
 mod ui;
 
+use audio::*;
+use console::*;
+use diagnostics::*;
 use input::*;
+use inspector::*;
+use menu::*;
 use rendering::*;
+use state::*;
 use ui::*;
 
 fn main() {
@@ -23,6 +36,7 @@ fn main() {
             }),
             ..default()
         }))
+        .add_plugins(EguiPlugin)
         .add_plugins(GamePlugin)
         .run();
 }
@@ -36,13 +50,55 @@ impl Plugin for GamePlugin {
             .init_resource::<MapData>()
             .init_resource::<PathfindingCache>()
             .init_resource::<PerformanceMetrics>()
-            .init_resource::<GameConfig>();
+            .init_resource::<TickTimingHistory>()
+            .init_resource::<GameConfig>()
+            .init_resource::<PheromoneField>()
+            .init_resource::<Reservations>()
+            .init_resource::<Visibility>()
+            .init_resource::<oldtimes_core::systems::ConsiderationRegistry>()
+            .init_resource::<SpatialIndex>()
+            .init_resource::<oldtimes_core::resources::PowerGrid>()
+            .init_resource::<oldtimes_core::resources::MarketPrices>()
+            .init_resource::<oldtimes_core::resources::PlayerMoney>()
+            .init_resource::<oldtimes_core::resources::BeltNetwork>()
+            .init_resource::<oldtimes_core::resources::FrameAllocator>()
+            .init_resource::<oldtimes_core::save::AutosaveConfig>()
+            .init_resource::<oldtimes_core::save::AutosaveState>()
+            .init_non_send_resource::<oldtimes_core::scripting::ScriptHost>();
+
+        // Sprite metadata is loaded through the asset pipeline (not read off
+        // disk directly) so it gets async loading, dependency tracking for
+        // atlas maps, and hot-reload via `AssetEvent`.
+        app.init_asset::<oldtimes_core::assets::SpriteMetadata>()
+            .init_asset_loader::<oldtimes_core::assets::SpriteMetadataLoader>()
+            .init_asset::<oldtimes_core::assets::AtlasFrameMap>()
+            .init_asset_loader::<oldtimes_core::assets::AtlasFrameMapLoader>()
+            .init_resource::<oldtimes_core::assets::SpriteMetadataRoots>();
 
         // Add client-specific resources
         app.init_resource::<GameSpeed>()
             .init_resource::<CameraController>()
             .init_resource::<BuildingPlacer>()
-            .init_resource::<DebugOverlay>();
+            .init_resource::<DebugOverlay>()
+            .init_resource::<InspectorState>()
+            .init_resource::<SystemDiagnostics>()
+            .init_resource::<ConsoleState>()
+            .insert_resource(input::InputMap::load_or_default("config/input.toml"))
+            .init_resource::<input::ActionState>()
+            .insert_resource(AudioFeedbackConfig::load_or_default("config/audio.toml"))
+            .init_resource::<AudioCooldowns>();
+
+        // Register components for the reflection-based inspector panel
+        app.register_type::<Position>()
+            .register_type::<Blocked>()
+            .register_type::<Road>()
+            .register_type::<Stockpile>()
+            .register_type::<Building>()
+            .register_type::<ConstructionMaterials>()
+            .register_type::<Producer>()
+            .register_type::<Worker>()
+            .register_type::<MovementSpeed>()
+            .register_type::<Tile>();
 
         // Add events
         app.add_event::<PlaceBuildingEvent>()
@@ -53,74 +109,165 @@ impl Plugin for GamePlugin {
             .add_event::<BuildingConstructedEvent>()
             .add_event::<ProductionCompletedEvent>()
             .add_event::<PathfindingRequestEvent>()
+            .add_event::<PathfindingFailedEvent>()
             .add_event::<MapChangedEvent>()
             .add_event::<SaveGameEvent>()
             .add_event::<LoadGameEvent>()
             .add_event::<ReplayEvent>()
             .add_event::<ProfileEvent>()
             .add_event::<LoadModEvent>()
-            .add_event::<ReloadConfigEvent>();
+            .add_event::<ReloadConfigEvent>()
+            .add_event::<WareRequestEvent>()
+            .add_event::<BuildRoadEvent>();
 
-        // Add systems
-        app.add_systems(
-            Startup,
-            (
-                oldtimes_core::assets::load_sprite_metadata_system,
-                setup_camera,
-                setup_ui,
-                initialize_game,
-                rendering::load_game_assets,
+        // Client lifecycle: loading screen until every sprite is ready, then gameplay
+        app.init_state::<AppState>()
+            .add_systems(
+                Startup,
+                (
+                    oldtimes_core::assets::load_sprite_metadata_system,
+                    setup_camera,
+                    setup_audio_listener,
+                    setup_ui,
+                    initialize_game,
+                    rendering::load_game_assets,
+                    audio::load_sfx_assets,
+                )
+                    .chain(),
             )
-                .chain(),
-        )
-        // Add input systems
+            .add_systems(OnEnter(AppState::Loading), setup_loading_screen)
+            .add_systems(
+                Update,
+                check_assets_ready.run_if(in_state(AppState::Loading)),
+            )
+            // Reacts to `SpriteMetadata`/`AtlasFrameMap` asset events
+            // regardless of state, so the metadata-driven asset list and
+            // atlas handles are ready by the time `check_assets_ready`
+            // would otherwise flip us into `InGame`.
+            .add_systems(
+                Update,
+                (
+                    oldtimes_core::assets::handle_sprite_metadata_events_system,
+                    rendering::refresh_game_assets_from_metadata_system,
+                ),
+            )
+            .add_systems(OnEnter(AppState::InGame), teardown_loading_screen)
+            // Main menu
+            .add_systems(OnEnter(AppState::MainMenu), setup_main_menu)
+            .add_systems(OnExit(AppState::MainMenu), teardown_main_menu)
+            .add_systems(
+                Update,
+                (main_menu_button_system, button_hover_system)
+                    .run_if(in_state(AppState::MainMenu)),
+            )
+            // Pause menu
+            .add_systems(OnEnter(AppState::Paused), setup_pause_menu)
+            .add_systems(OnExit(AppState::Paused), teardown_pause_menu)
+            .add_systems(
+                Update,
+                (pause_menu_button_system, button_hover_system, handle_save_load_events_system)
+                    .run_if(in_state(AppState::Paused)),
+            )
+            // Game over screen
+            .add_systems(OnEnter(AppState::GameOver), setup_game_over_screen)
+            .add_systems(OnExit(AppState::GameOver), teardown_game_over_screen)
+            .add_systems(
+                Update,
+                (game_over_button_system, button_hover_system)
+                    .run_if(in_state(AppState::GameOver)),
+            )
+            .add_systems(
+                Update,
+                pause_toggle_system.run_if(
+                    in_state(AppState::InGame).or_else(in_state(AppState::Paused)),
+                ),
+            )
+        // Add input systems. Chained so `update_action_state_system` always
+        // refreshes `ActionState` before the systems that read it run.
         .add_systems(
             Update,
             (
+                input::update_action_state_system,
                 camera_movement_system,
                 building_placement_input_system,
                 ui_input_system,
-            ),
+                inspector_toggle_system,
+                inspector_pick_system,
+                console_toggle_system,
+            )
+                .chain()
+                .run_if(in_state(AppState::InGame)),
         )
         // Add core simulation systems
         .add_systems(
             Update,
             (
                 oldtimes_core::systems::advance_tick_system,
-                oldtimes_core::systems::building_placement_system,
-                oldtimes_core::systems::worker_assignment_system,
-                oldtimes_core::systems::start_production_system,
-                oldtimes_core::systems::construction_system,
-                oldtimes_core::systems::production_system,
-                oldtimes_core::systems::worker_ai_system,
-                oldtimes_core::systems::pathfinding_system,
-            ),
+                oldtimes_core::scripting::load_mod_script_system,
+                oldtimes_core::scripting::reload_mod_scripts_system,
+                oldtimes_core::systems::building_placement_system_timed,
+                oldtimes_core::systems::spatial_index_system_timed,
+                oldtimes_core::systems::flag_spawning_system_timed,
+                oldtimes_core::systems::road_building_system_timed,
+                oldtimes_core::systems::carrier_assignment_system_timed,
+                oldtimes_core::systems::worker_assignment_system_timed,
+                oldtimes_core::systems::start_production_system_timed,
+                oldtimes_core::systems::power_grid_system_timed,
+                oldtimes_core::systems::belt_network_system_timed,
+                oldtimes_core::systems::construction_system_timed,
+                oldtimes_core::systems::production_system_timed,
+                oldtimes_core::systems::market_system_timed,
+                oldtimes_core::scripting::script_event_hook_system,
+                oldtimes_core::systems::visibility_system_timed,
+                oldtimes_core::systems::worker_ai_system_timed,
+                oldtimes_core::systems::worker_destination_revalidation_system_timed,
+                oldtimes_core::systems::carrier_arrival_system_timed,
+                oldtimes_core::systems::ware_request_system_timed,
+                oldtimes_core::systems::carrier_dispatch_system_timed,
+                oldtimes_core::systems::pathfinding_system_timed,
+            )
+                .run_if(in_state(AppState::InGame)),
+        )
+        // Periodic crash-resume autosave - an exclusive system (it needs the
+        // whole `World` to serialize), so it gets its own `add_systems` call
+        // rather than joining the tuples above.
+        .add_systems(
+            Update,
+            oldtimes_core::save::autosave_system.run_if(in_state(AppState::InGame)),
         )
         // Add more simulation systems
         .add_systems(
             Update,
             (
-                oldtimes_core::systems::movement_system,
-                oldtimes_core::systems::transport_system,
-                oldtimes_core::systems::resource_distribution_system,
-                oldtimes_core::systems::transport_completion_system,
-                oldtimes_core::systems::invalidate_pathfinding_cache_system,
+                oldtimes_core::systems::movement_system_timed,
+                oldtimes_core::systems::transport_system_timed,
+                oldtimes_core::systems::resource_distribution_system_timed,
+                oldtimes_core::systems::transport_completion_system_timed,
+                oldtimes_core::systems::invalidate_pathfinding_cache_system_timed,
                 oldtimes_core::systems::profile_systems_system,
-                oldtimes_core::systems::spawn_workers_system,
-            ),
+                oldtimes_core::systems::spawn_workers_system_timed,
+            )
+                .run_if(in_state(AppState::InGame)),
         )
         // Add rendering and UI systems
         .add_systems(
             Update,
             (
                 render_map_system,
+                animate_tiles_system,
+                update_fog_of_war_system,
                 render_buildings_system,
                 render_workers_system,
+                update_placement_highlight_system,
+                update_system_diagnostics_system,
                 update_ui_system,
                 update_debug_overlay_system,
                 game_speed_control_system,
-                oldtimes_core::assets::hot_reload_sprite_metadata_system,
-            ),
+                inspector_ui_system,
+                console_ui_system,
+                audio::audio_feedback_system,
+            )
+                .run_if(in_state(AppState::InGame)),
         );
     }
 }
@@ -129,19 +276,41 @@ fn setup_camera(mut commands: Commands) {
     commands.spawn(Camera2dBundle::default());
 }
 
-fn initialize_game(mut commands: Commands, mut map: ResMut<MapData>) {
-    // Generate demo map
-    oldtimes_core::map::generate_demo_map(&mut map);
+/// Resumes the latest crash-autosave before falling back to the demo map, so
+/// the periodic `autosave_system` actually protects a run instead of just
+/// writing files nothing ever reads back. Runs as an exclusive system since
+/// `resume_latest` needs the whole `World` to deserialize into.
+fn initialize_game(world: &mut World) {
+    let autosave_dir = world
+        .resource::<oldtimes_core::save::AutosaveConfig>()
+        .directory
+        .clone();
+    match oldtimes_core::save::resume_latest(world, &autosave_dir) {
+        Ok(true) => {
+            log::info!("Resumed simulation state from the latest autosave in {}", autosave_dir);
+            return;
+        }
+        Ok(false) => {}
+        Err(err) => log::warn!("Failed to resume autosave from {}: {}", autosave_dir, err),
+    }
+
+    // No resumable autosave - generate demo map
+    let (width, height) = {
+        let mut map = world.resource_mut::<MapData>();
+        oldtimes_core::map::generate_demo_map(&mut map);
+        (map.width, map.height)
+    };
+    *world.resource_mut::<Visibility>() = Visibility::new(width, height);
 
     // Spawn some initial entities for demo
-    commands.spawn((
+    world.spawn((
         Position::new(15, 15),
         Building::new("lumberjack".to_string(), 2),
         Stockpile::new(20),
         Blocked,
     ));
 
-    commands.spawn((
+    world.spawn((
         Position::new(25, 25),
         Building::new("sawmill".to_string(), 3),
         Stockpile::new(30),
@@ -200,18 +369,8 @@ fn game_speed_control_system(
     mut game_speed: ResMut<GameSpeed>,
     mut tick: ResMut<GameTick>,
 ) {
-    if keyboard.just_pressed(KeyCode::Space) {
-        game_speed.paused = !game_speed.paused;
-        log::info!(
-            "Game {}",
-            if game_speed.paused {
-                "paused"
-            } else {
-                "resumed"
-            }
-        );
-    }
-
+    // Pausing is now handled by Escape via `pause_toggle_system`, which moves
+    // the whole app into `AppState::Paused` instead of just freezing the tick.
     if keyboard.just_pressed(KeyCode::Digit1) {
         game_speed.speed_multiplier = 1.0;
         tick.target_tps = 20;
@@ -0,0 +1,281 @@
+use crate::state::AppState;
+use bevy::{app::AppExit, prelude::*};
+use oldtimes_core::events::{LoadGameEvent, SaveGameEvent};
+
+const QUICKSAVE_FILENAME: &str = "quicksave.ron";
+
+const NORMAL_BUTTON: Color = Color::srgb(0.2, 0.2, 0.2);
+const HOVERED_BUTTON: Color = Color::srgb(0.3, 0.3, 0.3);
+
+#[derive(Component)]
+struct MainMenuUI;
+
+#[derive(Component)]
+struct PauseMenuUI;
+
+#[derive(Component)]
+struct GameOverUI;
+
+#[derive(Component)]
+enum MainMenuButton {
+    Play,
+}
+
+#[derive(Component)]
+enum PauseMenuButton {
+    Resume,
+    Save,
+    Load,
+    Quit,
+}
+
+#[derive(Component)]
+enum GameOverButton {
+    MainMenu,
+}
+
+fn spawn_menu_button(parent: &mut ChildBuilder, label: &str, marker: impl Component) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(200.0),
+                    height: Val::Px(50.0),
+                    margin: UiRect::all(Val::Px(8.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: NORMAL_BUTTON.into(),
+                ..default()
+            },
+            marker,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+        });
+}
+
+/// Lightens any button's background while hovered or pressed.
+pub fn button_hover_system(
+    mut buttons: Query<(&Interaction, &mut BackgroundColor), Changed<Interaction>>,
+) {
+    for (interaction, mut color) in &mut buttons {
+        *color = match interaction {
+            Interaction::Hovered | Interaction::Pressed => HOVERED_BUTTON.into(),
+            Interaction::None => NORMAL_BUTTON.into(),
+        };
+    }
+}
+
+fn menu_root_style() -> Style {
+    Style {
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        flex_direction: FlexDirection::Column,
+        ..default()
+    }
+}
+
+pub fn setup_main_menu(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: menu_root_style(),
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.85).into(),
+                ..default()
+            },
+            MainMenuUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Old Times",
+                TextStyle {
+                    font_size: 40.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+            spawn_menu_button(parent, "Play", MainMenuButton::Play);
+        });
+}
+
+pub fn teardown_main_menu(mut commands: Commands, query: Query<Entity, With<MainMenuUI>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn main_menu_button_system(
+    buttons: Query<(&Interaction, &MainMenuButton), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, button) in &buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            MainMenuButton::Play => next_state.set(AppState::Loading),
+        }
+    }
+}
+
+pub fn setup_pause_menu(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: menu_root_style(),
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.85).into(),
+                ..default()
+            },
+            PauseMenuUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Paused",
+                TextStyle {
+                    font_size: 32.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+            spawn_menu_button(parent, "Resume", PauseMenuButton::Resume);
+            spawn_menu_button(parent, "Save", PauseMenuButton::Save);
+            spawn_menu_button(parent, "Load", PauseMenuButton::Load);
+            spawn_menu_button(parent, "Quit", PauseMenuButton::Quit);
+        });
+}
+
+pub fn teardown_pause_menu(mut commands: Commands, query: Query<Entity, With<PauseMenuUI>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn pause_menu_button_system(
+    buttons: Query<(&Interaction, &PauseMenuButton), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut save_events: EventWriter<SaveGameEvent>,
+    mut load_events: EventWriter<LoadGameEvent>,
+    mut app_exit: EventWriter<AppExit>,
+) {
+    for (interaction, button) in &buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            PauseMenuButton::Resume => next_state.set(AppState::InGame),
+            PauseMenuButton::Save => {
+                save_events.send(SaveGameEvent {
+                    filename: QUICKSAVE_FILENAME.to_string(),
+                });
+            }
+            PauseMenuButton::Load => {
+                load_events.send(LoadGameEvent {
+                    filename: QUICKSAVE_FILENAME.to_string(),
+                });
+            }
+            PauseMenuButton::Quit => {
+                app_exit.send(AppExit::Success);
+            }
+        }
+    }
+}
+
+/// Toggles between `InGame` and `Paused` when Escape is pressed during gameplay.
+pub fn pause_toggle_system(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if !keyboard.just_pressed(KeyCode::Escape) {
+        return;
+    }
+
+    match state.get() {
+        AppState::InGame => next_state.set(AppState::Paused),
+        AppState::Paused => next_state.set(AppState::InGame),
+        _ => {}
+    }
+}
+
+pub fn setup_game_over_screen(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: menu_root_style(),
+                background_color: Color::srgba(0.0, 0.0, 0.0, 0.85).into(),
+                ..default()
+            },
+            GameOverUI,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Game Over",
+                TextStyle {
+                    font_size: 36.0,
+                    color: Color::srgb(0.8, 0.2, 0.2),
+                    ..default()
+                },
+            ));
+            spawn_menu_button(parent, "Main Menu", GameOverButton::MainMenu);
+        });
+}
+
+pub fn teardown_game_over_screen(mut commands: Commands, query: Query<Entity, With<GameOverUI>>) {
+    for entity in &query {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub fn game_over_button_system(
+    buttons: Query<(&Interaction, &GameOverButton), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, button) in &buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            GameOverButton::MainMenu => next_state.set(AppState::MainMenu),
+        }
+    }
+}
+
+/// Drains `SaveGameEvent`/`LoadGameEvent` and applies them straight to the
+/// live `World`, since saving/loading needs `&mut World` access that a
+/// normal system can't get for arbitrary resources and entities.
+pub fn handle_save_load_events_system(world: &mut World) {
+    let save_requests: Vec<SaveGameEvent> = world
+        .resource_mut::<Events<SaveGameEvent>>()
+        .drain()
+        .collect();
+    for event in save_requests {
+        if let Err(err) = oldtimes_core::save::save_game_state(world, &event.filename) {
+            log::error!("Failed to save game to {}: {err}", event.filename);
+        } else {
+            log::info!("Saved game to {}", event.filename);
+        }
+    }
+
+    let load_requests: Vec<LoadGameEvent> = world
+        .resource_mut::<Events<LoadGameEvent>>()
+        .drain()
+        .collect();
+    for event in load_requests {
+        if let Err(err) = oldtimes_core::save::load_game_state(world, &event.filename) {
+            log::error!("Failed to load game from {}: {err}", event.filename);
+        } else {
+            log::info!("Loaded game from {}", event.filename);
+        }
+    }
+}
@@ -1,12 +1,12 @@
 use bevy::prelude::*;
 use oldtimes_core::{
-    assets::SpriteMetadataResource,
-    components::{Building, Position},
+    assets::{SpriteMetadata, SpriteMetadataResource},
+    components::{Building, Position, Stockpile},
     events::PlaceBuildingEvent,
-    resources::{GameConfig, MapData},
+    resources::{GameConfig, MapData, SpatialIndex},
 };
 
-use super::{camera::CursorWorldPos, map::map_coords};
+use super::{camera::CursorWorldPos, economy::GlobalResources, map::map_coords};
 
 /// Plugin to handle all building-related player interactions.
 pub struct BuildModePlugin;
@@ -14,13 +14,17 @@ pub struct BuildModePlugin;
 impl Plugin for BuildModePlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<BuildingPlacer>();
+        app.init_resource::<BuildQueue>();
 
         app.add_systems(
             Update,
             (handle_building_selection, place_building_on_click)
                 .in_set(crate::GameSystemSet::Input),
         );
-        app.add_systems(Update, ghost_manager.in_set(crate::GameSystemSet::Client));
+        app.add_systems(
+            Update,
+            (ghost_manager, process_build_queue_system).in_set(crate::GameSystemSet::Client),
+        );
         app.add_systems(
             Update,
             render_placed_buildings.in_set(crate::GameSystemSet::Render),
@@ -38,6 +42,35 @@ pub struct BuildingPlacer {
 #[derive(Component)]
 struct Ghost;
 
+/// Why a prospective placement can or can't happen right now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlacementStatus {
+    /// Terrain, bounds and collisions are clear, prerequisites are built and
+    /// the cost is affordable - the click can place the building immediately.
+    Valid,
+    /// Out of bounds, wrong tile type, or overlaps another building.
+    InvalidTerrain,
+    /// Terrain is fine, but a required building type hasn't been built yet.
+    BlockedByPrerequisite,
+    /// Terrain and prerequisites are fine, but the player can't afford it.
+    BlockedByCost,
+}
+
+/// A placement the player asked for that couldn't happen immediately because
+/// it was only blocked by prerequisites or cost (never by terrain) - retried
+/// every tick by `process_build_queue_system` until it can go through.
+struct QueuedPlacement {
+    building_type: String,
+    position: Position,
+}
+
+/// Placements waiting on missing prerequisites or resources to auto-place
+/// once the blocker clears.
+#[derive(Resource, Default)]
+pub struct BuildQueue {
+    queued: Vec<QueuedPlacement>,
+}
+
 /// Handles keyboard input for selecting which building to place.
 fn handle_building_selection(mut placer: ResMut<BuildingPlacer>, input: Res<ButtonInput<KeyCode>>) {
     if input.just_pressed(KeyCode::KeyQ) {
@@ -66,8 +99,11 @@ fn ghost_manager(
     game_config: Res<GameConfig>,
     asset_server: Res<AssetServer>,
     metadata: Res<SpriteMetadataResource>,
+    sprite_metadata_assets: Res<Assets<SpriteMetadata>>,
     mut ghost_query: Query<(Entity, &mut Transform, &mut Sprite), With<Ghost>>,
-    building_query: Query<(&Position, &Building)>,
+    spatial_index: Res<SpatialIndex>,
+    buildings: Query<&Building>,
+    global_resources: Res<GlobalResources>,
 ) {
     // If nothing is selected, despawn any existing ghost and return.
     if placer.kind.is_none() {
@@ -89,25 +125,30 @@ fn ghost_manager(
     let world_pos = map_coords::grid_to_world(grid_pos.x, grid_pos.y);
 
     // Check for placement validity.
-    let is_valid = check_placement_validity(
+    let status = evaluate_placement(
         &map_data,
         grid_pos,
         footprint,
-        &building_query,
+        &spatial_index,
+        building_kind,
         &game_config,
+        &buildings,
+        &global_resources,
     );
 
     // If a ghost exists, update it. Otherwise, spawn one.
     if let Ok((_, mut transform, mut sprite)) = ghost_query.get_single_mut() {
         transform.translation.x = world_pos.x;
         transform.translation.y = world_pos.y;
-        sprite.color = if is_valid {
-            Color::srgba(0.0, 1.0, 0.0, 0.5)
-        } else {
-            Color::srgba(1.0, 0.0, 0.0, 0.5)
+        sprite.color = match status {
+            PlacementStatus::Valid => Color::srgba(0.0, 1.0, 0.0, 0.5),
+            PlacementStatus::BlockedByPrerequisite | PlacementStatus::BlockedByCost => {
+                Color::srgba(1.0, 1.0, 0.0, 0.5)
+            }
+            PlacementStatus::InvalidTerrain => Color::srgba(1.0, 0.0, 0.0, 0.5),
         };
     } else {
-        if let Some(building_meta) = metadata.get_building(building_kind) {
+        if let Some(building_meta) = metadata.get_building(&sprite_metadata_assets, building_kind) {
             if let Some(path) = &building_meta.source {
                 commands.spawn((
                     SpriteBundle {
@@ -126,7 +167,10 @@ fn ghost_manager(
     }
 }
 
-/// Handles placing a building on left mouse click.
+/// Handles placing a building on left mouse click. A `Valid` click places
+/// immediately and pays its cost; a click only blocked by prerequisites or
+/// cost is queued instead of being silently dropped, so it auto-places once
+/// `process_build_queue_system` sees the blocker clear.
 fn place_building_on_click(
     placer: Res<BuildingPlacer>,
     cursor_pos: Res<CursorWorldPos>,
@@ -134,7 +178,11 @@ fn place_building_on_click(
     map_data: Res<MapData>,
     game_config: Res<GameConfig>,
     mut event_writer: EventWriter<PlaceBuildingEvent>,
-    building_query: Query<(&Position, &Building)>,
+    spatial_index: Res<SpatialIndex>,
+    buildings: Query<&Building>,
+    global_resources: Res<GlobalResources>,
+    mut stockpiles: Query<&mut Stockpile>,
+    mut build_queue: ResMut<BuildQueue>,
 ) {
     if mouse.just_pressed(MouseButton::Left) {
         if let Some(kind) = &placer.kind {
@@ -143,22 +191,101 @@ fn place_building_on_click(
                 .buildings
                 .get(kind)
                 .map_or((1, 1), |b| (b.size.0, b.size.1));
+            let position = Position::new(grid_pos.x, grid_pos.y);
 
-            if check_placement_validity(
+            let status = evaluate_placement(
                 &map_data,
                 grid_pos,
                 footprint,
-                &building_query,
+                &spatial_index,
+                kind,
                 &game_config,
-            ) {
+                &buildings,
+                &global_resources,
+            );
+
+            match status {
+                PlacementStatus::Valid => {
+                    if let Some(building_config) = game_config.buildings.get(kind) {
+                        deduct_cost(&building_config.construction_cost, &mut stockpiles);
+                    }
+                    event_writer.send(PlaceBuildingEvent {
+                        building_type: kind.clone(),
+                        position,
+                    });
+                    log::info!("Sent PlaceBuildingEvent for {} at {:?}", kind, grid_pos);
+                }
+                PlacementStatus::BlockedByPrerequisite | PlacementStatus::BlockedByCost => {
+                    log::info!("Queued {} at {:?}: {:?}", kind, grid_pos, status);
+                    build_queue.queued.push(QueuedPlacement {
+                        building_type: kind.clone(),
+                        position,
+                    });
+                }
+                PlacementStatus::InvalidTerrain => {}
+            }
+        }
+    }
+}
+
+/// Retries queued placements every tick, auto-placing (and paying for) any
+/// that have become `Valid` since they were queued. A queued placement whose
+/// terrain has since become invalid (e.g. another building placed on top) is
+/// dropped rather than retried forever.
+fn process_build_queue_system(
+    mut build_queue: ResMut<BuildQueue>,
+    map_data: Res<MapData>,
+    game_config: Res<GameConfig>,
+    spatial_index: Res<SpatialIndex>,
+    buildings: Query<&Building>,
+    global_resources: Res<GlobalResources>,
+    mut stockpiles: Query<&mut Stockpile>,
+    mut event_writer: EventWriter<PlaceBuildingEvent>,
+) {
+    let pending = std::mem::take(&mut build_queue.queued);
+
+    for queued in pending {
+        let grid_pos = IVec2::new(queued.position.x, queued.position.y);
+        let footprint = game_config
+            .buildings
+            .get(&queued.building_type)
+            .map_or((1, 1), |b| (b.size.0, b.size.1));
+
+        let status = evaluate_placement(
+            &map_data,
+            grid_pos,
+            footprint,
+            &spatial_index,
+            &queued.building_type,
+            &game_config,
+            &buildings,
+            &global_resources,
+        );
+
+        match status {
+            PlacementStatus::Valid => {
+                if let Some(building_config) = game_config.buildings.get(&queued.building_type) {
+                    deduct_cost(&building_config.construction_cost, &mut stockpiles);
+                }
+                log::info!(
+                    "Auto-placing queued {} at {:?}",
+                    queued.building_type,
+                    queued.position
+                );
                 event_writer.send(PlaceBuildingEvent {
-                    building_type: kind.clone(),
-                    position: Position {
-                        x: grid_pos.x,
-                        y: grid_pos.y,
-                    },
+                    building_type: queued.building_type,
+                    position: queued.position,
                 });
-                log::info!("Sent PlaceBuildingEvent for {} at {:?}", kind, grid_pos);
+            }
+            PlacementStatus::InvalidTerrain => {
+                log::warn!(
+                    "Dropping queued {} at {:?}: terrain no longer valid",
+                    queued.building_type,
+                    queued.position
+                );
+            }
+            PlacementStatus::BlockedByPrerequisite | PlacementStatus::BlockedByCost => {
+                build_queue.queued.push(queued);
             }
         }
     }
@@ -169,10 +296,11 @@ fn render_placed_buildings(
     mut commands: Commands,
     query: Query<(Entity, &Building, &Position), Added<Building>>,
     metadata: Res<SpriteMetadataResource>,
+    sprite_metadata_assets: Res<Assets<SpriteMetadata>>,
     asset_server: Res<AssetServer>,
 ) {
     for (_, building, position) in query.iter() {
-        if let Some(building_meta) = metadata.get_building(&building.building_type) {
+        if let Some(building_meta) = metadata.get_building(&sprite_metadata_assets, &building.building_type) {
             if let Some(path) = &building_meta.source {
                 let pos = map_coords::grid_to_world(position.x, position.y);
                 commands.spawn(SpriteBundle {
@@ -190,13 +318,15 @@ fn render_placed_buildings(
     }
 }
 
-/// Helper function to check if a building can be placed.
+/// Helper function to check if a building can be placed. Only looks at the
+/// tiles the prospective footprint actually covers, via the `SpatialIndex`,
+/// instead of scanning every building entity - this runs once per ghost
+/// frame (i.e. every cursor move), so it needs to stay cheap.
 fn check_placement_validity(
     map_data: &MapData,
     grid_pos: IVec2,
     footprint: (u32, u32),
-    building_query: &Query<(&Position, &Building)>,
-    game_config: &GameConfig,
+    spatial_index: &SpatialIndex,
 ) -> bool {
     // 1. Check map bounds and tile types
     for y in 0..footprint.1 {
@@ -218,31 +348,72 @@ fn check_placement_validity(
     }
 
     // 2. Check for collision with other buildings
-    let new_building_rect = Rect::from_corners(
-        grid_pos.as_vec2(),
-        (grid_pos + IVec2::new(footprint.0 as i32, footprint.1 as i32)).as_vec2(),
-    );
+    spatial_index.footprint_is_clear(Position::new(grid_pos.x, grid_pos.y), footprint)
+}
 
-    for (p, b) in building_query.iter() {
-        let b_footprint = game_config
-            .buildings
-            .get(&b.building_type)
-            .map_or((1, 1), |bc| (bc.size.0, bc.size.1));
-        let existing_building_rect = Rect::from_corners(
-            IVec2::new(p.x, p.y).as_vec2(),
-            (IVec2::new(p.x, p.y) + IVec2::new(b_footprint.0 as i32, b_footprint.1 as i32))
-                .as_vec2(),
-        );
+/// Full placement check for a specific building type: terrain/collision via
+/// `check_placement_validity`, then the build order's prerequisites, then
+/// affordability. Terrain is checked first since it can't be waited out by
+/// `BuildQueue`, unlike the other two.
+#[allow(clippy::too_many_arguments)]
+fn evaluate_placement(
+    map_data: &MapData,
+    grid_pos: IVec2,
+    footprint: (u32, u32),
+    spatial_index: &SpatialIndex,
+    building_type: &str,
+    game_config: &GameConfig,
+    buildings: &Query<&Building>,
+    global_resources: &GlobalResources,
+) -> PlacementStatus {
+    if !check_placement_validity(map_data, grid_pos, footprint, spatial_index) {
+        return PlacementStatus::InvalidTerrain;
+    }
 
-        // Simple AABB collision check
-        if new_building_rect.min.x < existing_building_rect.max.x
-            && new_building_rect.max.x > existing_building_rect.min.x
-            && new_building_rect.min.y < existing_building_rect.max.y
-            && new_building_rect.max.y > existing_building_rect.min.y
-        {
-            return false; // Collision detected
-        }
+    let Some(building_config) = game_config.buildings.get(building_type) else {
+        return PlacementStatus::InvalidTerrain;
+    };
+
+    if !prerequisites_met(&building_config.prerequisites, buildings) {
+        return PlacementStatus::BlockedByPrerequisite;
     }
 
-    true
+    if !global_resources.can_afford(&building_config.construction_cost) {
+        return PlacementStatus::BlockedByCost;
+    }
+
+    PlacementStatus::Valid
+}
+
+/// Whether every prerequisite building type already has a constructed
+/// instance somewhere on the map.
+fn prerequisites_met(prerequisites: &[String], buildings: &Query<&Building>) -> bool {
+    prerequisites.iter().all(|required_type| {
+        buildings
+            .iter()
+            .any(|building| building.is_constructed && building.building_type == *required_type)
+    })
+}
+
+/// Removes `cost` from the player's stockpiles, spreading the deduction
+/// across however many buildings hold each item (mirrors the scan-and-pull
+/// pattern `systems::transport::resource_distribution_system` uses).
+fn deduct_cost(cost: &std::collections::HashMap<String, u32>, stockpiles: &mut Query<&mut Stockpile>) {
+    for (item, &amount) in cost {
+        let mut remaining = amount;
+        for mut stockpile in stockpiles.iter_mut() {
+            if remaining == 0 {
+                break;
+            }
+            remaining -= stockpile.remove_item(item, remaining);
+        }
+        if remaining > 0 {
+            log::warn!(
+                "Could not fully deduct {} {} for building cost (short by {})",
+                amount,
+                item,
+                remaining
+            );
+        }
+    }
 }
@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 use oldtimes_core::{
-    components::Stockpile,
+    components::{Producer, Stockpile},
     resources::GameTick,
 };
 use std::collections::HashMap;
@@ -31,6 +31,30 @@ pub struct GlobalResources {
     pub planks: i32,
     pub food: i32,
     pub stone: i32,
+    /// Lowest `Producer::productivity_factor` among actively producing
+    /// buildings, if any is below `1.0` (a power shortage somewhere).
+    /// `None` means nothing is currently throttled.
+    pub power_shortage: Option<f32>,
+}
+
+impl GlobalResources {
+    /// Current total of a single item, by name. Unrecognized items (not one
+    /// of the tracked fields above) read as `0`.
+    pub fn get(&self, item: &str) -> i32 {
+        match item {
+            "wood" => self.wood,
+            "planks" => self.planks,
+            "food" => self.food,
+            "stone" => self.stone,
+            _ => 0,
+        }
+    }
+
+    /// Whether every item in `cost` is currently available in at least the
+    /// requested amount.
+    pub fn can_afford(&self, cost: &HashMap<String, u32>) -> bool {
+        cost.iter().all(|(item, &amount)| self.get(item) >= amount as i32)
+    }
 }
 
 /// Client-side resource to control game speed and pause state.
@@ -81,6 +105,7 @@ fn game_speed_control_system(
 fn update_global_resources_system(
     mut global_resources: ResMut<GlobalResources>,
     stockpile_query: Query<&Stockpile>,
+    producer_query: Query<&Producer>,
 ) {
     let mut totals: HashMap<String, u32> = HashMap::new();
 
@@ -96,4 +121,13 @@ fn update_global_resources_system(
     global_resources.planks = totals.get("planks").copied().unwrap_or(0) as i32;
     global_resources.food = totals.get("food").copied().unwrap_or(0) as i32;
     global_resources.stone = totals.get("stone").copied().unwrap_or(0) as i32;
+
+    global_resources.power_shortage = producer_query
+        .iter()
+        .filter(|producer| producer.is_producing)
+        .map(|producer| producer.productivity_factor)
+        .filter(|&factor| factor < 1.0)
+        .fold(None, |lowest: Option<f32>, factor| {
+            Some(lowest.map_or(factor, |current| current.min(factor)))
+        });
 }
@@ -1,5 +1,9 @@
 use bevy::prelude::*;
-use oldtimes_core::{assets::SpriteMetadataResource, components::TileType, resources::MapData};
+use oldtimes_core::{
+    assets::{SpriteMetadata, SpriteMetadataResource},
+    components::TileType,
+    resources::MapData,
+};
 
 /// Plugin for map generation, rendering, and coordinate systems.
 pub struct MapPlugin;
@@ -21,6 +25,7 @@ fn spawn_map_system(
     mut commands: Commands,
     map_data: Res<MapData>,
     metadata: Res<SpriteMetadataResource>,
+    sprite_metadata_assets: Res<Assets<SpriteMetadata>>,
     asset_server: Res<AssetServer>,
 ) {
     if map_data.width == 0 || map_data.height == 0 {
@@ -42,7 +47,7 @@ fn spawn_map_system(
                 TileType::Road => "road",
             };
 
-            if let Some(tile_meta) = metadata.get_tile(tile_name) {
+            if let Some(tile_meta) = metadata.get_tile(&sprite_metadata_assets, tile_name) {
                 if let Some(path) = &tile_meta.source {
                     let pos = map_coords::grid_to_world(tx as i32, ty as i32);
                     commands.spawn((
@@ -13,6 +13,8 @@ struct PlanksText;
 struct FoodText;
 #[derive(Component)]
 struct StoneText;
+#[derive(Component)]
+struct PowerText;
 
 /// System to create the main HUD.
 fn setup_ui(mut commands: Commands) {
@@ -83,6 +85,19 @@ fn setup_ui(mut commands: Commands) {
                 ),
                 StoneText,
             ));
+
+            // Power shortage indicator (hidden until a cluster browns out)
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 20.0,
+                        color: Color::srgb(1.0, 0.6, 0.2),
+                        ..default()
+                    },
+                ),
+                PowerText,
+            ));
         });
 
     log::info!("UI setup complete.");
@@ -97,6 +112,7 @@ fn update_resource_display_combined(
         Query<&mut Text, With<PlanksText>>,
         Query<&mut Text, With<FoodText>>,
         Query<&mut Text, With<StoneText>>,
+        Query<&mut Text, With<PowerText>>,
     )>,
 ) {
     if resources.is_changed() {
@@ -104,6 +120,10 @@ fn update_resource_display_combined(
         queries.p1().single_mut().sections[0].value = format!("Planks: {}", resources.planks);
         queries.p2().single_mut().sections[0].value = format!("Food: {}", resources.food);
         queries.p3().single_mut().sections[0].value = format!("Stone: {}", resources.stone);
+        queries.p4().single_mut().sections[0].value = match resources.power_shortage {
+            Some(factor) => format!("Running at {:.0}% due to power shortage", factor * 100.0),
+            None => String::new(),
+        };
     }
 }
 
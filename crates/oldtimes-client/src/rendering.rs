@@ -1,37 +1,113 @@
+use crate::BuildingPlacer;
 use bevy::prelude::*;
-use oldtimes_core::{assets::*, components::*, resources::*};
+use indexmap::IndexMap;
+use oldtimes_core::{assets::*, components::*, grid::GridShape, resources::*};
+
+/// Which metadata table a sprite name is looked up in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetKind {
+    Tile,
+    Building,
+    Unit,
+}
 
+/// Registry of every sprite handle the game uses, built from
+/// `SpriteMetadataResource` rather than one hardcoded field per
+/// building/tile/unit. `IndexMap` keeps entries in load order so asset lists
+/// (e.g. the loading-screen progress bar) are stable across runs. Mods can
+/// add arbitrary new sprite types in `sprites.toml` without touching this
+/// struct.
 #[derive(Resource)]
 pub struct GameAssets {
-    // Building textures
-    pub lumberjack: Handle<Image>,
-    pub sawmill: Handle<Image>,
-    pub farm: Handle<Image>,
-    pub mill: Handle<Image>,
-    pub bakery: Handle<Image>,
-    pub quarry: Handle<Image>,
-
-    // Terrain textures
-    pub grass: Handle<Image>,
-    pub water: Handle<Image>,
-    pub stone: Handle<Image>,
-    pub forest: Handle<Image>,
-    pub road: Handle<Image>,
-
-    // Unit textures
-    pub worker: Handle<Image>,
+    textures: IndexMap<(AssetKind, String), Handle<Image>>,
+    /// Atlas layout for any tile/building/unit whose sprite is a grid of
+    /// variants (e.g. bitmask-indexed autotile frames) rather than one image.
+    atlas_layouts: IndexMap<(AssetKind, String), Handle<TextureAtlasLayout>>,
+    /// Frame-count/frame-time for any tile whose metadata declared an
+    /// `animation` strip, keyed by tile name.
+    tile_animations: IndexMap<String, TileAnimationInfo>,
+    /// Shown in place of any `(AssetKind, name)` that isn't in the registry,
+    /// instead of silently aliasing an unrelated sprite.
+    pub missing: Handle<Image>,
+}
+
+/// Playback parameters for an animated tile, resolved once at asset-load
+/// time from `TileAnimationMetadata` so `animate_tiles_system` doesn't need
+/// to touch `SpriteMetadata` every frame.
+#[derive(Debug, Clone, Copy)]
+pub struct TileAnimationInfo {
+    pub frame_count: usize,
+    pub frame_time: f32,
+}
+
+impl GameAssets {
+    /// Every sprite handle the game uses, for the loading-state system to
+    /// poll with `AssetServer::get_load_state`.
+    pub fn all_handles(&self) -> Vec<UntypedHandle> {
+        self.textures
+            .values()
+            .map(|handle| handle.clone().untyped())
+            .chain(std::iter::once(self.missing.clone().untyped()))
+            .collect()
+    }
+
+    /// Looks up a sprite by kind and name, falling back to the shared
+    /// "missing texture" placeholder when the name isn't registered.
+    pub fn get(&self, kind: AssetKind, name: &str) -> Handle<Image> {
+        self.textures
+            .get(&(kind, name.to_string()))
+            .cloned()
+            .unwrap_or_else(|| self.missing.clone())
+    }
+
+    /// Atlas layout for an autotiled sprite, if its metadata declared one.
+    pub fn atlas_layout(&self, kind: AssetKind, name: &str) -> Option<Handle<TextureAtlasLayout>> {
+        self.atlas_layouts.get(&(kind, name.to_string())).cloned()
+    }
+
+    /// Animation playback parameters for a tile, if its metadata declared an
+    /// `animation` strip.
+    pub fn tile_animation(&self, name: &str) -> Option<TileAnimationInfo> {
+        self.tile_animations.get(name).copied()
+    }
 }
 
-const TILE_SIZE: f32 = 32.0;
+pub(crate) const TILE_SIZE: f32 = 32.0;
+
+/// Per-grid-step depth increment used by `grid_to_depth`. Kept well under
+/// 1.0 (the gap between the layer bands below) so even the largest realistic
+/// map's `tx + ty` spread can't push one band's sprites into the next one's
+/// depth range.
+const LAYER_STEP: f32 = 0.001;
+/// Depth band for terrain tiles, drawn behind everything else.
+const TERRAIN_LAYER: f32 = 0.0;
+/// Depth band for buildings, drawn above terrain.
+const BUILDING_LAYER: f32 = 1.0;
+/// Depth band for units, drawn above buildings.
+const UNIT_LAYER: f32 = 2.0;
+
+/// Depth (sprite z) for a sprite at grid position `(tx, ty)` on `layer`.
+/// Sprites farther "back" on the grid (smaller `tx + ty`) get a smaller z
+/// than ones in front, so overlapping sprites within the same layer band
+/// occlude each other in the right order (painter's algorithm); `layer`
+/// keeps terrain/buildings/units in their own non-overlapping bands
+/// regardless of how far `LAYER_STEP` pushes a sprite within its band.
+pub(crate) fn grid_to_depth(tx: i32, ty: i32, layer: f32) -> f32 {
+    layer + (tx + ty) as f32 * LAYER_STEP
+}
 
 pub fn load_game_assets(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
     sprite_metadata: Option<Res<SpriteMetadataResource>>,
+    sprite_metadata_assets: Res<Assets<SpriteMetadata>>,
 ) {
-    // Load assets using metadata if available, otherwise use hardcoded paths
+    // Load assets using metadata if it's already finished loading, otherwise
+    // fall back to hardcoded paths; `refresh_game_assets_from_metadata_system`
+    // swaps these in for the metadata-driven set once the async load completes.
     let assets = if let Some(metadata) = sprite_metadata {
-        load_assets_from_metadata(&asset_server, &metadata)
+        load_assets_from_metadata(&asset_server, atlas_layouts, &metadata, &sprite_metadata_assets)
     } else {
         load_assets_fallback(&asset_server)
     };
@@ -40,147 +116,206 @@ pub fn load_game_assets(
     log::info!("Game assets loaded");
 }
 
+/// Rebuilds `GameAssets` from the sprite metadata asset whenever it finishes
+/// its initial load or is hot-reloaded, so the metadata-driven texture list
+/// replaces the Startup-time fallback as soon as it's actually available.
+pub fn refresh_game_assets_from_metadata_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    sprite_metadata: Option<Res<SpriteMetadataResource>>,
+    sprite_metadata_assets: Res<Assets<SpriteMetadata>>,
+    mut events: EventReader<AssetEvent<SpriteMetadata>>,
+) {
+    let Some(sprite_metadata) = sprite_metadata else {
+        return;
+    };
+
+    for event in events.read() {
+        let id = match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => *id,
+            _ => continue,
+        };
+
+        if id != sprite_metadata.handle.id() {
+            continue;
+        }
+
+        commands.insert_resource(load_assets_from_metadata(
+            &asset_server,
+            atlas_layouts,
+            &sprite_metadata,
+            &sprite_metadata_assets,
+        ));
+        log::info!("Refreshed game assets from sprite metadata");
+        return;
+    }
+}
+
 fn load_assets_from_metadata(
     asset_server: &AssetServer,
+    mut atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
     metadata: &SpriteMetadataResource,
+    sprite_metadata_assets: &Assets<SpriteMetadata>,
 ) -> GameAssets {
     log::info!("Loading assets using sprite metadata");
 
-    GameAssets {
-        // Building textures - use metadata or fallback
-        lumberjack: load_sprite_with_fallback(
-            asset_server,
-            metadata,
-            "building",
-            "lumberjack",
-            "sprites/lumberjack.png",
-        ),
-        sawmill: load_sprite_with_fallback(
-            asset_server,
-            metadata,
-            "building",
-            "sawmill",
-            "sprites/sawmill.png",
-        ),
-        farm: load_sprite_with_fallback(
-            asset_server,
-            metadata,
-            "building",
-            "farm",
-            "sprites/farm.png",
-        ),
-        mill: load_sprite_with_fallback(
-            asset_server,
-            metadata,
-            "building",
-            "mill",
-            "sprites/mill.png",
-        ),
-        bakery: load_sprite_with_fallback(
-            asset_server,
-            metadata,
-            "building",
-            "bakery",
-            "sprites/bakery.png",
-        ),
-        quarry: load_sprite_with_fallback(
-            asset_server,
-            metadata,
-            "building",
-            "quarry",
-            "sprites/quarry.png",
-        ),
+    let mut textures = IndexMap::new();
+    let mut layouts = IndexMap::new();
+    let mut tile_animations = IndexMap::new();
 
-        // Terrain textures - use metadata or fallback
-        grass: load_sprite_with_fallback(
-            asset_server,
-            metadata,
-            "tile",
-            "grass",
-            "sprites/grass.png",
-        ),
-        water: load_sprite_with_fallback(
-            asset_server,
-            metadata,
-            "tile",
-            "water",
-            "sprites/water.png",
-        ),
-        stone: load_sprite_with_fallback(
-            asset_server,
-            metadata,
-            "tile",
-            "stone",
-            "sprites/stone.png",
-        ),
-        forest: load_sprite_with_fallback(
-            asset_server,
-            metadata,
-            "tile",
-            "forest",
-            "sprites/forest.png",
-        ),
-        road: load_sprite_with_fallback(asset_server, metadata, "tile", "road", "sprites/road.png"),
-
-        // Unit textures - use metadata or fallback
-        worker: load_sprite_with_fallback(
-            asset_server,
-            metadata,
-            "unit",
-            "worker",
-            "sprites/worker.png",
-        ),
+    let Some(loaded) = sprite_metadata_assets.get(&metadata.handle) else {
+        return load_assets_fallback(asset_server);
+    };
+
+    // The metadata tables are `HashMap`s, so walk them in name order to keep
+    // the `IndexMap` insertion order the same from run to run.
+    let mut tiles: Vec<_> = loaded.tiles.iter().collect();
+    tiles.sort_by_key(|(name, _)| *name);
+    let mut buildings: Vec<_> = loaded.buildings.iter().collect();
+    buildings.sort_by_key(|(name, _)| *name);
+    let mut units: Vec<_> = loaded.units.iter().collect();
+    units.sort_by_key(|(name, _)| *name);
+
+    for (name, tile) in tiles {
+        let path = tile.source.clone().unwrap_or_else(|| format!("sprites/{name}.png"));
+        textures.insert((AssetKind::Tile, name.clone()), asset_server.load(path));
+
+        if let Some(animation) = &tile.animation {
+            let layout = TextureAtlasLayout::from_grid(
+                Vec2::new(animation.tile_size[0] as f32, animation.tile_size[1] as f32),
+                animation.frame_count as usize,
+                1,
+                None,
+                None,
+            );
+            layouts.insert((AssetKind::Tile, name.clone()), atlas_layouts.add(layout));
+            tile_animations.insert(
+                name.clone(),
+                TileAnimationInfo {
+                    frame_count: animation.frame_count as usize,
+                    frame_time: animation.frame_time,
+                },
+            );
+        } else if let Some(atlas) = &tile.atlas {
+            let layout = TextureAtlasLayout::from_grid(
+                Vec2::new(atlas.tile_size[0] as f32, atlas.tile_size[1] as f32),
+                atlas.columns as usize,
+                atlas.rows as usize,
+                None,
+                None,
+            );
+            layouts.insert((AssetKind::Tile, name.clone()), atlas_layouts.add(layout));
+        }
+    }
+    for (name, building) in buildings {
+        let path = building
+            .source
+            .clone()
+            .unwrap_or_else(|| format!("sprites/{name}.png"));
+        textures.insert((AssetKind::Building, name.clone()), asset_server.load(path));
+    }
+    for (name, unit) in units {
+        let path = unit.source.clone().unwrap_or_else(|| format!("sprites/{name}.png"));
+        textures.insert((AssetKind::Unit, name.clone()), asset_server.load(path));
+    }
+
+    GameAssets {
+        textures,
+        atlas_layouts: layouts,
+        tile_animations,
+        missing: asset_server.load("sprites/_missing.png"),
     }
 }
 
 fn load_assets_fallback(asset_server: &AssetServer) -> GameAssets {
     log::info!("Loading assets using fallback hardcoded paths");
 
+    let mut textures = IndexMap::new();
+    for name in ["lumberjack", "sawmill", "farm", "mill", "bakery", "quarry"] {
+        textures.insert(
+            (AssetKind::Building, name.to_string()),
+            asset_server.load(format!("sprites/{name}.png")),
+        );
+    }
+    for name in ["grass", "water", "stone", "forest", "road"] {
+        textures.insert(
+            (AssetKind::Tile, name.to_string()),
+            asset_server.load(format!("sprites/{name}.png")),
+        );
+    }
+    textures.insert(
+        (AssetKind::Unit, "worker".to_string()),
+        asset_server.load("sprites/worker.png"),
+    );
+
     GameAssets {
-        // Building textures
-        lumberjack: asset_server.load("sprites/lumberjack.png"),
-        sawmill: asset_server.load("sprites/sawmill.png"),
-        farm: asset_server.load("sprites/farm.png"),
-        mill: asset_server.load("sprites/mill.png"),
-        bakery: asset_server.load("sprites/bakery.png"),
-        quarry: asset_server.load("sprites/quarry.png"),
-
-        // Terrain textures
-        grass: asset_server.load("sprites/grass.png"),
-        water: asset_server.load("sprites/water.png"),
-        stone: asset_server.load("sprites/stone.png"),
-        forest: asset_server.load("sprites/forest.png"),
-        road: asset_server.load("sprites/road.png"),
-
-        // Unit textures
-        worker: asset_server.load("sprites/worker.png"),
+        textures,
+        atlas_layouts: IndexMap::new(),
+        tile_animations: IndexMap::new(),
+        missing: asset_server.load("sprites/_missing.png"),
     }
 }
 
-fn load_sprite_with_fallback(
-    asset_server: &AssetServer,
-    metadata: &SpriteMetadataResource,
-    sprite_type: &str,
-    name: &str,
-    fallback_path: &str,
-) -> Handle<Image> {
-    if let Some(path) = get_sprite_path_from_metadata(metadata, sprite_type, name) {
-        log::debug!("Loading {} {} from metadata: {}", sprite_type, name, path);
-        asset_server.load(path)
-    } else {
-        log::debug!(
-            "Using fallback path for {} {}: {}",
-            sprite_type,
-            name,
-            fallback_path
-        );
-        asset_server.load(fallback_path.to_string())
+/// Orthogonal neighbor offsets in bitmask order: bit 0 = north, 1 = east,
+/// 2 = south, 3 = west.
+const AUTOTILE_NEIGHBOR_OFFSETS: [(i32, i32); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+
+/// Computes the 4-bit neighbor mask used to index a tile's autotile sprite
+/// sheet: each bit is set when the neighbor in that direction satisfies
+/// `connects` (same connective type for roads, "is grass" for the blended
+/// edges of water/forest patches).
+fn autotile_mask(map: &MapData, x: i32, y: i32, connects: impl Fn(TileType) -> bool) -> u8 {
+    let mut mask = 0u8;
+    for (i, (dx, dy)) in AUTOTILE_NEIGHBOR_OFFSETS.iter().enumerate() {
+        let connected = map
+            .get_tile(x + dx, y + dy)
+            .map(|tile| connects(tile.tile_type))
+            .unwrap_or(false);
+        if connected {
+            mask |= 1 << i;
+        }
+    }
+    mask
+}
+
+/// Neighbor mask for a tile's autotile variant, per the connectivity rule
+/// for its type: roads connect to other roads or to an adjacent building
+/// (so a road reaching a building's door still looks connected), while
+/// water and forest patches blend their edge into neighboring grass.
+fn autotile_mask_for(
+    map: &MapData,
+    building_positions: &std::collections::HashSet<Position>,
+    position: Position,
+    tile_type: TileType,
+) -> u8 {
+    match tile_type {
+        TileType::Road => {
+            let mut mask = 0u8;
+            for (i, (dx, dy)) in AUTOTILE_NEIGHBOR_OFFSETS.iter().enumerate() {
+                let neighbor = Position::new(position.x + dx, position.y + dy);
+                let connects = map
+                    .get_tile(neighbor.x, neighbor.y)
+                    .map(|tile| tile.tile_type == TileType::Road)
+                    .unwrap_or(false)
+                    || building_positions.contains(&neighbor);
+                if connects {
+                    mask |= 1 << i;
+                }
+            }
+            mask
+        }
+        TileType::Water | TileType::Forest => {
+            autotile_mask(map, position.x, position.y, |t| t == TileType::Grass)
+        }
+        TileType::Grass | TileType::Stone => 0,
     }
 }
 
 pub fn render_map_system(
     mut commands: Commands,
     map: Res<MapData>,
+    buildings: Query<&Position, With<Building>>,
     existing_tiles: Query<Entity, With<TileRenderer>>,
     assets: Option<Res<GameAssets>>,
 ) {
@@ -195,30 +330,86 @@ pub fn render_map_system(
             commands.entity(entity).despawn();
         }
 
+        let building_positions: std::collections::HashSet<Position> =
+            buildings.iter().copied().collect();
+
         // Render new map
         for y in 0..map.height {
             for x in 0..map.width {
                 if let Some(tile) = map.get_tile(x as i32, y as i32) {
                     let texture = get_tile_texture(&assets, &tile.tile_type);
+                    let position = Position::new(x as i32, y as i32);
 
-                    commands.spawn((
+                    let mut entity_commands = commands.spawn((
                         SpriteBundle {
                             texture: texture.clone(),
                             transform: Transform::from_xyz(
                                 x as f32 * TILE_SIZE,
                                 y as f32 * TILE_SIZE,
-                                0.0,
+                                grid_to_depth(x as i32, y as i32, TERRAIN_LAYER),
                             ),
                             ..default()
                         },
-                        TileRenderer,
+                        TileRenderer { position },
                     ));
+
+                    let tile_name = tile_type_name(&tile.tile_type);
+                    if let Some(animation) = assets.tile_animation(tile_name) {
+                        if let Some(layout) = assets.atlas_layout(AssetKind::Tile, tile_name) {
+                            entity_commands.insert(TextureAtlas { layout, index: 0 });
+                        }
+                        entity_commands.insert(AnimatedTile::new(
+                            animation.frame_count,
+                            animation.frame_time,
+                        ));
+                    } else if let Some(layout) = assets.atlas_layout(AssetKind::Tile, tile_name) {
+                        let index =
+                            autotile_mask_for(&map, &building_positions, position, tile.tile_type)
+                                as usize;
+                        entity_commands.insert(TextureAtlas { layout, index });
+                    }
                 }
             }
         }
     }
 }
 
+/// Advances each animated tile's frame timer, stepping `TextureAtlas::index`
+/// forward and wrapping back to 0 after the last frame. Keeps non-animated
+/// terrain (the overwhelming majority of tiles) on the static single-texture
+/// path in `render_map_system` with no per-frame cost here at all, since this
+/// query only ever matches tiles that actually carry an `AnimatedTile`.
+pub fn animate_tiles_system(time: Res<Time>, mut tiles: Query<(&mut AnimatedTile, &mut TextureAtlas)>) {
+    for (mut animated, mut atlas) in tiles.iter_mut() {
+        animated.timer += time.delta_seconds();
+        if animated.timer >= animated.frame_time {
+            animated.timer -= animated.frame_time;
+            atlas.index = (atlas.index + 1) % animated.frame_count.max(1);
+        }
+    }
+}
+
+/// Tints each tile sprite by its fog-of-war state: `Visible` tiles render at
+/// full brightness, `Explored` tiles are dimmed to show remembered terrain,
+/// and `Unexplored` tiles are hidden entirely.
+pub fn update_fog_of_war_system(
+    visibility: Res<Visibility>,
+    mut tiles: Query<(&TileRenderer, &mut Sprite)>,
+) {
+    if !visibility.is_changed() {
+        return;
+    }
+
+    for (renderer, mut sprite) in tiles.iter_mut() {
+        let alpha = match visibility.state_at(renderer.position) {
+            VisibilityState::Visible => 1.0,
+            VisibilityState::Explored => 0.5,
+            VisibilityState::Unexplored => 0.0,
+        };
+        sprite.color = sprite.color.with_alpha(alpha);
+    }
+}
+
 pub fn render_buildings_system(
     mut commands: Commands,
     buildings: Query<
@@ -248,7 +439,7 @@ pub fn render_buildings_system(
                 transform: Transform::from_xyz(
                     position.x as f32 * TILE_SIZE,
                     position.y as f32 * TILE_SIZE,
-                    1.0,
+                    grid_to_depth(position.x, position.y, BUILDING_LAYER),
                 ),
                 ..default()
             },
@@ -278,11 +469,11 @@ pub fn render_workers_system(
     for (entity, position, _worker) in workers.iter() {
         commands.spawn((
             SpriteBundle {
-                texture: assets.worker.clone(),
+                texture: assets.get(AssetKind::Unit, "worker"),
                 transform: Transform::from_xyz(
                     position.x as f32 * TILE_SIZE,
                     position.y as f32 * TILE_SIZE,
-                    2.0,
+                    grid_to_depth(position.x, position.y, UNIT_LAYER),
                 ),
                 ..default()
             },
@@ -297,30 +488,106 @@ pub fn render_workers_system(
         if let Ok((_, position, _)) = workers.get(renderer.worker_entity) {
             transform.translation.x = position.x as f32 * TILE_SIZE;
             transform.translation.y = position.y as f32 * TILE_SIZE;
+            transform.translation.z = grid_to_depth(position.x, position.y, UNIT_LAYER);
         }
     }
 }
 
-fn get_tile_texture(assets: &GameAssets, tile_type: &TileType) -> Handle<Image> {
+/// Shows a one-tile highlight at the cursor's tile while a building is
+/// selected for placement, tinted green when the whole footprint can legally be built on
+/// and red when any tile in it is blocked or off the map. Checks the same
+/// `SpatialIndex` footprint test `building_placement_system` uses, rather
+/// than a single tile, so a multi-tile building previews correctly even
+/// when the cursor sits over a footprint tile that isn't another building's
+/// origin.
+pub fn update_placement_highlight_system(
+    mut commands: Commands,
+    placer: Res<BuildingPlacer>,
+    map: Res<MapData>,
+    config: Res<GameConfig>,
+    spatial_index: Res<SpatialIndex>,
+    mut existing: Query<(Entity, &mut Transform, &mut Sprite), With<PlacementHighlight>>,
+) {
+    let Some(position) = placer.preview_position else {
+        for (entity, ..) in &existing {
+            commands.entity(entity).despawn();
+        }
+        return;
+    };
+
+    let footprint_size = placer
+        .selected_building
+        .as_ref()
+        .and_then(|building_type| config.buildings.get(building_type))
+        .map_or((1, 1), |building_config| building_config.size);
+
+    let in_bounds = position.x >= 0
+        && position.y >= 0
+        && (position.x as u32 + footprint_size.0 - 1) < map.width
+        && (position.y as u32 + footprint_size.1 - 1) < map.height;
+    let is_blocked = !spatial_index.footprint_is_clear(position, footprint_size);
+    let color = if in_bounds && !is_blocked {
+        Color::srgba(0.2, 0.9, 0.2, 0.4)
+    } else {
+        Color::srgba(0.9, 0.2, 0.2, 0.4)
+    };
+
+    // A pointy-top hex is 2 * HEX_SIZE tall and sqrt(3) * HEX_SIZE wide (the
+    // column spacing `hex_grid_to_world` uses), so the outline is sized and
+    // positioned to match instead of reusing the square footprint.
+    let (translation, size) = match config.grid_shape {
+        GridShape::Square => (
+            Vec3::new(position.x as f32 * TILE_SIZE, position.y as f32 * TILE_SIZE, 1.5),
+            Vec2::splat(TILE_SIZE),
+        ),
+        GridShape::Hex => {
+            let world_pos = oldtimes_core::grid::hex_grid_to_world(position);
+            (
+                Vec3::new(world_pos.x, world_pos.y, 1.5),
+                Vec2::new(
+                    3f32.sqrt() * oldtimes_core::grid::HEX_SIZE,
+                    oldtimes_core::grid::HEX_SIZE * 2.0,
+                ),
+            )
+        }
+    };
+
+    if let Ok((_, mut transform, mut sprite)) = existing.get_single_mut() {
+        transform.translation = translation;
+        sprite.color = color;
+        sprite.custom_size = Some(size);
+    } else {
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(size),
+                    ..default()
+                },
+                transform: Transform::from_translation(translation),
+                ..default()
+            },
+            PlacementHighlight,
+        ));
+    }
+}
+
+fn tile_type_name(tile_type: &TileType) -> &'static str {
     match tile_type {
-        TileType::Grass => assets.grass.clone(),
-        TileType::Water => assets.water.clone(),
-        TileType::Stone => assets.stone.clone(),
-        TileType::Forest => assets.forest.clone(),
-        TileType::Road => assets.road.clone(),
+        TileType::Grass => "grass",
+        TileType::Water => "water",
+        TileType::Stone => "stone",
+        TileType::Forest => "forest",
+        TileType::Road => "road",
     }
 }
 
+fn get_tile_texture(assets: &GameAssets, tile_type: &TileType) -> Handle<Image> {
+    assets.get(AssetKind::Tile, tile_type_name(tile_type))
+}
+
 fn get_building_texture(assets: &GameAssets, building_type: &str) -> Handle<Image> {
-    match building_type {
-        "lumberjack" => assets.lumberjack.clone(),
-        "sawmill" => assets.sawmill.clone(),
-        "farm" => assets.farm.clone(),
-        "mill" => assets.mill.clone(),
-        "bakery" => assets.bakery.clone(),
-        "quarry" => assets.quarry.clone(),
-        _ => assets.lumberjack.clone(), // Default fallback
-    }
+    assets.get(AssetKind::Building, building_type)
 }
 
 // Keep the old color functions for fallback
@@ -348,7 +615,9 @@ fn get_building_color(building_type: &str) -> Color {
 
 // Marker components for renderers
 #[derive(Component)]
-pub struct TileRenderer;
+pub struct TileRenderer {
+    pub position: Position,
+}
 
 #[derive(Component)]
 pub struct BuildingRenderer {
@@ -359,3 +628,25 @@ pub struct BuildingRenderer {
 pub struct WorkerRenderer {
     pub worker_entity: Entity,
 }
+
+/// Drives frame-by-frame cycling for a tile whose sprite is a strip of
+/// animation frames (e.g. rippling water), ticked by `animate_tiles_system`.
+#[derive(Component, Debug, Clone)]
+pub struct AnimatedTile {
+    pub frame_count: usize,
+    pub frame_time: f32,
+    timer: f32,
+}
+
+impl AnimatedTile {
+    pub fn new(frame_count: usize, frame_time: f32) -> Self {
+        Self {
+            frame_count,
+            frame_time,
+            timer: 0.0,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct PlacementHighlight;
@@ -0,0 +1,117 @@
+use crate::rendering::GameAssets;
+use bevy::prelude::*;
+
+/// Top-level client lifecycle. `MainMenu` is the entry point; picking Play
+/// moves to `Loading`, which waits for every sprite handle in `GameAssets` to
+/// report `Loaded` before switching to `InGame`. From there Escape opens
+/// `Paused`, and a triggered loss condition would move to `GameOver`.
+#[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum AppState {
+    #[default]
+    MainMenu,
+    Loading,
+    InGame,
+    Paused,
+    GameOver,
+}
+
+#[derive(Component)]
+struct LoadingScreen;
+
+#[derive(Component)]
+struct LoadingProgressBar;
+
+pub fn setup_loading_screen(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                background_color: Color::BLACK.into(),
+                ..default()
+            },
+            LoadingScreen,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Loading...",
+                TextStyle {
+                    font_size: 24.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        width: Val::Px(300.0),
+                        height: Val::Px(20.0),
+                        margin: UiRect::top(Val::Px(10.0)),
+                        ..default()
+                    },
+                    background_color: Color::srgb(0.2, 0.2, 0.2).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(0.0),
+                                height: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: Color::srgb(0.2, 0.8, 0.2).into(),
+                            ..default()
+                        },
+                        LoadingProgressBar,
+                    ));
+                });
+        });
+}
+
+/// Polls every handle in `GameAssets` and advances the progress bar; once all
+/// of them report `Loaded`, transitions to `AppState::InGame`.
+pub fn check_assets_ready(
+    assets: Option<Res<GameAssets>>,
+    asset_server: Res<AssetServer>,
+    mut progress_bar: Query<&mut Style, With<LoadingProgressBar>>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let Some(assets) = assets else {
+        return;
+    };
+
+    let handles = assets.all_handles();
+    let loaded = handles
+        .iter()
+        .filter(|handle| {
+            matches!(
+                asset_server.get_load_state(*handle),
+                Some(bevy::asset::LoadState::Loaded)
+            )
+        })
+        .count();
+
+    if let Ok(mut style) = progress_bar.get_single_mut() {
+        let progress = loaded as f32 / handles.len().max(1) as f32;
+        style.width = Val::Percent(progress * 100.0);
+    }
+
+    if loaded == handles.len() {
+        next_state.set(AppState::InGame);
+        log::info!("All assets loaded, entering game");
+    }
+}
+
+pub fn teardown_loading_screen(mut commands: Commands, query: Query<Entity, With<LoadingScreen>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{BuildingPlacer, DebugOverlay, GameSpeed};
+use crate::{BuildingPlacer, DebugOverlay, GameSpeed, SystemDiagnostics};
 use bevy::prelude::*;
 use oldtimes_core::resources::*;
 
@@ -42,7 +42,7 @@ pub fn setup_ui(mut commands: Commands) {
             // Speed controls
             parent.spawn((
                 TextBundle::from_section(
-                    "Speed: 1x | SPACE: Pause | 1/2/4: Speed",
+                    "Speed: 1x | ESC: Pause | 1/2/4: Speed",
                     TextStyle {
                         font_size: 16.0,
                         color: Color::srgb(0.7, 0.7, 0.7),
@@ -168,7 +168,7 @@ pub fn update_ui_system(
         } else {
             &format!("{}x", game_speed.speed_multiplier)
         };
-        text.sections[0].value = format!("Speed: {} | SPACE: Pause | 1/2/4: Speed", status);
+        text.sections[0].value = format!("Speed: {} | ESC: Pause | 1/2/4: Speed", status);
     }
 
     // Update building help
@@ -189,6 +189,7 @@ pub fn update_debug_overlay_system(
     metrics: Res<PerformanceMetrics>,
     pathfinding_cache: Res<PathfindingCache>,
     tick: Res<GameTick>,
+    system_diagnostics: Res<SystemDiagnostics>,
     mut overlay_query: Query<&mut Visibility, With<DebugOverlayUI>>,
     mut content_query: Query<&mut Text, With<DebugContentText>>,
 ) {
@@ -216,6 +217,14 @@ pub fn update_debug_overlay_system(
                 for (system, time) in &metrics.system_times {
                     content.push_str(&format!("  {}: {:.2}ms\n", system, time));
                 }
+
+                content.push_str(&format!(
+                    "\nCPU: {:.0}% | RSS: {} MB | Sys Mem: {:.1}/{:.1} GB\n",
+                    system_diagnostics.cpu_usage_percent,
+                    system_diagnostics.process_memory_bytes / (1024 * 1024),
+                    system_diagnostics.system_used_memory_bytes as f64 / 1_000_000_000.0,
+                    system_diagnostics.system_total_memory_bytes as f64 / 1_000_000_000.0,
+                ));
             }
 
             if debug_overlay.show_pathfinding {
@@ -1,13 +1,18 @@
 // Asset metadata loading and management
 // This module provides structures and functions to load sprite metadata from TOML files
 
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use anyhow::{Result, Context};
+use std::path::{Path, PathBuf};
 
-/// Sprite metadata loaded from sprites.toml
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Sprite metadata loaded from sprites.toml. A real Bevy `Asset`, loaded
+/// asynchronously through `AssetServer` by `SpriteMetadataLoader` rather than
+/// read from disk on demand, so it gets dependency tracking and file-watcher
+/// driven hot-reload for free.
+#[derive(Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
 pub struct SpriteMetadata {
     pub tiles: HashMap<String, TileMetadata>,
     pub buildings: HashMap<String, BuildingMetadata>,
@@ -20,6 +25,37 @@ pub struct TileMetadata {
     pub kind: String, // Should be "tile"
     pub size: [u32; 2], // [width, height] in pixels
     pub source: Option<String>, // Path to sprite file
+    /// Present when `source` is a sprite sheet of autotile variants rather
+    /// than a single image, e.g. the 16 bitmask-indexed frames roads/water
+    /// use to connect to their neighbors.
+    #[serde(default)]
+    pub atlas: Option<AtlasGridMetadata>,
+    /// Present when the tile should cycle through a strip of frames over
+    /// time (e.g. rippling water) instead of staying on one fixed image or
+    /// autotile variant. Mutually exclusive with `atlas` in practice - a
+    /// tile is either a neighbor-connected autotile or a timed animation,
+    /// not both.
+    #[serde(default)]
+    pub animation: Option<TileAnimationMetadata>,
+}
+
+/// Describes a sprite sheet laid out as a uniform grid of same-size frames,
+/// indexed left-to-right, top-to-bottom.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AtlasGridMetadata {
+    pub tile_size: [u32; 2],
+    pub columns: u32,
+    pub rows: u32,
+}
+
+/// Describes a tile's animation frames: a single row of `frame_count`
+/// same-size frames, each shown for `frame_time` seconds before advancing to
+/// the next and wrapping back to the start.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TileAnimationMetadata {
+    pub tile_size: [u32; 2],
+    pub frame_count: u32,
+    pub frame_time: f32,
 }
 
 /// Metadata for building sprites
@@ -44,20 +80,53 @@ pub struct UnitMetadata {
     pub atlas_map: Option<String>, // Path to atlas frame map JSON
 }
 
-/// Animation frame information for atlas-based sprites
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Animation frame information for atlas-based sprites. Also a real `Asset`,
+/// loaded by `AtlasFrameMapLoader`; a unit's `atlas_map` path becomes a
+/// `Handle<AtlasFrameMap>` dependency resolved by `SpriteMetadataLoader`
+/// rather than read synchronously on demand.
+#[derive(Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
 pub struct AtlasFrameMap {
     pub frames: HashMap<String, FrameData>,
     pub meta: AtlasMetadata,
 }
 
-/// Individual frame data in an atlas
+/// Individual frame data in an atlas, in the TexturePacker-compatible
+/// representation: `x,y,w,h` is the rect actually packed into the atlas
+/// image, which a rotated or trimmed frame doesn't map onto 1:1 with the
+/// sprite's original, logical bounds.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FrameData {
     pub x: u32,
     pub y: u32,
     pub w: u32,
     pub h: u32,
+    /// When true, the rect above is stored rotated 90° clockwise to pack
+    /// tighter, so the renderer must swap w/h for the sprite's draw size and
+    /// rotate the sampled region back to display it upright.
+    #[serde(default)]
+    pub rotated: bool,
+    /// When true, the transparent border was stripped before packing, so
+    /// `sprite_source_size`/`source_size` are needed to place the trimmed
+    /// quad at its original position within the frame.
+    #[serde(default)]
+    pub trimmed: bool,
+    /// Offset and size of the visible region within the frame's original,
+    /// untrimmed bounds. Only meaningful when `trimmed` is true.
+    #[serde(default)]
+    pub sprite_source_size: Option<AtlasRect>,
+    /// The frame's full logical size before trimming.
+    #[serde(default)]
+    pub source_size: Option<AtlasSize>,
+}
+
+/// A pixel rect, used both for an atlas-packed frame's sample region and for
+/// a trimmed frame's offset within its original bounds.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct AtlasRect {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
 }
 
 /// Atlas metadata
@@ -75,157 +144,404 @@ pub struct AtlasSize {
     pub h: u32,
 }
 
-/// Resource containing loaded sprite metadata
-#[derive(Resource, Default)]
-pub struct SpriteMetadataResource {
-    pub metadata: Option<SpriteMetadata>,
-    pub atlas_maps: HashMap<String, AtlasFrameMap>,
+/// Error returned by [`SpriteMetadataLoader`] and [`AtlasFrameMapLoader`].
+#[derive(Debug)]
+pub enum MetadataLoaderError {
+    Io(std::io::Error),
+    Toml(toml::de::Error),
+    Json(serde_json::Error),
 }
 
-impl SpriteMetadataResource {
-    /// Load sprite metadata from TOML file
-    pub fn load_from_file(path: &str) -> Result<SpriteMetadata> {
-        let content = std::fs::read_to_string(path)
-            .with_context(|| format!("Failed to read sprites metadata from {}", path))?;
-        
-        let metadata: SpriteMetadata = toml::from_str(&content)
-            .with_context(|| format!("Failed to parse sprites metadata from {}", path))?;
-        
-        log::info!("Loaded sprite metadata: {} tiles, {} buildings, {} units", 
-                   metadata.tiles.len(), 
-                   metadata.buildings.len(), 
-                   metadata.units.len());
-        
+impl std::fmt::Display for MetadataLoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetadataLoaderError::Io(e) => write!(f, "failed to read asset file: {e}"),
+            MetadataLoaderError::Toml(e) => write!(f, "failed to parse TOML asset: {e}"),
+            MetadataLoaderError::Json(e) => write!(f, "failed to parse JSON asset: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for MetadataLoaderError {}
+
+impl From<std::io::Error> for MetadataLoaderError {
+    fn from(e: std::io::Error) -> Self {
+        MetadataLoaderError::Io(e)
+    }
+}
+
+impl From<toml::de::Error> for MetadataLoaderError {
+    fn from(e: toml::de::Error) -> Self {
+        MetadataLoaderError::Toml(e)
+    }
+}
+
+impl From<serde_json::Error> for MetadataLoaderError {
+    fn from(e: serde_json::Error) -> Self {
+        MetadataLoaderError::Json(e)
+    }
+}
+
+/// `AssetLoader` for `sprites.toml`. Also kicks off the load of every unit's
+/// `atlas_map`, so the resulting `SpriteMetadata` asset carries its atlas
+/// frame maps as tracked dependencies rather than leaving callers to load
+/// them separately and hope the timing works out.
+#[derive(Default)]
+pub struct SpriteMetadataLoader;
+
+impl AssetLoader for SpriteMetadataLoader {
+    type Asset = SpriteMetadata;
+    type Settings = ();
+    type Error = MetadataLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content).await?;
+        let mut metadata: SpriteMetadata = toml::from_str(&content)?;
+
+        // Every relative `source`/`atlas_map` path is declared relative to
+        // the directory its `sprites.toml` lives in, so a root loaded as a
+        // layer (e.g. a mod folder) stays self-contained rather than having
+        // its sprite files looked up next to the base game's assets.
+        let root = load_context.path().parent().map(Path::to_path_buf).unwrap_or_default();
+        resolve_sprite_metadata_paths(&mut metadata, &root);
+
+        for (name, unit) in &metadata.units {
+            if let Some(atlas_map_path) = &unit.atlas_map {
+                // Registers the atlas map as a dependency of this asset so
+                // the loader's own "loaded with dependencies" signal waits
+                // for it too.
+                let _: Handle<AtlasFrameMap> = load_context.load(atlas_map_path.as_str());
+                log::debug!("Queued atlas map load for unit {}: {}", name, atlas_map_path);
+            }
+        }
+
+        log::info!(
+            "Loaded sprite metadata from {:?}: {} tiles, {} buildings, {} units",
+            root,
+            metadata.tiles.len(),
+            metadata.buildings.len(),
+            metadata.units.len()
+        );
+
         Ok(metadata)
     }
-    
-    /// Load atlas frame map from JSON file
-    pub fn load_atlas_map(path: &str) -> Result<AtlasFrameMap> {
-        let content = std::fs::read_to_string(path)
-            .with_context(|| format!("Failed to read atlas map from {}", path))?;
-        
-        let atlas_map: AtlasFrameMap = serde_json::from_str(&content)
-            .with_context(|| format!("Failed to parse atlas map from {}", path))?;
-        
+
+    fn extensions(&self) -> &[&str] {
+        &["toml"]
+    }
+}
+
+/// `AssetLoader` for atlas frame map JSON files.
+#[derive(Default)]
+pub struct AtlasFrameMapLoader;
+
+impl AssetLoader for AtlasFrameMapLoader {
+    type Asset = AtlasFrameMap;
+    type Settings = ();
+    type Error = MetadataLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut content = String::new();
+        reader.read_to_string(&mut content).await?;
+        let atlas_map: AtlasFrameMap = serde_json::from_str(&content)?;
+
         log::debug!("Loaded atlas map with {} frames", atlas_map.frames.len());
-        
+
         Ok(atlas_map)
     }
-    
+
+    fn extensions(&self) -> &[&str] {
+        &["json"]
+    }
+}
+
+/// Ordered list of asset-relative directories to load `sprites.toml` from -
+/// base game first, mod overrides after. Mirrors the "mod overrides base,
+/// per entry key" contract `ModLoader` already uses for
+/// `buildings.toml`/`recipes.toml`/`workers.toml`, just applied to sprite
+/// metadata instead. A relative `source`/`atlas_map` path declared in a
+/// root's `sprites.toml` resolves against that same root, so a mod's sprite
+/// files stay self-contained under its own directory.
+#[derive(Resource, Clone, Debug)]
+pub struct SpriteMetadataRoots(pub Vec<PathBuf>);
+
+impl Default for SpriteMetadataRoots {
+    fn default() -> Self {
+        Self(vec![PathBuf::from("data")])
+    }
+}
+
+/// Handle to the merged sprite metadata, plus a resolved map from each
+/// unit's rewritten `atlas_map` path to the `Handle<AtlasFrameMap>` that path
+/// loads. The accessor methods take the relevant `Assets<T>` registries
+/// rather than owning the data directly, since the data itself now lives in
+/// Bevy's asset storage.
+#[derive(Resource, Default)]
+pub struct SpriteMetadataResource {
+    /// The merged, effective metadata - synthesized from `layers` once every
+    /// one of them has loaded, so it's a standalone asset with no handle of
+    /// its own on disk.
+    pub handle: Handle<SpriteMetadata>,
+    pub atlas_maps: HashMap<String, Handle<AtlasFrameMap>>,
+    /// Per-root handle, in override order. Kept around so
+    /// `handle_sprite_metadata_events_system` knows when every layer is
+    /// loaded and can re-derive `handle`'s merge.
+    layers: Vec<Handle<SpriteMetadata>>,
+}
+
+impl SpriteMetadataResource {
     /// Get tile metadata by name
-    pub fn get_tile(&self, name: &str) -> Option<&TileMetadata> {
-        self.metadata.as_ref()?.tiles.get(name)
+    pub fn get_tile<'a>(
+        &self,
+        sprite_metadata: &'a Assets<SpriteMetadata>,
+        name: &str,
+    ) -> Option<&'a TileMetadata> {
+        sprite_metadata.get(&self.handle)?.tiles.get(name)
     }
-    
+
     /// Get building metadata by name
-    pub fn get_building(&self, name: &str) -> Option<&BuildingMetadata> {
-        self.metadata.as_ref()?.buildings.get(name)
+    pub fn get_building<'a>(
+        &self,
+        sprite_metadata: &'a Assets<SpriteMetadata>,
+        name: &str,
+    ) -> Option<&'a BuildingMetadata> {
+        sprite_metadata.get(&self.handle)?.buildings.get(name)
     }
-    
+
     /// Get unit metadata by name
-    pub fn get_unit(&self, name: &str) -> Option<&UnitMetadata> {
-        self.metadata.as_ref()?.units.get(name)
+    pub fn get_unit<'a>(
+        &self,
+        sprite_metadata: &'a Assets<SpriteMetadata>,
+        name: &str,
+    ) -> Option<&'a UnitMetadata> {
+        sprite_metadata.get(&self.handle)?.units.get(name)
     }
-    
-    /// Get atlas frame map by path
-    pub fn get_atlas_map(&self, path: &str) -> Option<&AtlasFrameMap> {
-        self.atlas_maps.get(path)
+
+    /// Get atlas frame map by the path it was declared with in `sprites.toml`
+    pub fn get_atlas_map<'a>(
+        &self,
+        atlas_maps: &'a Assets<AtlasFrameMap>,
+        path: &str,
+    ) -> Option<&'a AtlasFrameMap> {
+        atlas_maps.get(self.atlas_maps.get(path)?)
     }
-    
+
     /// Get frame data for a specific animation frame
-    pub fn get_frame_data(&self, atlas_path: &str, frame_name: &str) -> Option<&FrameData> {
-        self.get_atlas_map(atlas_path)?.frames.get(frame_name)
+    pub fn get_frame_data<'a>(
+        &self,
+        atlas_maps: &'a Assets<AtlasFrameMap>,
+        atlas_path: &str,
+        frame_name: &str,
+    ) -> Option<&'a FrameData> {
+        self.get_atlas_map(atlas_maps, atlas_path)?.frames.get(frame_name)
     }
+
+    /// Resolves a frame's rotation/trim metadata into the geometry animation
+    /// code actually needs to place it: the rect to sample from the atlas
+    /// texture, whether that rect is rotated, and the frame's visible region
+    /// within its original logical bounds. An untrimmed, unrotated frame
+    /// (the common case) resolves to its own `x,y,w,h` with no offset.
+    pub fn get_resolved_frame(
+        &self,
+        atlas_maps: &Assets<AtlasFrameMap>,
+        atlas_path: &str,
+        frame_name: &str,
+    ) -> Option<ResolvedFrame> {
+        let frame = self.get_frame_data(atlas_maps, atlas_path, frame_name)?;
+        Some(ResolvedFrame {
+            atlas_rect: AtlasRect {
+                x: frame.x,
+                y: frame.y,
+                w: frame.w,
+                h: frame.h,
+            },
+            rotated: frame.rotated,
+            sprite_source_size: frame.sprite_source_size.unwrap_or(AtlasRect {
+                x: 0,
+                y: 0,
+                w: frame.w,
+                h: frame.h,
+            }),
+            source_size: frame.source_size.unwrap_or(AtlasSize {
+                w: frame.w,
+                h: frame.h,
+            }),
+        })
+    }
+}
+
+/// The computed draw geometry for one atlas frame, derived from its
+/// TexturePacker-style rotation/trim metadata by
+/// [`SpriteMetadataResource::get_resolved_frame`].
+#[derive(Debug, Clone, Copy)]
+pub struct ResolvedFrame {
+    /// Rect to sample from the atlas texture, exactly as packed - still
+    /// rotated if `rotated` is true, so the renderer must swap `w`/`h` for
+    /// the sprite's draw size and rotate the sampled region back to display
+    /// it upright.
+    pub atlas_rect: AtlasRect,
+    pub rotated: bool,
+    /// Offset and size of the frame's visible region within its original,
+    /// untrimmed bounds - `(0, 0, w, h)` when the frame isn't trimmed.
+    pub sprite_source_size: AtlasRect,
+    /// The frame's full logical size before trimming.
+    pub source_size: AtlasSize,
 }
 
-/// System to load sprite metadata on startup
+/// System to load sprite metadata on startup. Each root in
+/// `SpriteMetadataRoots` starts its own async load; the resource is inserted
+/// immediately holding those (not-yet-loaded) per-root handles, and
+/// `handle_sprite_metadata_events_system` merges them into the effective
+/// `handle` once every layer has actually finished loading.
 pub fn load_sprite_metadata_system(
     mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    roots: Option<Res<SpriteMetadataRoots>>,
 ) {
-    let metadata_resource = load_sprite_metadata_resource();
-    commands.insert_resource(metadata_resource);
+    let roots = roots.map(|r| r.0.clone()).unwrap_or_default();
+    let layers = roots
+        .into_iter()
+        .map(|root| asset_server.load(root.join("sprites.toml")))
+        .collect();
+
+    commands.insert_resource(SpriteMetadataResource {
+        handle: Handle::default(),
+        atlas_maps: HashMap::new(),
+        layers,
+    });
 }
 
-/// System to hot-reload sprite metadata during development
-pub fn hot_reload_sprite_metadata_system(
-    mut metadata_resource: ResMut<SpriteMetadataResource>,
-) {
-    // Check if sprites.toml file has been modified
-    // For now, we'll just reload on every call in debug mode
-    #[cfg(debug_assertions)]
-    {
-        if let Ok(new_metadata) = SpriteMetadataResource::load_from_file("assets/data/sprites.toml") {
-            // Only reload if the content has actually changed
-            // This is a simple implementation - in production you'd want to check file modification time
-            let mut atlas_maps = HashMap::new();
-            
-            for (name, unit) in &new_metadata.units {
-                if let Some(atlas_map_path) = &unit.atlas_map {
-                    match SpriteMetadataResource::load_atlas_map(atlas_map_path) {
-                        Ok(atlas_map) => {
-                            atlas_maps.insert(atlas_map_path.clone(), atlas_map);
-                        }
-                        Err(e) => {
-                            log::warn!("Failed to load atlas map for unit {}: {}", name, e);
-                        }
-                    }
-                }
-            }
-            
-            metadata_resource.metadata = Some(new_metadata);
-            metadata_resource.atlas_maps = atlas_maps;
-            log::debug!("Hot-reloaded sprite metadata");
-        }
+/// Resolves a `sprites.toml`-relative path against the root directory that
+/// declared it, so each layer's sprite files stay self-contained under their
+/// own directory instead of being looked up relative to the base game's
+/// assets.
+fn resolve_layer_path(root: &Path, relative: &str) -> String {
+    root.join(relative).to_string_lossy().into_owned()
+}
+
+/// Rewrites every relative `source`/`atlas_map` path in `metadata` to be
+/// rooted at `root` - the directory its `sprites.toml` file lives in.
+fn resolve_sprite_metadata_paths(metadata: &mut SpriteMetadata, root: &Path) {
+    for tile in metadata.tiles.values_mut() {
+        tile.source = tile.source.as_deref().map(|s| resolve_layer_path(root, s));
+    }
+    for building in metadata.buildings.values_mut() {
+        building.source = building.source.as_deref().map(|s| resolve_layer_path(root, s));
+    }
+    for unit in metadata.units.values_mut() {
+        unit.source = unit.source.as_deref().map(|s| resolve_layer_path(root, s));
+        unit.atlas_map = unit.atlas_map.as_deref().map(|s| resolve_layer_path(root, s));
     }
 }
 
-/// Load sprite metadata resource with error handling
-fn load_sprite_metadata_resource() -> SpriteMetadataResource {
-    let mut metadata_resource = SpriteMetadataResource::default();
-    
-    // Try to load sprites.toml metadata
-    match SpriteMetadataResource::load_from_file("assets/data/sprites.toml") {
-        Ok(metadata) => {
-            // Load any referenced atlas maps
-            let mut atlas_maps = HashMap::new();
-            
-            for (name, unit) in &metadata.units {
-                if let Some(atlas_map_path) = &unit.atlas_map {
-                    match SpriteMetadataResource::load_atlas_map(atlas_map_path) {
-                        Ok(atlas_map) => {
-                            atlas_maps.insert(atlas_map_path.clone(), atlas_map);
-                            log::debug!("Loaded atlas map for unit {}: {}", name, atlas_map_path);
-                        }
-                        Err(e) => {
-                            log::warn!("Failed to load atlas map for unit {}: {}", name, e);
-                        }
-                    }
-                }
-            }
-            
-            metadata_resource.metadata = Some(metadata);
-            metadata_resource.atlas_maps = atlas_maps;
-            
-            log::info!("Sprite metadata loaded successfully");
+/// Merges an ordered list of per-root `SpriteMetadata` layers into one
+/// effective `SpriteMetadata`, later roots overriding earlier ones per
+/// tile/building/unit key - the same "mod overrides base" rule `ModLoader`
+/// applies to `buildings.toml`/`recipes.toml`/`workers.toml`. Paths have
+/// already been rooted by `SpriteMetadataLoader` by this point, so merging
+/// is a plain key-wins union.
+fn merge_sprite_metadata_layers(layers: &[&SpriteMetadata]) -> SpriteMetadata {
+    let mut merged = SpriteMetadata {
+        tiles: HashMap::new(),
+        buildings: HashMap::new(),
+        units: HashMap::new(),
+    };
+
+    for layer in layers {
+        for (name, tile) in &layer.tiles {
+            merged.tiles.insert(name.clone(), tile.clone());
+        }
+        for (name, building) in &layer.buildings {
+            merged.buildings.insert(name.clone(), building.clone());
         }
-        Err(e) => {
-            log::warn!("Failed to load sprite metadata: {}. Using fallback asset loading.", e);
+        for (name, unit) in &layer.units {
+            merged.units.insert(name.clone(), unit.clone());
+        }
+    }
+
+    merged
+}
+
+/// Reacts to `SpriteMetadata` asset events instead of polling the filesystem
+/// every frame: a fresh load (`LoadedWithDependencies`) and an edit picked up
+/// by Bevy's asset watcher (`Modified`) both need the same thing - re-merging
+/// every root layer into the effective metadata and resolving each unit's
+/// `atlas_map` path to the `Handle<AtlasFrameMap>` that path loads, so
+/// `get_atlas_map`/`get_frame_data` can look it up.
+pub fn handle_sprite_metadata_events_system(
+    asset_server: Res<AssetServer>,
+    mut sprite_metadata_assets: ResMut<Assets<SpriteMetadata>>,
+    mut metadata_resource: ResMut<SpriteMetadataResource>,
+    mut events: EventReader<AssetEvent<SpriteMetadata>>,
+) {
+    let relevant = events.read().any(|event| {
+        let id = match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => *id,
+            _ => return false,
+        };
+        metadata_resource.layers.iter().any(|handle| handle.id() == id)
+    });
+
+    if !relevant {
+        return;
+    }
+
+    // Wait until every layer has finished loading before merging, so an
+    // override root that's still fetching doesn't get silently dropped from
+    // the merge the moment the base layer finishes first.
+    let Some(layers): Option<Vec<&SpriteMetadata>> = metadata_resource
+        .layers
+        .iter()
+        .map(|handle| sprite_metadata_assets.get(handle))
+        .collect()
+    else {
+        return;
+    };
+
+    let merged = merge_sprite_metadata_layers(&layers);
+
+    let mut atlas_maps = HashMap::new();
+    for (name, unit) in &merged.units {
+        if let Some(atlas_map_path) = &unit.atlas_map {
+            atlas_maps.insert(atlas_map_path.clone(), asset_server.load(atlas_map_path.as_str()));
+            log::debug!("Resolved atlas map handle for unit {}: {}", name, atlas_map_path);
         }
     }
-    
-    metadata_resource
+
+    let layer_count = metadata_resource.layers.len();
+    metadata_resource.handle = sprite_metadata_assets.add(merged);
+    metadata_resource.atlas_maps = atlas_maps;
+    log::info!(
+        "Sprite metadata (re)merged from {} root(s): {} tiles, {} buildings, {} units",
+        layer_count,
+        sprite_metadata_assets.get(&metadata_resource.handle).unwrap().tiles.len(),
+        sprite_metadata_assets.get(&metadata_resource.handle).unwrap().buildings.len(),
+        sprite_metadata_assets.get(&metadata_resource.handle).unwrap().units.len(),
+    );
 }
 
 /// Helper function to get sprite path from metadata
 pub fn get_sprite_path_from_metadata(
     metadata: &SpriteMetadataResource,
+    sprite_metadata_assets: &Assets<SpriteMetadata>,
     sprite_type: &str,
     name: &str,
 ) -> Option<String> {
     match sprite_type {
-        "tile" => metadata.get_tile(name)?.source.clone(),
-        "building" => metadata.get_building(name)?.source.clone(),
-        "unit" => metadata.get_unit(name)?.source.clone(),
+        "tile" => metadata.get_tile(sprite_metadata_assets, name)?.source.clone(),
+        "building" => metadata.get_building(sprite_metadata_assets, name)?.source.clone(),
+        "unit" => metadata.get_unit(sprite_metadata_assets, name)?.source.clone(),
         _ => None,
     }
 }
@@ -233,13 +549,14 @@ pub fn get_sprite_path_from_metadata(
 /// Helper function to get sprite size from metadata
 pub fn get_sprite_size_from_metadata(
     metadata: &SpriteMetadataResource,
+    sprite_metadata_assets: &Assets<SpriteMetadata>,
     sprite_type: &str,
     name: &str,
 ) -> Option<[u32; 2]> {
     match sprite_type {
-        "tile" => Some(metadata.get_tile(name)?.size),
-        "building" => Some(metadata.get_building(name)?.size),
-        "unit" => metadata.get_unit(name)?.frame_size,
+        "tile" => Some(metadata.get_tile(sprite_metadata_assets, name)?.size),
+        "building" => Some(metadata.get_building(sprite_metadata_assets, name)?.size),
+        "unit" => metadata.get_unit(sprite_metadata_assets, name)?.frame_size,
         _ => None,
     }
 }
@@ -247,15 +564,9 @@ pub fn get_sprite_size_from_metadata(
 #[cfg(test)]
 mod integration_tests {
     use super::*;
-    use std::fs;
-    use tempfile::tempdir;
 
     #[test]
     fn test_metadata_driven_asset_loading() {
-        let dir = tempdir().unwrap();
-        let sprites_path = dir.path().join("sprites.toml");
-        
-        // Create test sprites.toml
         let toml_content = r#"
 [tiles.grass]
 kind = "tile"
@@ -277,79 +588,74 @@ anim_walk_fps = 10
 anim_walk_len = 8
 layout = "single_sprite"
 "#;
-        
-        fs::write(&sprites_path, toml_content).unwrap();
-        
-        // Load metadata
-        let metadata = SpriteMetadataResource::load_from_file(sprites_path.to_str().unwrap()).unwrap();
+        let metadata: SpriteMetadata = toml::from_str(toml_content).unwrap();
+
+        let mut sprite_metadata_assets = Assets::<SpriteMetadata>::default();
+        let handle = sprite_metadata_assets.add(metadata);
         let resource = SpriteMetadataResource {
-            metadata: Some(metadata),
-            atlas_maps: HashMap::new(),
+            handle,
+            ..Default::default()
         };
-        
+
         // Test sprite path retrieval
         assert_eq!(
-            get_sprite_path_from_metadata(&resource, "tile", "grass"),
+            get_sprite_path_from_metadata(&resource, &sprite_metadata_assets, "tile", "grass"),
             Some("test_sprites/grass.png".to_string())
         );
-        
+
         assert_eq!(
-            get_sprite_path_from_metadata(&resource, "building", "lumberjack"),
+            get_sprite_path_from_metadata(&resource, &sprite_metadata_assets, "building", "lumberjack"),
             Some("test_sprites/lumberjack.png".to_string())
         );
-        
+
         assert_eq!(
-            get_sprite_path_from_metadata(&resource, "unit", "worker"),
+            get_sprite_path_from_metadata(&resource, &sprite_metadata_assets, "unit", "worker"),
             Some("test_sprites/worker.png".to_string())
         );
-        
+
         // Test fallback for non-existent sprites
         assert_eq!(
-            get_sprite_path_from_metadata(&resource, "tile", "nonexistent"),
+            get_sprite_path_from_metadata(&resource, &sprite_metadata_assets, "tile", "nonexistent"),
             None
         );
-        
+
         // Test sprite size retrieval
         assert_eq!(
-            get_sprite_size_from_metadata(&resource, "tile", "grass"),
+            get_sprite_size_from_metadata(&resource, &sprite_metadata_assets, "tile", "grass"),
             Some([32, 32])
         );
-        
+
         assert_eq!(
-            get_sprite_size_from_metadata(&resource, "building", "lumberjack"),
+            get_sprite_size_from_metadata(&resource, &sprite_metadata_assets, "building", "lumberjack"),
             Some([64, 64])
         );
-        
+
         assert_eq!(
-            get_sprite_size_from_metadata(&resource, "unit", "worker"),
+            get_sprite_size_from_metadata(&resource, &sprite_metadata_assets, "unit", "worker"),
             Some([32, 32])
         );
     }
 
     #[test]
     fn test_backward_compatibility() {
-        // Test that the system works when no sprites.toml exists
+        // Test that the system works when the metadata asset hasn't loaded yet
+        let sprite_metadata_assets = Assets::<SpriteMetadata>::default();
         let resource = SpriteMetadataResource::default();
-        
+
         // Should return None for all lookups when no metadata is loaded
         assert_eq!(
-            get_sprite_path_from_metadata(&resource, "tile", "grass"),
+            get_sprite_path_from_metadata(&resource, &sprite_metadata_assets, "tile", "grass"),
             None
         );
-        
+
         assert_eq!(
-            get_sprite_size_from_metadata(&resource, "building", "lumberjack"),
+            get_sprite_size_from_metadata(&resource, &sprite_metadata_assets, "building", "lumberjack"),
             None
         );
     }
 
     #[test]
     fn test_atlas_support() {
-        let dir = tempdir().unwrap();
-        let sprites_path = dir.path().join("sprites.toml");
-        let atlas_path = dir.path().join("worker_atlas.json");
-        
-        // Create test atlas JSON
         let atlas_content = r#"
 {
   "frames": {
@@ -365,11 +671,9 @@ layout = "single_sprite"
   }
 }
 "#;
-        
-        fs::write(&atlas_path, atlas_content).unwrap();
-        
-        // Create test sprites.toml with atlas reference
-        let toml_content = format!(r#"
+        let atlas_map: AtlasFrameMap = serde_json::from_str(atlas_content).unwrap();
+
+        let toml_content = r#"
 [units.worker]
 kind = "unit"
 source = "test_sprites/worker_atlas.png"
@@ -378,37 +682,166 @@ directions = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"]
 anim_walk_fps = 10
 anim_walk_len = 8
 layout = "dirs_rows"
-atlas_map = "{}"
-"#, atlas_path.to_str().unwrap());
-        
-        fs::write(&sprites_path, toml_content).unwrap();
-        
-        // Load metadata
-        let metadata = SpriteMetadataResource::load_from_file(sprites_path.to_str().unwrap()).unwrap();
-        let atlas_map = SpriteMetadataResource::load_atlas_map(atlas_path.to_str().unwrap()).unwrap();
-        
+atlas_map = "test_sprites/worker_atlas.json"
+"#;
+        let metadata: SpriteMetadata = toml::from_str(toml_content).unwrap();
+
+        let mut sprite_metadata_assets = Assets::<SpriteMetadata>::default();
+        let metadata_handle = sprite_metadata_assets.add(metadata);
+
+        let mut atlas_map_assets = Assets::<AtlasFrameMap>::default();
+        let atlas_handle = atlas_map_assets.add(atlas_map);
+
         let mut atlas_maps = HashMap::new();
-        atlas_maps.insert(atlas_path.to_str().unwrap().to_string(), atlas_map);
-        
+        atlas_maps.insert("test_sprites/worker_atlas.json".to_string(), atlas_handle);
+
         let resource = SpriteMetadataResource {
-            metadata: Some(metadata),
+            handle: metadata_handle,
             atlas_maps,
+            ..Default::default()
         };
-        
+
         // Test atlas frame retrieval
-        let frame_data = resource.get_frame_data(atlas_path.to_str().unwrap(), "walk_N_0");
+        let frame_data = resource.get_frame_data(&atlas_map_assets, "test_sprites/worker_atlas.json", "walk_N_0");
         assert!(frame_data.is_some());
-        
+
         let frame = frame_data.unwrap();
         assert_eq!(frame.x, 0);
         assert_eq!(frame.y, 0);
         assert_eq!(frame.w, 32);
         assert_eq!(frame.h, 32);
-        
+
         // Test unit metadata
-        let worker = resource.get_unit("worker").unwrap();
+        let worker = resource.get_unit(&sprite_metadata_assets, "worker").unwrap();
         assert_eq!(worker.anim_walk_fps, Some(10));
         assert_eq!(worker.anim_walk_len, Some(8));
         assert_eq!(worker.layout, Some("dirs_rows".to_string()));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_atlas_frame_rotation_and_trim_support() {
+        let atlas_content = r#"
+{
+  "frames": {
+    "plain": {"x": 0, "y": 0, "w": 32, "h": 32},
+    "rotated_and_trimmed": {
+      "x": 32, "y": 0, "w": 20, "h": 28,
+      "rotated": true,
+      "trimmed": true,
+      "sprite_source_size": {"x": 4, "y": 2, "w": 28, "h": 20},
+      "source_size": {"w": 32, "h": 32}
+    }
+  },
+  "meta": {
+    "size": {"w": 256, "h": 256},
+    "format": "RGBA8888",
+    "scale": 1
+  }
+}
+"#;
+        let atlas_map: AtlasFrameMap = serde_json::from_str(atlas_content).unwrap();
+        let mut atlas_map_assets = Assets::<AtlasFrameMap>::default();
+        let atlas_handle = atlas_map_assets.add(atlas_map);
+
+        let mut atlas_maps = HashMap::new();
+        atlas_maps.insert("atlas.json".to_string(), atlas_handle);
+        let resource = SpriteMetadataResource {
+            atlas_maps,
+            ..Default::default()
+        };
+
+        // A plain frame with no packer metadata resolves to its own rect,
+        // unrotated, with no trim offset.
+        let plain = resource.get_resolved_frame(&atlas_map_assets, "atlas.json", "plain").unwrap();
+        assert!(!plain.rotated);
+        assert_eq!(plain.sprite_source_size.w, 32);
+        assert_eq!(plain.sprite_source_size.h, 32);
+        assert_eq!(plain.source_size.w, 32);
+        assert_eq!(plain.source_size.h, 32);
+
+        // A rotated, trimmed frame carries its packed rect as-is plus the
+        // offset/size needed to place it back within its logical bounds.
+        let rotated = resource
+            .get_resolved_frame(&atlas_map_assets, "atlas.json", "rotated_and_trimmed")
+            .unwrap();
+        assert!(rotated.rotated);
+        assert_eq!(rotated.atlas_rect.w, 20);
+        assert_eq!(rotated.atlas_rect.h, 28);
+        assert_eq!(rotated.sprite_source_size.x, 4);
+        assert_eq!(rotated.sprite_source_size.y, 2);
+        assert_eq!(rotated.sprite_source_size.w, 28);
+        assert_eq!(rotated.sprite_source_size.h, 20);
+        assert_eq!(rotated.source_size.w, 32);
+        assert_eq!(rotated.source_size.h, 32);
+    }
+
+    #[test]
+    fn test_layered_roots_merge_with_mod_overriding_base() {
+        // Paths are already rooted here, mirroring what `SpriteMetadataLoader`
+        // would have rewritten them to before merge time.
+        let mut base: SpriteMetadata = toml::from_str(
+            r#"
+[tiles.grass]
+kind = "tile"
+size = [32, 32]
+source = "grass.png"
+
+[units.worker]
+kind = "unit"
+source = "worker.png"
+"#,
+        )
+        .unwrap();
+        resolve_sprite_metadata_paths(&mut base, Path::new("data"));
+
+        let mut mod_layer: SpriteMetadata = toml::from_str(
+            r#"
+[units.worker]
+kind = "unit"
+source = "worker_reskin.png"
+atlas_map = "worker_atlas.json"
+"#,
+        )
+        .unwrap();
+        resolve_sprite_metadata_paths(&mut mod_layer, Path::new("mods/rivermod"));
+
+        let merged = merge_sprite_metadata_layers(&[&base, &mod_layer]);
+
+        // The base tile survives untouched since the mod never redefined it.
+        assert_eq!(merged.tiles.get("grass").unwrap().source, Some("data/grass.png".to_string()));
+
+        // The mod's unit replaces the base one entirely, and its paths
+        // resolve against the mod's own root rather than the base root.
+        let worker = merged.units.get("worker").unwrap();
+        assert_eq!(worker.source, Some("mods/rivermod/worker_reskin.png".to_string()));
+        assert_eq!(worker.atlas_map, Some("mods/rivermod/worker_atlas.json".to_string()));
+    }
+
+    #[test]
+    fn test_relative_paths_resolve_against_their_declaring_root() {
+        let mut metadata: SpriteMetadata = toml::from_str(
+            r#"
+[tiles.grass]
+kind = "tile"
+size = [32, 32]
+source = "grass.png"
+
+[units.worker]
+kind = "unit"
+source = "worker.png"
+atlas_map = "worker_atlas.json"
+"#,
+        )
+        .unwrap();
+
+        resolve_sprite_metadata_paths(&mut metadata, Path::new("mods/rivermod"));
+
+        assert_eq!(
+            metadata.tiles.get("grass").unwrap().source,
+            Some("mods/rivermod/grass.png".to_string())
+        );
+        let worker = metadata.units.get("worker").unwrap();
+        assert_eq!(worker.source, Some("mods/rivermod/worker.png".to_string()));
+        assert_eq!(worker.atlas_map, Some("mods/rivermod/worker_atlas.json".to_string()));
+    }
+}
@@ -3,7 +3,8 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 /// Position on the tile grid
-#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Component, Reflect, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[reflect(Component)]
 pub struct Position {
     pub x: i32,
     pub y: i32,
@@ -22,11 +23,13 @@ impl Position {
 }
 
 /// Marks a tile as blocked for pathfinding
-#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+#[derive(Component, Reflect, Debug, Clone, Serialize, Deserialize)]
+#[reflect(Component)]
 pub struct Blocked;
 
 /// Road tile that provides movement bonus
-#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+#[derive(Component, Reflect, Debug, Clone, Serialize, Deserialize)]
+#[reflect(Component)]
 pub struct Road {
     pub movement_cost: f32,
 }
@@ -38,7 +41,8 @@ impl Default for Road {
 }
 
 /// Stockpile for storing resources
-#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+#[derive(Component, Reflect, Debug, Clone, Serialize, Deserialize)]
+#[reflect(Component)]
 pub struct Stockpile {
     pub capacity: u32,
     pub items: std::collections::HashMap<String, u32>,
@@ -88,10 +92,34 @@ impl Stockpile {
     pub fn get_item_count(&self, item: &str) -> u32 {
         self.items.get(item).copied().unwrap_or(0)
     }
+
+    /// Free capacity after subtracting space already promised to in-flight
+    /// deliveries, so a second worker can't commit to the same last slot.
+    pub fn effective_available_space(
+        &self,
+        entity: Entity,
+        reservations: &crate::resources::Reservations,
+    ) -> u32 {
+        self.available_space()
+            .saturating_sub(reservations.reserved_in(entity))
+    }
+
+    /// Item count still uncommitted to an outgoing haul, so a second worker
+    /// can't also pick up goods already claimed for pickup.
+    pub fn effective_item_count(
+        &self,
+        item: &str,
+        entity: Entity,
+        reservations: &crate::resources::Reservations,
+    ) -> u32 {
+        self.get_item_count(item)
+            .saturating_sub(reservations.reserved_out(entity, item))
+    }
 }
 
 /// Building component
-#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+#[derive(Component, Reflect, Debug, Clone, Serialize, Deserialize)]
+#[reflect(Component)]
 pub struct Building {
     pub building_type: String,
     pub construction_progress: f32, // 0.0 to 1.0
@@ -112,13 +140,79 @@ impl Building {
     }
 }
 
+/// Per-construction-job material ledger: how much of each item the build
+/// still needs, how much is already committed to an in-flight delivery, and
+/// how much has actually arrived. Attached to every `Building` at placement
+/// alongside its `construction_cost`, and consulted by
+/// `systems::worker_ai::enumerate_candidates` so idle workers stop fetching
+/// materials for a job the moment enough are already reserved or delivered,
+/// instead of every idle worker independently committing a full load.
+#[derive(Component, Reflect, Debug, Clone, Default, Serialize, Deserialize)]
+#[reflect(Component)]
+pub struct ConstructionMaterials {
+    pub required: std::collections::HashMap<String, u32>,
+    pub reserved: std::collections::HashMap<String, u32>,
+    pub delivered: std::collections::HashMap<String, u32>,
+}
+
+impl ConstructionMaterials {
+    pub fn new(required: std::collections::HashMap<String, u32>) -> Self {
+        Self {
+            required,
+            reserved: std::collections::HashMap::new(),
+            delivered: std::collections::HashMap::new(),
+        }
+    }
+
+    /// Demand for `item` not already covered by an in-flight delivery or by
+    /// materials already on site.
+    pub fn remaining_demand(&self, item: &str) -> u32 {
+        let required = self.required.get(item).copied().unwrap_or(0);
+        let committed = self.reserved.get(item).copied().unwrap_or(0)
+            + self.delivered.get(item).copied().unwrap_or(0);
+        required.saturating_sub(committed)
+    }
+
+    /// Commits `amount` of `item` to an in-flight delivery, so other workers
+    /// see it as already spoken for.
+    pub fn reserve(&mut self, item: &str, amount: u32) {
+        *self.reserved.entry(item.to_string()).or_insert(0) += amount;
+    }
+
+    /// Releases a reservation an aborted delivery was holding.
+    pub fn release_reservation(&mut self, item: &str, amount: u32) {
+        if let Some(reserved) = self.reserved.get_mut(item) {
+            *reserved = reserved.saturating_sub(amount);
+        }
+    }
+
+    /// Moves `amount` of `item` from reserved to delivered, on arrival.
+    pub fn deliver(&mut self, item: &str, amount: u32) {
+        self.release_reservation(item, amount);
+        *self.delivered.entry(item.to_string()).or_insert(0) += amount;
+    }
+
+    /// Whether every required item has fully arrived.
+    pub fn is_fulfilled(&self) -> bool {
+        self.required
+            .iter()
+            .all(|(item, &amount)| self.delivered.get(item).copied().unwrap_or(0) >= amount)
+    }
+}
+
 /// Production facility that converts inputs to outputs
-#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+#[derive(Component, Reflect, Debug, Clone, Serialize, Deserialize)]
+#[reflect(Component)]
 pub struct Producer {
     pub recipe_id: String,
     pub production_progress: f32, // 0.0 to 1.0
     pub is_producing: bool,
     pub production_queue: Vec<String>, // Queue of recipe IDs
+    /// This building's cluster's power satisfaction ratio as of the last
+    /// `production_system` run, `1.0` if it draws no power. Surfaced so the
+    /// client can show "running at N% due to power shortage" instead of
+    /// silently running slow.
+    pub productivity_factor: f32,
 }
 
 impl Producer {
@@ -128,22 +222,33 @@ impl Producer {
             production_progress: 0.0,
             is_producing: false,
             production_queue: Vec::new(),
+            productivity_factor: 1.0,
         }
     }
 }
 
 /// Worker unit
-#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+#[derive(Component, Reflect, Debug, Clone, Serialize, Deserialize)]
+#[reflect(Component)]
 pub struct Worker {
+    /// Not reflected: `Uuid` has no `Reflect` impl, and the inspector has no
+    /// use for editing an entity's stable id anyway.
+    #[reflect(ignore)]
     pub id: Uuid,
     pub worker_type: String,
     pub assigned_building: Option<Entity>,
     pub current_task: WorkerTask,
     pub carrying: Option<(String, u32)>, // (item_type, amount)
     pub movement_speed: f32,
+    /// Proficiency per recipe id, 0.0 (unskilled) to 1.0 (mastered). Rises
+    /// slowly while the worker is `Working` on a matching recipe.
+    pub skills: std::collections::HashMap<String, f32>,
+    /// The `RoadSegment` this worker is bound to as a carrier, if any. A
+    /// carrier never leaves its segment - see `systems::logistics`.
+    pub assigned_road_segment: Option<Entity>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Reflect, Debug, Clone, Serialize, Deserialize)]
 pub enum WorkerTask {
     Idle,
     MovingTo {
@@ -157,15 +262,32 @@ pub enum WorkerTask {
     Carrying {
         from: Position,
         to: Position,
+        /// Stockpile the item is being delivered into, so the incoming-space
+        /// reservation it claimed can be released on arrival. For a carrier
+        /// hop (`route.is_some()`) this is instead the flag at the far end
+        /// of the segment being walked.
+        destination: Entity,
         item: String,
         amount: u32,
+        /// Set only for a carrier's single hop along a `RoadSegment`: the
+        /// flags still to be hopped through after `destination` (nearest
+        /// first) and the building the ware is ultimately bound for. `None`
+        /// for an ordinary point-to-point haul, where `destination` is
+        /// already the final stockpile.
+        route: Option<(Vec<Entity>, Entity)>,
     },
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Reflect, Debug, Clone, Serialize, Deserialize)]
 pub enum TaskPurpose {
     GoToWork,
-    PickupResource { item: String, amount: u32 },
+    PickupResource {
+        /// Stockpile the item is being claimed from, so the outgoing
+        /// reservation it claimed can be released on arrival.
+        source: Entity,
+        item: String,
+        amount: u32,
+    },
     DeliverResource { item: String, amount: u32 },
     Construction,
 }
@@ -179,8 +301,27 @@ impl Worker {
             current_task: WorkerTask::Idle,
             carrying: None,
             movement_speed: 1.0,
+            skills: std::collections::HashMap::new(),
+            assigned_road_segment: None,
         }
     }
+
+    /// Proficiency at a given recipe, 0.0 if never practiced.
+    pub fn skill_for(&self, recipe_id: &str) -> f32 {
+        self.skills.get(recipe_id).copied().unwrap_or(0.0)
+    }
+
+    /// Movement speed scaled by proficiency at `recipe_id`: half speed unskilled,
+    /// full speed at max skill.
+    pub fn effective_speed(&self, recipe_id: &str) -> f32 {
+        self.movement_speed * (0.5 + self.skill_for(recipe_id) * 0.5)
+    }
+
+    /// Production rate scaled by proficiency at `recipe_id`: half rate unskilled,
+    /// up to 1.5x at max skill.
+    pub fn effective_production_rate(&self, base_rate: f32, recipe_id: &str) -> f32 {
+        base_rate * (0.5 + self.skill_for(recipe_id))
+    }
 }
 
 /// Pathfinding component for moving entities
@@ -189,6 +330,13 @@ pub struct Pathfinding {
     pub path: Vec<Position>,
     pub current_target_index: usize,
     pub recalculate: bool,
+    /// Pheromone trail this path was requested on, if any. Carried along so the
+    /// movement system knows which channel to reinforce as the entity walks.
+    pub channel: Option<crate::resources::PheromoneChannel>,
+    /// Distance already covered toward `current_target`, so a unit too slow
+    /// to finish a segment in one tick resumes mid-segment next tick instead
+    /// of losing the progress.
+    pub segment_progress: f32,
 }
 
 impl Pathfinding {
@@ -197,6 +345,18 @@ impl Pathfinding {
             path,
             current_target_index: 0,
             recalculate: false,
+            channel: None,
+            segment_progress: 0.0,
+        }
+    }
+
+    pub fn with_channel(path: Vec<Position>, channel: Option<crate::resources::PheromoneChannel>) -> Self {
+        Self {
+            path,
+            current_target_index: 0,
+            recalculate: false,
+            channel,
+            segment_progress: 0.0,
         }
     }
 
@@ -207,6 +367,7 @@ impl Pathfinding {
     pub fn advance_target(&mut self) -> bool {
         if self.current_target_index + 1 < self.path.len() {
             self.current_target_index += 1;
+            self.segment_progress = 0.0;
             true
         } else {
             false
@@ -218,14 +379,32 @@ impl Pathfinding {
     }
 }
 
+/// How fast an entity covers ground along its `Pathfinding` path, in tiles
+/// per second. Entities without this component move at
+/// [`DEFAULT_MOVEMENT_SPEED`].
+#[derive(Component, Reflect, Debug, Clone, Copy, Serialize, Deserialize)]
+#[reflect(Component)]
+pub struct MovementSpeed(pub f32);
+
+/// Fallback speed (tiles/second) for a `Pathfinding`-driven entity with no
+/// `MovementSpeed` component of its own.
+pub const DEFAULT_MOVEMENT_SPEED: f32 = 1.0;
+
+impl Default for MovementSpeed {
+    fn default() -> Self {
+        Self(DEFAULT_MOVEMENT_SPEED)
+    }
+}
+
 /// Tile component for map tiles
-#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+#[derive(Component, Reflect, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[reflect(Component)]
 pub struct Tile {
     pub tile_type: TileType,
     pub elevation: u8,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum TileType {
     Grass,
     Water,
@@ -249,3 +428,108 @@ impl Tile {
         !matches!(self.tile_type, TileType::Water)
     }
 }
+
+/// A flag: the fixed drop-off point at a building's entrance where wares
+/// wait for a carrier to take them onward, Widelands-style. The logistics
+/// network is built from flags and the `RoadSegment`s between them, not
+/// from buildings directly.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct Flag {
+    pub building: Entity,
+}
+
+/// A road between two flags, worked by at most one dedicated `carrier` who
+/// only ever walks this one segment back and forth, never the whole map.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct RoadSegment {
+    pub from_flag: Entity,
+    pub to_flag: Entity,
+    pub carrier: Option<Entity>,
+}
+
+impl RoadSegment {
+    pub fn new(from_flag: Entity, to_flag: Entity) -> Self {
+        Self {
+            from_flag,
+            to_flag,
+            carrier: None,
+        }
+    }
+
+    /// The flag at the other end of this segment from `flag`, if `flag` is
+    /// actually one of its two ends.
+    pub fn other_end(&self, flag: Entity) -> Option<Entity> {
+        if flag == self.from_flag {
+            Some(self.to_flag)
+        } else if flag == self.to_flag {
+            Some(self.from_flag)
+        } else {
+            None
+        }
+    }
+}
+
+/// One ware sitting at a `Flag`, waiting for the carrier working the
+/// segment toward `route`'s next flag to pick it up. `route` is only the
+/// remaining hops - each flag only needs to know the next step, not the
+/// whole path the economy planned when it first posted the request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WaitingWare {
+    pub item: String,
+    pub amount: u32,
+    /// Remaining flags to hop through, nearest first. The last entry is
+    /// `destination_building`'s own flag.
+    pub route: Vec<Entity>,
+    pub destination_building: Entity,
+}
+
+/// Per-flag queue of wares waiting for pickup by whichever carrier works
+/// the segment toward their next hop.
+#[derive(Component, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FlagQueue {
+    pub waiting: Vec<WaitingWare>,
+}
+
+/// A directed conveyor carrying a single item type from one stockpile-bearing
+/// entity to another at a fixed per-cycle throughput cap, the automated
+/// counterpart to a `RoadSegment`'s worker-hauled goods. Standalone entity,
+/// same pattern as `RoadSegment` itself.
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+pub struct BeltSegment {
+    pub from: Entity,
+    pub to: Entity,
+    pub item: String,
+    pub capacity: u32,
+}
+
+impl BeltSegment {
+    pub fn new(from: Entity, to: Entity, item: String, capacity: u32) -> Self {
+        Self {
+            from,
+            to,
+            item,
+            capacity,
+        }
+    }
+}
+
+/// Attached to a source entity to ratio-split its outgoing `BeltSegment`s for
+/// a given item across their destinations instead of splitting evenly.
+/// Destinations with no entry here split the remainder evenly between them.
+#[derive(Component, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Splitter {
+    pub ratios: std::collections::HashMap<Entity, f32>,
+}
+
+/// Attached by `systems::transport_system` to a worker it just dispatched to
+/// fetch a `TransferResourceEvent`'s item, recording the destination that
+/// event named (already reserved via `Reservations::reserve_in`) so
+/// `systems::transport_completion_system` can deliver there directly once the
+/// pickup finishes, instead of guessing at a destination by scanning every
+/// stockpile for free space. Removed again as soon as that handoff happens.
+#[derive(Component, Debug, Clone)]
+pub struct PendingDelivery {
+    pub destination: Entity,
+    pub item: String,
+    pub amount: u32,
+}
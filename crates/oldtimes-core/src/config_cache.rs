@@ -0,0 +1,225 @@
+//! Binary cache for validated [`GameConfig`]s, keyed by a content hash of
+//! the source TOML files.
+//!
+//! `DataLoader::load_from_directory` re-parses and re-validates every TOML
+//! file on each call, which gets expensive on large modded data sets. This
+//! module hashes the raw bytes of the files that feed a load together with
+//! [`CACHE_FORMAT_VERSION`], and stores the already-validated `GameConfig`
+//! as an `rkyv` archive at `<data_dir>/.cache/config.rkyv`. A later load
+//! whose file bytes hash the same way skips TOML parsing and the
+//! `validate_building`/`validate_recipe`/`validate_worker` passes entirely
+//! and instead checks the archive and deserializes it back into a
+//! `GameConfig`.
+
+use crate::resources::GameConfig;
+use anyhow::{anyhow, Result};
+use rkyv::ser::serializers::AllocSerializer;
+use rkyv::ser::Serializer;
+use rkyv::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Bumped whenever the archived `GameConfig` layout changes, so a cache
+/// built by an older binary is never mistaken for a hit.
+const CACHE_FORMAT_VERSION: u32 = 1;
+
+const CACHE_FILE_NAME: &str = "config.rkyv";
+
+/// Files that feed `DataLoader::load_from_directory`, hashed in this fixed
+/// order. A file that doesn't exist contributes nothing, matching
+/// `load_from_directory`'s "file absent -> default" behavior.
+const HASHED_FILE_NAMES: &[&str] = &[
+    "buildings.toml",
+    "recipes.toml",
+    "workers.toml",
+    "mapgen.toml",
+    "pheromones.toml",
+    "visibility.toml",
+    "grid.toml",
+];
+
+const HEADER_LEN: usize = 4 + 32;
+
+/// Hashes the format version tag and the concatenated raw bytes of every
+/// file in `HASHED_FILE_NAMES` found under `data_dir`.
+pub fn content_hash<P: AsRef<Path>>(data_dir: P) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&CACHE_FORMAT_VERSION.to_le_bytes());
+
+    for name in HASHED_FILE_NAMES {
+        if let Ok(bytes) = fs::read(data_dir.as_ref().join(name)) {
+            hasher.update(name.as_bytes());
+            hasher.update(&bytes);
+        }
+    }
+
+    *hasher.finalize().as_bytes()
+}
+
+fn cache_path<P: AsRef<Path>>(data_dir: P) -> PathBuf {
+    data_dir.as_ref().join(".cache").join(CACHE_FILE_NAME)
+}
+
+struct CacheHeader {
+    format_version: u32,
+    content_hash: [u8; 32],
+}
+
+impl CacheHeader {
+    fn encode(&self) -> [u8; HEADER_LEN] {
+        let mut buf = [0u8; HEADER_LEN];
+        buf[0..4].copy_from_slice(&self.format_version.to_le_bytes());
+        buf[4..HEADER_LEN].copy_from_slice(&self.content_hash);
+        buf
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < HEADER_LEN {
+            return None;
+        }
+        let format_version = u32::from_le_bytes(bytes[0..4].try_into().ok()?);
+        let mut content_hash = [0u8; 32];
+        content_hash.copy_from_slice(&bytes[4..HEADER_LEN]);
+        Some(Self {
+            format_version,
+            content_hash,
+        })
+    }
+}
+
+/// Loads the cached `GameConfig` for `data_dir` if `.cache/config.rkyv`
+/// exists, its header format version matches, and its stored hash matches
+/// `expected_hash`. Any read, header, or archive-validation failure is
+/// treated as a cache miss rather than an error, since reparsing from TOML
+/// is always a safe fallback.
+pub fn try_load<P: AsRef<Path>>(data_dir: P, expected_hash: [u8; 32]) -> Option<GameConfig> {
+    let bytes = fs::read(cache_path(&data_dir)).ok()?;
+    let header = CacheHeader::decode(&bytes)?;
+
+    if header.format_version != CACHE_FORMAT_VERSION || header.content_hash != expected_hash {
+        return None;
+    }
+
+    let archived = rkyv::check_archived_root::<GameConfig>(&bytes[HEADER_LEN..]).ok()?;
+    let config: GameConfig = archived.deserialize(&mut rkyv::Infallible).ok()?;
+
+    log::info!("Loaded GameConfig from binary cache, skipping TOML parsing and validation");
+    Some(config)
+}
+
+/// Serializes `config` as an rkyv archive and atomically rewrites
+/// `.cache/config.rkyv` under `data_dir`, prefixed with a header carrying
+/// `content_hash` so a later [`try_load`] can recognize a hit.
+pub fn store<P: AsRef<Path>>(
+    data_dir: P,
+    content_hash: [u8; 32],
+    config: &GameConfig,
+) -> Result<()> {
+    let cache_dir = data_dir.as_ref().join(".cache");
+    fs::create_dir_all(&cache_dir)?;
+
+    let mut serializer = AllocSerializer::<4096>::default();
+    serializer
+        .serialize_value(config)
+        .map_err(|e| anyhow!("failed to archive GameConfig: {e}"))?;
+    let archive_bytes = serializer.into_serializer().into_inner();
+
+    let mut out = Vec::with_capacity(HEADER_LEN + archive_bytes.len());
+    out.extend_from_slice(
+        &CacheHeader {
+            format_version: CACHE_FORMAT_VERSION,
+            content_hash,
+        }
+        .encode(),
+    );
+    out.extend_from_slice(&archive_bytes);
+
+    // Write to a sibling temp file and rename so a reader never observes a
+    // partially written cache.
+    let final_path = cache_path(&data_dir);
+    let tmp_path = final_path.with_extension("rkyv.tmp");
+    fs::write(&tmp_path, &out)?;
+    fs::rename(&tmp_path, &final_path)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::GameConfig;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_store_and_try_load_round_trip_is_byte_identical() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = GameConfig::default();
+        let hash = content_hash(temp_dir.path());
+
+        store(temp_dir.path(), hash, &config).unwrap();
+        let loaded = try_load(temp_dir.path(), hash).expect("freshly stored cache should hit");
+
+        assert_eq!(format!("{config:?}"), format!("{loaded:?}"));
+    }
+
+    #[test]
+    fn test_try_load_misses_on_format_version_mismatch() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = GameConfig::default();
+        let hash = content_hash(temp_dir.path());
+        store(temp_dir.path(), hash, &config).unwrap();
+
+        // Rewrite just the header with a stale format version, leaving the
+        // hash and archive bytes untouched.
+        let mut bytes = fs::read(cache_path(temp_dir.path())).unwrap();
+        bytes[0..4].copy_from_slice(&(CACHE_FORMAT_VERSION + 1).to_le_bytes());
+        fs::write(cache_path(temp_dir.path()), &bytes).unwrap();
+
+        assert!(try_load(temp_dir.path(), hash).is_none());
+    }
+
+    #[test]
+    fn test_try_load_misses_on_content_hash_mismatch() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = GameConfig::default();
+        let hash = content_hash(temp_dir.path());
+        store(temp_dir.path(), hash, &config).unwrap();
+
+        let mut different_hash = hash;
+        different_hash[0] ^= 0xFF;
+
+        assert!(try_load(temp_dir.path(), different_hash).is_none());
+    }
+
+    #[test]
+    fn test_try_load_misses_instead_of_panicking_on_truncated_archive() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = GameConfig::default();
+        let hash = content_hash(temp_dir.path());
+        store(temp_dir.path(), hash, &config).unwrap();
+
+        let bytes = fs::read(cache_path(temp_dir.path())).unwrap();
+        fs::write(cache_path(temp_dir.path()), &bytes[..bytes.len() / 2]).unwrap();
+
+        assert!(try_load(temp_dir.path(), hash).is_none());
+    }
+
+    #[test]
+    fn test_try_load_misses_when_cache_file_absent() {
+        let temp_dir = TempDir::new().unwrap();
+        let hash = content_hash(temp_dir.path());
+
+        assert!(try_load(temp_dir.path(), hash).is_none());
+    }
+
+    #[test]
+    fn test_content_hash_changes_when_a_hashed_file_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let before = content_hash(temp_dir.path());
+
+        fs::write(temp_dir.path().join("buildings.toml"), "changed").unwrap();
+        let after = content_hash(temp_dir.path());
+
+        assert_ne!(before, after);
+    }
+}
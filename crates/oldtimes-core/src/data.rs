@@ -1,8 +1,17 @@
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+
+/// On-disk shape of `grid.toml`; `GridShape` alone isn't a valid TOML
+/// document root, so it's wrapped in a one-field table.
+#[derive(Debug, Serialize, Deserialize)]
+struct GridFile {
+    shape: crate::grid::GridShape,
+}
 
 /// Data loader for game configuration files
 pub struct DataLoader {
@@ -21,13 +30,25 @@ impl DataLoader {
     }
 
     /// Load all data from a directory
+    ///
+    /// Checks the binary config cache first: if `.cache/config.rkyv` under
+    /// `data_dir` matches the content hash of the current TOML files, the
+    /// already-validated `GameConfig` is deserialized from the archive and
+    /// TOML parsing/validation is skipped entirely. On a miss
+    /// the directory is loaded and validated as usual, then the cache is
+    /// rewritten for next time.
     pub fn load_from_directory<P: AsRef<Path>>(
         data_dir: P,
     ) -> Result<crate::resources::GameConfig> {
-        let mut loader = Self::new();
-
         let data_path = data_dir.as_ref();
 
+        let hash = crate::config_cache::content_hash(data_path);
+        if let Some(config) = crate::config_cache::try_load(data_path, hash) {
+            return Ok(config);
+        }
+
+        let mut loader = Self::new();
+
         // Load buildings
         let buildings_path = data_path.join("buildings.toml");
         if buildings_path.exists() {
@@ -54,12 +75,45 @@ impl DataLoader {
             crate::resources::MapGenerationConfig::default()
         };
 
-        Ok(crate::resources::GameConfig {
+        // Load pheromone trail tuning
+        let pheromones_path = data_path.join("pheromones.toml");
+        let pheromones = if pheromones_path.exists() {
+            loader.load_pheromone_config(&pheromones_path)?
+        } else {
+            crate::resources::PheromoneConfig::default()
+        };
+
+        // Load fog-of-war sight radii
+        let visibility_path = data_path.join("visibility.toml");
+        let visibility = if visibility_path.exists() {
+            loader.load_visibility_config(&visibility_path)?
+        } else {
+            crate::resources::VisibilityConfig::default()
+        };
+
+        // Load grid layout (square vs hex)
+        let grid_path = data_path.join("grid.toml");
+        let grid_shape = if grid_path.exists() {
+            loader.load_grid_shape(&grid_path)?
+        } else {
+            crate::grid::GridShape::default()
+        };
+
+        let config = crate::resources::GameConfig {
             buildings: loader.buildings,
             recipes: loader.recipes,
             workers: loader.workers,
             map_generation,
-        })
+            pheromones,
+            visibility,
+            grid_shape,
+        };
+
+        if let Err(e) = crate::config_cache::store(data_path, hash, &config) {
+            log::warn!("Failed to write config cache, will reparse next time: {e}");
+        }
+
+        Ok(config)
     }
 
     fn load_buildings<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
@@ -113,6 +167,36 @@ impl DataLoader {
         Ok(config)
     }
 
+    fn load_pheromone_config<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<crate::resources::PheromoneConfig> {
+        let content = fs::read_to_string(path)?;
+        let config: crate::resources::PheromoneConfig = toml::from_str(&content)?;
+
+        log::info!("Loaded pheromone trail config");
+        Ok(config)
+    }
+
+    fn load_visibility_config<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<crate::resources::VisibilityConfig> {
+        let content = fs::read_to_string(path)?;
+        let config: crate::resources::VisibilityConfig = toml::from_str(&content)?;
+
+        log::info!("Loaded visibility config");
+        Ok(config)
+    }
+
+    fn load_grid_shape<P: AsRef<Path>>(&self, path: P) -> Result<crate::grid::GridShape> {
+        let content = fs::read_to_string(path)?;
+        let file: GridFile = toml::from_str(&content)?;
+
+        log::info!("Loaded grid shape: {:?}", file.shape);
+        Ok(file.shape)
+    }
+
     fn validate_building(&self, building: &crate::resources::BuildingConfig) -> Result<()> {
         if building.name.is_empty() {
             return Err(anyhow::anyhow!("Building name cannot be empty"));
@@ -132,6 +216,10 @@ impl DataLoader {
             return Err(anyhow::anyhow!("Building size must be positive"));
         }
 
+        if building.power_generation < 0.0 || building.power_demand < 0.0 {
+            return Err(anyhow::anyhow!("Building power values cannot be negative"));
+        }
+
         Ok(())
     }
 
@@ -178,6 +266,56 @@ impl DataLoader {
 
         Ok(())
     }
+
+    /// Watches `buildings.toml`, `recipes.toml`, `workers.toml`, and
+    /// `mapgen.toml` under `data_dir` and sends a freshly rebuilt
+    /// `GameConfig` (run through the same `validate_*` checks as a normal
+    /// load) every time one of them changes. A reload that fails validation
+    /// is logged and dropped rather than sent, so the previously published
+    /// config stays live instead of the simulation crashing on a bad edit.
+    pub fn watch_directory<P: AsRef<Path>>(
+        data_dir: P,
+    ) -> Result<Receiver<crate::resources::GameConfig>> {
+        let data_path = data_dir.as_ref().to_path_buf();
+        let (tx, rx) = mpsc::channel();
+        let (notify_tx, notify_rx) = mpsc::channel();
+
+        let mut watcher: RecommendedWatcher = notify::recommended_watcher(notify_tx)?;
+        watcher.watch(&data_path, RecursiveMode::NonRecursive)?;
+
+        std::thread::spawn(move || {
+            // Keep the watcher alive for as long as the thread runs; it
+            // stops emitting events as soon as it's dropped.
+            let _watcher = watcher;
+
+            for event in notify_rx {
+                let Ok(event) = event else { continue };
+                if !event.paths.iter().any(|path| is_watched_data_file(path)) {
+                    continue;
+                }
+
+                match Self::load_from_directory(&data_path) {
+                    Ok(config) => {
+                        if tx.send(config).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("Config reload failed, keeping previous config live: {e}");
+                    }
+                }
+            }
+        });
+
+        Ok(rx)
+    }
+}
+
+fn is_watched_data_file(path: &Path) -> bool {
+    matches!(
+        path.file_name().and_then(|name| name.to_str()),
+        Some("buildings.toml") | Some("recipes.toml") | Some("workers.toml") | Some("mapgen.toml")
+    )
 }
 
 /// Mod loader for loading game modifications
@@ -192,6 +330,124 @@ pub struct ModInfo {
     pub description: String,
     pub author: String,
     pub priority: i32,
+    /// Other mod name -> semver requirement (e.g. `"^1.2"`) that the named
+    /// mod's own declared `version` must satisfy for this mod to load.
+    #[serde(default)]
+    pub dependencies: HashMap<String, String>,
+    /// Names of mods that must not be loaded alongside this one.
+    #[serde(default)]
+    pub conflicts: Vec<String>,
+}
+
+/// Computes a load order for `mods` that respects every declared
+/// dependency, replacing the old `sort_by_key(priority)` step with a
+/// topological sort over the dependency graph (`priority` only breaks ties
+/// among mods with no relative ordering constraint between them). Returns an
+/// error naming the offending mod on a missing dependency, an unsatisfied
+/// version requirement, a declared conflict, or a dependency cycle.
+fn resolve_mod_load_order(mods: &[ModInfo]) -> Result<Vec<ModInfo>> {
+    let by_name: HashMap<&str, &ModInfo> = mods.iter().map(|m| (m.name.as_str(), m)).collect();
+
+    for mod_info in mods {
+        for (dep_name, requirement) in &mod_info.dependencies {
+            let dep = *by_name.get(dep_name.as_str()).ok_or_else(|| {
+                anyhow!(
+                    "Mod '{}' requires '{}', which is not present",
+                    mod_info.name,
+                    dep_name
+                )
+            })?;
+
+            let req = semver::VersionReq::parse(requirement).with_context(|| {
+                format!(
+                    "Mod '{}' declares an invalid semver requirement '{}' for '{}'",
+                    mod_info.name, requirement, dep_name
+                )
+            })?;
+            let dep_version = semver::Version::parse(&dep.version).with_context(|| {
+                format!(
+                    "Mod '{}' has an invalid semver version '{}'",
+                    dep.name, dep.version
+                )
+            })?;
+
+            if !req.matches(&dep_version) {
+                return Err(anyhow!(
+                    "Mod '{}' requires '{}' {}, but found version {}",
+                    mod_info.name,
+                    dep_name,
+                    requirement,
+                    dep.version
+                ));
+            }
+        }
+
+        for conflict in &mod_info.conflicts {
+            if by_name.contains_key(conflict.as_str()) {
+                return Err(anyhow!(
+                    "Mod '{}' conflicts with '{}', which is also present",
+                    mod_info.name,
+                    conflict
+                ));
+            }
+        }
+    }
+
+    // Kahn's algorithm: in-degree = number of not-yet-loaded dependencies.
+    let mut in_degree: HashMap<&str, usize> = mods
+        .iter()
+        .map(|m| (m.name.as_str(), m.dependencies.len()))
+        .collect();
+
+    let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+    for mod_info in mods {
+        for dep_name in mod_info.dependencies.keys() {
+            dependents
+                .entry(dep_name.as_str())
+                .or_default()
+                .push(mod_info.name.as_str());
+        }
+    }
+
+    let mut ready: Vec<&str> = in_degree
+        .iter()
+        .filter(|(_, &degree)| degree == 0)
+        .map(|(&name, _)| name)
+        .collect();
+    // Lower priority loads first, higher priority loads last and overrides
+    // it - the same tie-break rule the old priority-only sort used.
+    ready.sort_by_key(|name| (by_name[name].priority, *name));
+
+    let mut order = Vec::with_capacity(mods.len());
+    while !ready.is_empty() {
+        let name = ready.remove(0);
+        order.push(by_name[name].clone());
+
+        if let Some(waiting) = dependents.get(name) {
+            for &dependent in waiting {
+                let degree = in_degree.get_mut(dependent).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    ready.push(dependent);
+                }
+            }
+        }
+        ready.sort_by_key(|name| (by_name[name].priority, *name));
+    }
+
+    if order.len() != mods.len() {
+        let stuck: Vec<&str> = in_degree
+            .iter()
+            .filter(|(_, &degree)| degree > 0)
+            .map(|(&name, _)| name)
+            .collect();
+        return Err(anyhow!(
+            "Dependency cycle detected among mods: {}",
+            stuck.join(", ")
+        ));
+    }
+
+    Ok(order)
 }
 
 impl ModLoader {
@@ -221,20 +477,25 @@ impl ModLoader {
             }
         }
 
-        // Load mod info and sort by priority
-        let mut mods_with_priority = Vec::new();
+        // Load mod info for every discovered mod directory
+        let mut mod_infos = Vec::new();
+        let mut dirs_by_name = HashMap::new();
         for mod_dir in mod_dirs {
             if let Ok(mod_info) = loader.load_mod_info(&mod_dir) {
-                mods_with_priority.push((mod_info, mod_dir));
+                dirs_by_name.insert(mod_info.name.clone(), mod_dir);
+                mod_infos.push(mod_info);
             }
         }
 
-        // Sort by priority (higher priority loads last, overriding earlier mods)
-        mods_with_priority.sort_by_key(|(info, _)| info.priority);
+        // Resolve a load order satisfying every mod's declared dependencies,
+        // using `priority` only to break ties among mods with no relative
+        // ordering constraint between them.
+        let load_order = resolve_mod_load_order(&mod_infos)?;
 
-        // Apply mods in priority order
-        for (mod_info, mod_dir) in mods_with_priority {
-            loader.apply_mod(&mut base_config, &mod_dir, &mod_info)?;
+        // Apply mods in dependency order
+        for mod_info in load_order {
+            let mod_dir = &dirs_by_name[&mod_info.name];
+            loader.apply_mod(&mut base_config, mod_dir, &mod_info)?;
         }
 
         log::info!("Loaded {} mods", loader.loaded_mods.len());
@@ -283,6 +544,45 @@ impl ModLoader {
     pub fn get_loaded_mods(&self) -> &[ModInfo] {
         &self.loaded_mods
     }
+
+    /// Watches every mod directory under `mods_dir` and sends a freshly
+    /// rebuilt, merged `GameConfig` whenever any mod's data files change.
+    /// Follows the same validate-then-publish contract as
+    /// `DataLoader::watch_directory`: a reload that fails validation is
+    /// logged and dropped instead of crashing the watcher thread.
+    pub fn watch_directory<P: AsRef<Path>>(
+        mods_dir: P,
+    ) -> Result<Receiver<crate::resources::GameConfig>> {
+        let mods_path = mods_dir.as_ref().to_path_buf();
+        let (tx, rx) = mpsc::channel();
+        let (notify_tx, notify_rx) = mpsc::channel();
+
+        let mut watcher: RecommendedWatcher = notify::recommended_watcher(notify_tx)?;
+        watcher.watch(&mods_path, RecursiveMode::Recursive)?;
+
+        std::thread::spawn(move || {
+            let _watcher = watcher;
+
+            for event in notify_rx {
+                if event.is_err() {
+                    continue;
+                }
+
+                match Self::load_mods(&mods_path) {
+                    Ok(config) => {
+                        if tx.send(config).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        log::error!("Mod reload failed, keeping previous config live: {e}");
+                    }
+                }
+            }
+        });
+
+        Ok(rx)
+    }
 }
 
 /// Create default data files for a new project
@@ -305,6 +605,17 @@ pub fn create_default_data_files<P: AsRef<Path>>(data_dir: P) -> Result<()> {
     let mapgen_content = toml::to_string_pretty(&default_config.map_generation)?;
     fs::write(data_path.join("mapgen.toml"), mapgen_content)?;
 
+    let pheromones_content = toml::to_string_pretty(&default_config.pheromones)?;
+    fs::write(data_path.join("pheromones.toml"), pheromones_content)?;
+
+    let visibility_content = toml::to_string_pretty(&default_config.visibility)?;
+    fs::write(data_path.join("visibility.toml"), visibility_content)?;
+
+    let grid_content = toml::to_string_pretty(&GridFile {
+        shape: default_config.grid_shape,
+    })?;
+    fs::write(data_path.join("grid.toml"), grid_content)?;
+
     log::info!("Created default data files in {:?}", data_path);
     Ok(())
 }
@@ -346,6 +657,8 @@ mod tests {
             description: "A test mod".to_string(),
             author: "Test Author".to_string(),
             priority: 100,
+            dependencies: HashMap::new(),
+            conflicts: Vec::new(),
         };
 
         let mod_info_content = toml::to_string_pretty(&mod_info).unwrap();
@@ -362,4 +675,69 @@ mod tests {
         // Should still have default config since mod files are empty
         assert!(!config.buildings.is_empty());
     }
+
+    fn mod_info(name: &str, version: &str, priority: i32) -> ModInfo {
+        ModInfo {
+            name: name.to_string(),
+            version: version.to_string(),
+            description: String::new(),
+            author: String::new(),
+            priority,
+            dependencies: HashMap::new(),
+            conflicts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_mod_load_order_respects_dependencies_over_priority() {
+        // "B" has a higher priority than "A", so on priority alone it would
+        // load last, but "A" depends on it, so "B" must still load first.
+        let mut a = mod_info("A", "1.0.0", 0);
+        a.dependencies.insert("B".to_string(), "^1.0".to_string());
+        let b = mod_info("B", "1.2.0", 100);
+
+        let order = resolve_mod_load_order(&[a, b]).unwrap();
+        let names: Vec<&str> = order.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["B", "A"]);
+    }
+
+    #[test]
+    fn test_resolve_mod_load_order_missing_dependency() {
+        let mut a = mod_info("A", "1.0.0", 0);
+        a.dependencies.insert("Missing".to_string(), "^1.0".to_string());
+
+        let err = resolve_mod_load_order(&[a]).unwrap_err();
+        assert!(err.to_string().contains("Missing"));
+    }
+
+    #[test]
+    fn test_resolve_mod_load_order_unsatisfied_version() {
+        let mut a = mod_info("A", "1.0.0", 0);
+        a.dependencies.insert("B".to_string(), "^2.0".to_string());
+        let b = mod_info("B", "1.2.0", 0);
+
+        let err = resolve_mod_load_order(&[a, b]).unwrap_err();
+        assert!(err.to_string().contains("requires 'B'"));
+    }
+
+    #[test]
+    fn test_resolve_mod_load_order_conflict() {
+        let mut a = mod_info("A", "1.0.0", 0);
+        a.conflicts.push("B".to_string());
+        let b = mod_info("B", "1.0.0", 0);
+
+        let err = resolve_mod_load_order(&[a, b]).unwrap_err();
+        assert!(err.to_string().contains("conflicts"));
+    }
+
+    #[test]
+    fn test_resolve_mod_load_order_cycle() {
+        let mut a = mod_info("A", "1.0.0", 0);
+        a.dependencies.insert("B".to_string(), "*".to_string());
+        let mut b = mod_info("B", "1.0.0", 0);
+        b.dependencies.insert("A".to_string(), "*".to_string());
+
+        let err = resolve_mod_load_order(&[a, b]).unwrap_err();
+        assert!(err.to_string().contains("cycle"));
+    }
 }
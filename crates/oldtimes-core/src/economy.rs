@@ -1,7 +1,7 @@
 #[allow(unused_imports)]
 use crate::resources::{GameConfig, RecipeConfig};
 use anyhow::Result;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// Economic system for analyzing production chains and resource flows
 pub struct EconomyAnalyzer {
@@ -76,23 +76,47 @@ impl EconomyAnalyzer {
         analysis
     }
 
-    /// Calculate resource flow rates for a given production setup
+    /// Calculate resource flow rates for a given production setup, assuming
+    /// every recipe runs at full throughput.
     pub fn calculate_flow_rates(
         &self,
         production_rates: &HashMap<String, f32>,
+    ) -> HashMap<String, f32> {
+        self.calculate_flow_rates_with_productivity(production_rates, &HashMap::new())
+    }
+
+    /// Same as [`Self::calculate_flow_rates`], but scales each recipe's rate
+    /// by its entry in `productivity_factors` (keyed by recipe id, clamped to
+    /// `[0, 1]`, defaulting to `1.0` for a recipe with no entry) first, so a
+    /// power-starved producer's reduced throughput shows up as reduced flow
+    /// instead of the analysis assuming every building always runs at full
+    /// speed.
+    pub fn calculate_flow_rates_with_productivity(
+        &self,
+        production_rates: &HashMap<String, f32>,
+        productivity_factors: &HashMap<String, f32>,
     ) -> HashMap<String, f32> {
         let mut flow_rates: HashMap<String, f32> = HashMap::new();
 
         for (recipe_id, rate) in production_rates {
             if let Some(recipe) = self.config.recipes.get(recipe_id) {
+                let productivity = productivity_factors
+                    .get(recipe_id)
+                    .copied()
+                    .unwrap_or(1.0)
+                    .clamp(0.0, 1.0);
+                let effective_rate = rate * productivity;
+
                 // Add outputs
                 for (output, amount) in &recipe.outputs {
-                    *flow_rates.entry(output.clone()).or_insert(0.0) += rate * (*amount as f32);
+                    *flow_rates.entry(output.clone()).or_insert(0.0) +=
+                        effective_rate * (*amount as f32);
                 }
 
                 // Subtract inputs
                 for (input, amount) in &recipe.inputs {
-                    *flow_rates.entry(input.clone()).or_insert(0.0) -= rate * (*amount as f32);
+                    *flow_rates.entry(input.clone()).or_insert(0.0) -=
+                        effective_rate * (*amount as f32);
                 }
             }
         }
@@ -100,6 +124,99 @@ impl EconomyAnalyzer {
         flow_rates
     }
 
+    /// Monetizes a production setup's net flow at the given item `prices`
+    /// (an item missing from `prices` is valued at `1.0`): positive when the
+    /// setup is a net earner, negative when it's a net drain. Feeds
+    /// `systems::market_system`'s per-tick balance update and the
+    /// `suggest_profitable_chains` ranking below the same net-flow figures
+    /// `calculate_flow_rates` already computes for the cycle/source/sink graph.
+    pub fn value_flow(
+        &self,
+        production_rates: &HashMap<String, f32>,
+        prices: &HashMap<String, f32>,
+    ) -> f32 {
+        self.calculate_flow_rates(production_rates)
+            .iter()
+            .map(|(item, flow)| flow * prices.get(item).copied().unwrap_or(1.0))
+            .sum()
+    }
+
+    /// Ranks every recipe by margin per second at the given `prices`: output
+    /// value minus input value, divided by `production_time` so recipes with
+    /// different cycle lengths compare fairly, minus the upkeep of the
+    /// building type that runs it (`building_upkeep`, `0.0` for a type with
+    /// no entry). Lets modders and the balance CLI spot a
+    /// recipe that's actually a money sink despite looking productive in the
+    /// plain cycle/source/sink analysis.
+    pub fn suggest_profitable_chains(
+        &self,
+        prices: &HashMap<String, f32>,
+        building_upkeep: &HashMap<String, f32>,
+    ) -> Vec<ChainProfitability> {
+        let mut chains: Vec<ChainProfitability> = self
+            .config
+            .recipes
+            .iter()
+            .map(|(recipe_id, recipe)| {
+                let item_value = |items: &HashMap<String, u32>| -> f32 {
+                    items
+                        .iter()
+                        .map(|(item, amount)| {
+                            prices.get(item).copied().unwrap_or(1.0) * (*amount as f32)
+                        })
+                        .sum()
+                };
+                let upkeep = building_upkeep
+                    .get(&recipe.required_building)
+                    .copied()
+                    .unwrap_or(0.0);
+                let margin_per_second = (item_value(&recipe.outputs) - item_value(&recipe.inputs))
+                    / recipe.production_time
+                    - upkeep;
+
+                ChainProfitability {
+                    recipe_id: recipe_id.clone(),
+                    margin_per_second,
+                }
+            })
+            .collect();
+
+        chains.sort_by(|a, b| b.margin_per_second.total_cmp(&a.margin_per_second));
+        chains
+    }
+
+    /// Flags items whose required throughput (from [`Self::calculate_flow_rates`])
+    /// exceeds what the placed belt network can actually move. `belt_capacities`
+    /// is each item's total capacity summed across every `BeltSegment` carrying
+    /// it - the caller's job to aggregate from the live `BeltNetwork`, since
+    /// (like [`Self::value_flow`]) this analyzer only ever deals in config and
+    /// plain numbers, never live entity state.
+    pub fn validate_belt_capacity(
+        &self,
+        flow_rates: &HashMap<String, f32>,
+        belt_capacities: &HashMap<String, f32>,
+    ) -> Vec<UnderProvisionedLink> {
+        let mut under_provisioned: Vec<UnderProvisionedLink> = flow_rates
+            .iter()
+            .filter(|(_, &rate)| rate > 0.0)
+            .filter_map(|(item, &required_rate)| {
+                let available_capacity = belt_capacities.get(item).copied().unwrap_or(0.0);
+                if available_capacity < required_rate {
+                    Some(UnderProvisionedLink {
+                        item: item.clone(),
+                        required_rate,
+                        available_capacity,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        under_provisioned.sort_by(|a, b| a.item.cmp(&b.item));
+        under_provisioned
+    }
+
     /// Find optimal production ratios for a target output
     pub fn find_production_ratios(
         &self,
@@ -162,6 +279,435 @@ impl EconomyAnalyzer {
         Ok(())
     }
 
+    /// Find the first recipe that produces `item`, mirroring the "use the
+    /// first producer" convention of `calculate_ratios_recursive`.
+    fn first_producer(&self, item: &str) -> Option<(&String, &RecipeConfig)> {
+        self.config
+            .recipes
+            .iter()
+            .find(|(_, recipe)| recipe.outputs.contains_key(item))
+    }
+
+    /// Topologically order every item reachable from `target` (via chosen
+    /// producers) so each item appears only after every recipe that demands
+    /// it. Target comes first, raw resources last.
+    fn topological_order_from(&self, target: &str) -> Result<Vec<String>> {
+        let mut order = Vec::new();
+        let mut finished: HashSet<String> = HashSet::new();
+        let mut in_progress: HashSet<String> = HashSet::new();
+        self.visit_for_topo_order(target, &mut order, &mut finished, &mut in_progress)?;
+        order.reverse();
+        Ok(order)
+    }
+
+    fn visit_for_topo_order(
+        &self,
+        item: &str,
+        order: &mut Vec<String>,
+        finished: &mut HashSet<String>,
+        in_progress: &mut HashSet<String>,
+    ) -> Result<()> {
+        if finished.contains(item) {
+            return Ok(());
+        }
+        if in_progress.contains(item) {
+            return Err(anyhow::anyhow!(
+                "Circular dependency detected for item: {}",
+                item
+            ));
+        }
+
+        in_progress.insert(item.to_string());
+        if let Some((_, recipe)) = self.first_producer(item) {
+            for input_item in recipe.inputs.keys() {
+                self.visit_for_topo_order(input_item, order, finished, in_progress)?;
+            }
+        }
+        in_progress.remove(item);
+        finished.insert(item.to_string());
+        order.push(item.to_string());
+
+        Ok(())
+    }
+
+    /// Plan the exact integer batches needed to produce `quantity` units of
+    /// `target_item`, like the "space stoichiometry" reduction: walk the
+    /// recipe graph in topological order (target first, raw resources last)
+    /// so every item is processed exactly once, after every recipe that
+    /// demands it has already contributed to its total. For each item we
+    /// compute `batches = ceil(total_needed / output_per_batch)`, record the
+    /// leftover `batches * output_per_batch - total_needed` as surplus, and
+    /// propagate `batches * input_amount` to its inputs. Items with no
+    /// producer are accumulated as raw-resource totals.
+    pub fn plan_integer_production(
+        &self,
+        target_item: &str,
+        quantity: u64,
+    ) -> Result<ProductionPlan> {
+        if quantity == 0 {
+            return Ok(ProductionPlan::default());
+        }
+
+        let order = self.topological_order_from(target_item)?;
+
+        let mut demand: HashMap<String, u64> = HashMap::new();
+        demand.insert(target_item.to_string(), quantity);
+
+        let mut plan = ProductionPlan::default();
+
+        for item in order {
+            let total_needed = match demand.get(&item) {
+                Some(&amount) if amount > 0 => amount,
+                _ => continue,
+            };
+
+            match self.first_producer(&item) {
+                Some((recipe_id, recipe)) => {
+                    let output_per_batch = *recipe.outputs.get(&item).unwrap() as u64;
+                    let batch_count = (total_needed + output_per_batch - 1) / output_per_batch;
+                    let produced = batch_count * output_per_batch;
+
+                    *plan.batches.entry(recipe_id.clone()).or_insert(0) += batch_count;
+                    plan.surplus.insert(item.clone(), produced - total_needed);
+
+                    for (input_item, input_amount) in &recipe.inputs {
+                        *demand.entry(input_item.clone()).or_insert(0) +=
+                            batch_count * (*input_amount as u64);
+                    }
+                }
+                None => {
+                    *plan.raw_resources.entry(item.clone()).or_insert(0) += total_needed;
+                }
+            }
+        }
+
+        Ok(plan)
+    }
+
+    /// Binary-search the largest quantity of `target_item` that can be built
+    /// from `available` raw resources, reusing `plan_integer_production` as
+    /// the cost oracle. Raw cost is non-decreasing in quantity, so the
+    /// feasible quantities form a contiguous `0..=n` range.
+    pub fn max_output_from_stock(
+        &self,
+        target_item: &str,
+        available: &HashMap<String, u64>,
+    ) -> Result<MaxOutputPlan> {
+        let fits = |qty: u64| -> Result<bool> {
+            if qty == 0 {
+                return Ok(true);
+            }
+            let plan = self.plan_integer_production(target_item, qty)?;
+            Ok(plan
+                .raw_resources
+                .iter()
+                .all(|(item, needed)| available.get(item).copied().unwrap_or(0) >= *needed))
+        };
+
+        // Expand the search window until a quantity no longer fits (or we
+        // hit a sane cap, in which case that quantity is our answer).
+        let mut lo: u64 = 0;
+        let mut hi: u64 = 1;
+        while fits(hi)? {
+            lo = hi;
+            if hi >= u64::MAX / 2 {
+                break;
+            }
+            hi *= 2;
+        }
+
+        if fits(hi)? {
+            lo = hi;
+        } else {
+            while lo + 1 < hi {
+                let mid = lo + (hi - lo) / 2;
+                if fits(mid)? {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+        }
+
+        let plan = self.plan_integer_production(target_item, lo)?;
+        Ok(MaxOutputPlan { quantity: lo, plan })
+    }
+
+    /// Branch-and-bound search for the recipe/build mix that maximizes
+    /// cumulative `target_item` output over `horizon_ticks`, choosing among
+    /// *every* producer of each item in the chain (unlike
+    /// `find_production_ratios`/`plan_integer_production`, which always take
+    /// `producers.first()`), under a cap of `building_slots` total
+    /// buildings.
+    ///
+    /// Each tick performs exactly one action: build one more of an
+    /// affordable building type, run one batch of a recipe backed by an
+    /// already-built building, or idle. Raw resources (items with no
+    /// producer recipe anywhere in the chain) trickle into the stockpile at
+    /// a fixed rate of one unit per tick, so they are never hand-modeled as
+    /// their own buildings. Search states are `(tick, building counts,
+    /// stockpile)`; branches are pruned once a node has already matched its
+    /// optimistic bound (the largest single-batch target output of any
+    /// relevant recipe, every remaining tick, as if inputs and buildings were
+    /// never a constraint), and recurring states are memoized on
+    /// a quantized stockpile so near-identical branches collapse together.
+    ///
+    /// Note: building counts gate *which* recipes are runnable, but each
+    /// tick still only runs a single batch total - multiple buildings of the
+    /// same type don't yet unlock multiple simultaneous batches per tick.
+    pub fn optimize_production(
+        &self,
+        target_item: &str,
+        horizon_ticks: u64,
+        building_slots: u32,
+    ) -> Result<ProductionOptimizationResult> {
+        let feeding_items = self.items_feeding(target_item);
+        let relevant_recipes: Vec<(&String, &RecipeConfig)> = self
+            .config
+            .recipes
+            .iter()
+            .filter(|(_, recipe)| {
+                recipe
+                    .outputs
+                    .keys()
+                    .any(|item| feeding_items.contains(item))
+            })
+            .collect();
+        let raw_feed_items: Vec<String> = feeding_items
+            .iter()
+            .filter(|item| {
+                !self
+                    .config
+                    .recipes
+                    .values()
+                    .any(|recipe| recipe.outputs.contains_key(*item))
+            })
+            .cloned()
+            .collect();
+
+        let initial_state = SearchState {
+            tick: 0,
+            building_counts: BTreeMap::new(),
+            stockpile: BTreeMap::new(),
+        };
+
+        let mut memo: HashMap<MemoKey, (u64, Vec<ProductionAction>)> = HashMap::new();
+        let (achieved_output, actions) = self.search_production(
+            &initial_state,
+            target_item,
+            horizon_ticks,
+            building_slots,
+            &relevant_recipes,
+            &raw_feed_items,
+            &mut memo,
+        );
+
+        let mut recipe_mix: HashMap<String, u64> = HashMap::new();
+        let mut buildings_built: HashMap<String, u32> = HashMap::new();
+        for action in &actions {
+            match action {
+                ProductionAction::Run(recipe_id) => {
+                    *recipe_mix.entry(recipe_id.clone()).or_insert(0) += 1;
+                }
+                ProductionAction::Build(building_id) => {
+                    *buildings_built.entry(building_id.clone()).or_insert(0) += 1;
+                }
+                ProductionAction::Idle => {}
+            }
+        }
+
+        Ok(ProductionOptimizationResult {
+            recipe_mix,
+            buildings_built,
+            achieved_output,
+        })
+    }
+
+    /// Every item that (transitively, through any producer) feeds into
+    /// `target_item`, including `target_item` itself.
+    fn items_feeding(&self, target_item: &str) -> HashSet<String> {
+        let mut seen = HashSet::new();
+        let mut stack = vec![target_item.to_string()];
+
+        while let Some(item) = stack.pop() {
+            if !seen.insert(item.clone()) {
+                continue;
+            }
+            for recipe in self.config.recipes.values() {
+                if recipe.outputs.contains_key(&item) {
+                    for input in recipe.inputs.keys() {
+                        if !seen.contains(input) {
+                            stack.push(input.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        seen
+    }
+
+    /// Returns the best additional `target_item` output achievable from
+    /// `state` through the end of the horizon, along with the actions that
+    /// achieve it.
+    #[allow(clippy::too_many_arguments)]
+    fn search_production(
+        &self,
+        state: &SearchState,
+        target_item: &str,
+        horizon_ticks: u64,
+        building_slots: u32,
+        recipes: &[(&String, &RecipeConfig)],
+        raw_feed_items: &[String],
+        memo: &mut HashMap<MemoKey, (u64, Vec<ProductionAction>)>,
+    ) -> (u64, Vec<ProductionAction>) {
+        if state.tick >= horizon_ticks {
+            return (0, Vec::new());
+        }
+
+        let memo_key = state.memo_key();
+        if let Some(cached) = memo.get(&memo_key) {
+            return cached.clone();
+        }
+
+        // Optimistic bound: from here on, assume the best single batch of
+        // target output could be produced every remaining tick with no input
+        // constraints. Once a sibling branch has already matched it, trying
+        // more is pointless.
+        let best_batch_output = recipes
+            .iter()
+            .map(|(_, recipe)| recipe.outputs.get(target_item).copied().unwrap_or(0) as u64)
+            .max()
+            .unwrap_or(0);
+        let node_bound = (horizon_ticks - state.tick) * best_batch_output;
+        let mut best_output = 0u64;
+        let mut best_actions: Vec<ProductionAction> = Vec::new();
+
+        // Branch: idle.
+        {
+            let next_state = Self::advance_feed(state, raw_feed_items);
+            let (sub_output, sub_actions) = self.search_production(
+                &next_state,
+                target_item,
+                horizon_ticks,
+                building_slots,
+                recipes,
+                raw_feed_items,
+                memo,
+            );
+            if sub_output > best_output {
+                best_output = sub_output;
+                best_actions = std::iter::once(ProductionAction::Idle)
+                    .chain(sub_actions)
+                    .collect();
+            }
+        }
+
+        // Branch: construct one more building, if a slot and the cost are
+        // both available.
+        if best_output < node_bound && state.building_counts.values().sum::<u32>() < building_slots
+        {
+            for (building_id, building) in &self.config.buildings {
+                if best_output >= node_bound {
+                    break;
+                }
+                if !Self::can_afford(&state.stockpile, &building.construction_cost) {
+                    continue;
+                }
+
+                let mut next_state = Self::advance_feed(state, raw_feed_items);
+                Self::spend(&mut next_state.stockpile, &building.construction_cost);
+                *next_state
+                    .building_counts
+                    .entry(building_id.clone())
+                    .or_insert(0) += 1;
+
+                let (sub_output, sub_actions) = self.search_production(
+                    &next_state,
+                    target_item,
+                    horizon_ticks,
+                    building_slots,
+                    recipes,
+                    raw_feed_items,
+                    memo,
+                );
+                if sub_output > best_output {
+                    best_output = sub_output;
+                    best_actions = std::iter::once(ProductionAction::Build(building_id.clone()))
+                        .chain(sub_actions)
+                        .collect();
+                }
+            }
+        }
+
+        // Branch: run one batch of a recipe backed by an already-built
+        // building.
+        if best_output < node_bound {
+            for (recipe_id, recipe) in recipes {
+                if best_output >= node_bound {
+                    break;
+                }
+                let built = state
+                    .building_counts
+                    .get(&recipe.required_building)
+                    .copied()
+                    .unwrap_or(0);
+                if built == 0 || !Self::can_afford(&state.stockpile, &recipe.inputs) {
+                    continue;
+                }
+
+                let mut next_state = Self::advance_feed(state, raw_feed_items);
+                Self::spend(&mut next_state.stockpile, &recipe.inputs);
+                for (item, amount) in &recipe.outputs {
+                    *next_state.stockpile.entry(item.clone()).or_insert(0) += *amount as u64;
+                }
+                let produced_target = recipe.outputs.get(target_item).copied().unwrap_or(0) as u64;
+
+                let (sub_output, sub_actions) = self.search_production(
+                    &next_state,
+                    target_item,
+                    horizon_ticks,
+                    building_slots,
+                    recipes,
+                    raw_feed_items,
+                    memo,
+                );
+                let total = produced_target + sub_output;
+                if total > best_output {
+                    best_output = total;
+                    best_actions = std::iter::once(ProductionAction::Run((*recipe_id).clone()))
+                        .chain(sub_actions)
+                        .collect();
+                }
+            }
+        }
+
+        memo.insert(memo_key, (best_output, best_actions.clone()));
+        (best_output, best_actions)
+    }
+
+    fn advance_feed(state: &SearchState, raw_feed_items: &[String]) -> SearchState {
+        let mut next = state.clone();
+        next.tick += 1;
+        for item in raw_feed_items {
+            *next.stockpile.entry(item.clone()).or_insert(0) += 1;
+        }
+        next
+    }
+
+    fn can_afford(stockpile: &BTreeMap<String, u64>, cost: &HashMap<String, u32>) -> bool {
+        cost.iter()
+            .all(|(item, amount)| stockpile.get(item).copied().unwrap_or(0) >= *amount as u64)
+    }
+
+    fn spend(stockpile: &mut BTreeMap<String, u64>, cost: &HashMap<String, u32>) {
+        for (item, amount) in cost {
+            if let Some(balance) = stockpile.get_mut(item) {
+                *balance = balance.saturating_sub(*amount as u64);
+            }
+        }
+    }
+
     fn detect_cycles(&self, dependencies: &HashMap<String, HashSet<String>>) -> Vec<Vec<String>> {
         let mut cycles = Vec::new();
         let mut visited = HashSet::new();
@@ -233,6 +779,107 @@ impl EconomyAnalyzer {
     }
 }
 
+/// The exact-batch result of [`EconomyAnalyzer::plan_integer_production`]:
+/// how many batches of each recipe to run and how much of each raw resource
+/// that requires, so a UI can show "to build N furniture you need X wood, Y
+/// stone."
+#[derive(Debug, Clone, Default)]
+pub struct ProductionPlan {
+    pub batches: HashMap<String, u64>,
+    pub raw_resources: HashMap<String, u64>,
+    pub surplus: HashMap<String, u64>,
+}
+
+/// One item whose demanded flow rate outstrips the belt capacity placed for
+/// it, from [`EconomyAnalyzer::validate_belt_capacity`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnderProvisionedLink {
+    pub item: String,
+    pub required_rate: f32,
+    pub available_capacity: f32,
+}
+
+/// One recipe's ranking from [`EconomyAnalyzer::suggest_profitable_chains`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainProfitability {
+    pub recipe_id: String,
+    pub margin_per_second: f32,
+}
+
+/// The result of [`EconomyAnalyzer::max_output_from_stock`]: the largest
+/// quantity producible from the available raw resources, along with the
+/// plan that achieves it.
+#[derive(Debug, Clone)]
+pub struct MaxOutputPlan {
+    pub quantity: u64,
+    pub plan: ProductionPlan,
+}
+
+/// The result of [`EconomyAnalyzer::optimize_production`]: how many times
+/// to run each recipe, how many of each building to construct, and the
+/// cumulative target output that build order achieves.
+#[derive(Debug, Clone, Default)]
+pub struct ProductionOptimizationResult {
+    pub recipe_mix: HashMap<String, u64>,
+    pub buildings_built: HashMap<String, u32>,
+    pub achieved_output: u64,
+}
+
+/// A single tick's choice in `optimize_production`'s search tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ProductionAction {
+    Build(String),
+    Run(String),
+    Idle,
+}
+
+/// Exact `(tick, building counts, stockpile)` search state for
+/// `optimize_production`. Kept at full precision so transitions (affordability
+/// checks, spending) stay correct; only `memo_key` is quantized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SearchState {
+    tick: u64,
+    building_counts: BTreeMap<String, u32>,
+    stockpile: BTreeMap<String, u64>,
+}
+
+/// How close two stockpiles need to be to be treated as "the same" for
+/// memoization purposes.
+const STOCKPILE_QUANTUM: u64 = 4;
+/// Stockpile amounts above this are bucketed together regardless of exact
+/// value, since hoarding far beyond any single recipe's needs doesn't change
+/// what's reachable from here.
+const STOCKPILE_CAP: u64 = 64;
+
+impl SearchState {
+    /// A lossy, quantized key used only to collapse near-identical branches
+    /// in the memo table - the real `stockpile` above stays exact.
+    fn memo_key(&self) -> MemoKey {
+        let stockpile_bucket = self
+            .stockpile
+            .iter()
+            .filter(|(_, &amount)| amount > 0)
+            .map(|(item, &amount)| {
+                let bucketed = (amount.min(STOCKPILE_CAP) / STOCKPILE_QUANTUM) * STOCKPILE_QUANTUM;
+                (item.clone(), bucketed)
+            })
+            .collect();
+
+        MemoKey {
+            tick: self.tick,
+            building_counts: self.building_counts.clone(),
+            stockpile_bucket,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct MemoKey {
+    tick: u64,
+    building_counts: BTreeMap<String, u32>,
+    stockpile_bucket: BTreeMap<String, u64>,
+}
+
 #[derive(Debug)]
 pub struct ProductionGraphAnalysis {
     pub dependencies: HashMap<String, HashSet<String>>,
@@ -338,6 +985,283 @@ mod tests {
         assert_eq!(ratios.get("make_planks"), Some(&5.0));
     }
 
+    #[test]
+    fn test_plan_integer_production_uses_ceiling_batches_and_tracks_surplus() {
+        let mut config = GameConfig::default();
+
+        config.recipes.insert(
+            "make_planks".to_string(),
+            RecipeConfig {
+                name: "Make Planks".to_string(),
+                production_time: 5.0,
+                inputs: [("wood".to_string(), 1)].into(),
+                outputs: [("planks".to_string(), 2)].into(),
+                required_building: "sawmill".to_string(),
+            },
+        );
+
+        config.recipes.insert(
+            "make_furniture".to_string(),
+            RecipeConfig {
+                name: "Make Furniture".to_string(),
+                production_time: 10.0,
+                inputs: [("planks".to_string(), 3)].into(),
+                outputs: [("furniture".to_string(), 1)].into(),
+                required_building: "workshop".to_string(),
+            },
+        );
+
+        let analyzer = EconomyAnalyzer::new(config);
+        let plan = analyzer.plan_integer_production("furniture", 2).unwrap();
+
+        // 2 furniture needs 6 planks; 6 planks / 2 per batch = 3 batches of
+        // make_planks with no surplus, and 3 wood (1 per batch).
+        assert_eq!(plan.batches.get("make_furniture"), Some(&2));
+        assert_eq!(plan.batches.get("make_planks"), Some(&3));
+        assert_eq!(plan.raw_resources.get("wood"), Some(&3));
+        assert_eq!(plan.surplus.get("planks"), Some(&0));
+
+        // 1 furniture needs 3 planks; that only takes 2 batches (4 planks),
+        // leaving a surplus of 1 plank, and 2 wood.
+        let plan_one = analyzer.plan_integer_production("furniture", 1).unwrap();
+        assert_eq!(plan_one.batches.get("make_planks"), Some(&2));
+        assert_eq!(plan_one.surplus.get("planks"), Some(&1));
+        assert_eq!(plan_one.raw_resources.get("wood"), Some(&2));
+    }
+
+    #[test]
+    fn test_plan_integer_production_aggregates_demand_for_a_shared_intermediate() {
+        let mut config = GameConfig::default();
+
+        // Diamond: furniture needs both planks and nails, and nails are also
+        // made from planks, so "planks" is demanded from two consumers and
+        // must only be processed once, after both have contributed.
+        config.recipes.insert(
+            "make_planks".to_string(),
+            RecipeConfig {
+                name: "Make Planks".to_string(),
+                production_time: 5.0,
+                inputs: [("wood".to_string(), 1)].into(),
+                outputs: [("planks".to_string(), 1)].into(),
+                required_building: "sawmill".to_string(),
+            },
+        );
+
+        config.recipes.insert(
+            "make_nails".to_string(),
+            RecipeConfig {
+                name: "Make Nails".to_string(),
+                production_time: 2.0,
+                inputs: [("planks".to_string(), 1)].into(),
+                outputs: [("nails".to_string(), 4)].into(),
+                required_building: "workshop".to_string(),
+            },
+        );
+
+        config.recipes.insert(
+            "make_furniture".to_string(),
+            RecipeConfig {
+                name: "Make Furniture".to_string(),
+                production_time: 10.0,
+                inputs: [("planks".to_string(), 2), ("nails".to_string(), 4)].into(),
+                outputs: [("furniture".to_string(), 1)].into(),
+                required_building: "workshop".to_string(),
+            },
+        );
+
+        let analyzer = EconomyAnalyzer::new(config);
+        let plan = analyzer.plan_integer_production("furniture", 1).unwrap();
+
+        // 1 furniture needs 4 nails (1 batch of make_nails, consuming 1
+        // plank) plus 2 planks directly, for a total demand of 3 planks (3
+        // batches of make_planks) and 3 wood.
+        assert_eq!(plan.batches.get("make_nails"), Some(&1));
+        assert_eq!(plan.batches.get("make_planks"), Some(&3));
+        assert_eq!(plan.raw_resources.get("wood"), Some(&3));
+    }
+
+    #[test]
+    fn test_plan_integer_production_detects_cycles() {
+        let mut config = GameConfig::default();
+
+        config.recipes.insert(
+            "convert_a_to_b".to_string(),
+            RecipeConfig {
+                name: "A to B".to_string(),
+                production_time: 1.0,
+                inputs: [("a".to_string(), 1)].into(),
+                outputs: [("b".to_string(), 1)].into(),
+                required_building: "workshop".to_string(),
+            },
+        );
+
+        config.recipes.insert(
+            "convert_b_to_a".to_string(),
+            RecipeConfig {
+                name: "B to A".to_string(),
+                production_time: 1.0,
+                inputs: [("b".to_string(), 1)].into(),
+                outputs: [("a".to_string(), 1)].into(),
+                required_building: "workshop".to_string(),
+            },
+        );
+
+        let analyzer = EconomyAnalyzer::new(config);
+        assert!(analyzer.plan_integer_production("a", 1).is_err());
+    }
+
+    #[test]
+    fn test_max_output_from_stock_binary_searches_the_largest_feasible_quantity() {
+        let mut config = GameConfig::default();
+
+        config.recipes.insert(
+            "make_planks".to_string(),
+            RecipeConfig {
+                name: "Make Planks".to_string(),
+                production_time: 5.0,
+                inputs: [("wood".to_string(), 1)].into(),
+                outputs: [("planks".to_string(), 2)].into(),
+                required_building: "sawmill".to_string(),
+            },
+        );
+
+        config.recipes.insert(
+            "make_furniture".to_string(),
+            RecipeConfig {
+                name: "Make Furniture".to_string(),
+                production_time: 10.0,
+                inputs: [("planks".to_string(), 3)].into(),
+                outputs: [("furniture".to_string(), 1)].into(),
+                required_building: "workshop".to_string(),
+            },
+        );
+
+        let analyzer = EconomyAnalyzer::new(config);
+        let mut available = HashMap::new();
+        available.insert("wood".to_string(), 5);
+
+        // 3 furniture needs 9 planks (5 batches of 2, 1 surplus) = 5 wood;
+        // 4 furniture needs 12 planks (6 batches) = 6 wood, which overshoots.
+        let result = analyzer
+            .max_output_from_stock("furniture", &available)
+            .unwrap();
+        assert_eq!(result.quantity, 3);
+        assert_eq!(result.plan.raw_resources.get("wood"), Some(&5));
+
+        available.insert("wood".to_string(), 0);
+        let none_result = analyzer
+            .max_output_from_stock("furniture", &available)
+            .unwrap();
+        assert_eq!(none_result.quantity, 0);
+        assert!(none_result.plan.raw_resources.is_empty());
+    }
+
+    #[test]
+    fn test_optimize_production_prefers_the_better_of_two_competing_producers() {
+        use crate::resources::BuildingConfig;
+
+        let mut config = GameConfig::default();
+
+        config.buildings.insert(
+            "slow_shop".to_string(),
+            BuildingConfig {
+                name: "Slow Workshop".to_string(),
+                construction_time: 1.0,
+                construction_cost: HashMap::new(),
+                worker_capacity: 1,
+                stockpile_capacity: 10,
+                size: (1, 1),
+                prerequisites: Vec::new(),
+                power_generation: 0.0,
+                power_demand: 0.0,
+            },
+        );
+        config.buildings.insert(
+            "fast_shop".to_string(),
+            BuildingConfig {
+                name: "Fast Workshop".to_string(),
+                construction_time: 1.0,
+                construction_cost: HashMap::new(),
+                worker_capacity: 1,
+                stockpile_capacity: 10,
+                size: (1, 1),
+                prerequisites: Vec::new(),
+                power_generation: 0.0,
+                power_demand: 0.0,
+            },
+        );
+
+        config.recipes.insert(
+            "make_tool_slow".to_string(),
+            RecipeConfig {
+                name: "Make Tool (slow)".to_string(),
+                production_time: 1.0,
+                inputs: HashMap::new(),
+                outputs: [("tool".to_string(), 1)].into(),
+                required_building: "slow_shop".to_string(),
+            },
+        );
+        config.recipes.insert(
+            "make_tool_fast".to_string(),
+            RecipeConfig {
+                name: "Make Tool (fast)".to_string(),
+                production_time: 1.0,
+                inputs: HashMap::new(),
+                outputs: [("tool".to_string(), 3)].into(),
+                required_building: "fast_shop".to_string(),
+            },
+        );
+
+        let analyzer = EconomyAnalyzer::new(config);
+
+        // Only one building slot, so the search has to choose fast_shop over
+        // slow_shop instead of blindly taking `producers.first()`.
+        let result = analyzer.optimize_production("tool", 4, 1).unwrap();
+
+        assert_eq!(result.achieved_output, 9);
+        assert_eq!(result.buildings_built.get("fast_shop"), Some(&1));
+        assert_eq!(result.buildings_built.get("slow_shop"), None);
+        assert_eq!(result.recipe_mix.get("make_tool_fast"), Some(&3));
+    }
+
+    #[test]
+    fn test_optimize_production_yields_nothing_with_no_building_slots() {
+        use crate::resources::BuildingConfig;
+
+        let mut config = GameConfig::default();
+        config.buildings.insert(
+            "fast_shop".to_string(),
+            BuildingConfig {
+                name: "Fast Workshop".to_string(),
+                construction_time: 1.0,
+                construction_cost: HashMap::new(),
+                worker_capacity: 1,
+                stockpile_capacity: 10,
+                size: (1, 1),
+                prerequisites: Vec::new(),
+                power_generation: 0.0,
+                power_demand: 0.0,
+            },
+        );
+        config.recipes.insert(
+            "make_tool_fast".to_string(),
+            RecipeConfig {
+                name: "Make Tool (fast)".to_string(),
+                production_time: 1.0,
+                inputs: HashMap::new(),
+                outputs: [("tool".to_string(), 3)].into(),
+                required_building: "fast_shop".to_string(),
+            },
+        );
+
+        let analyzer = EconomyAnalyzer::new(config);
+        let result = analyzer.optimize_production("tool", 4, 0).unwrap();
+
+        assert_eq!(result.achieved_output, 0);
+        assert!(result.buildings_built.is_empty());
+        assert!(result.recipe_mix.is_empty());
+    }
+
     #[test]
     fn test_flow_rates() {
         let config = GameConfig::default();
@@ -351,4 +1275,133 @@ mod tests {
         // Should produce 4 wood per second (2 recipes * 2 wood per recipe)
         assert_eq!(flow_rates.get("wood"), Some(&4.0));
     }
+
+    #[test]
+    fn test_flow_rates_with_productivity_scales_down_brownout_recipes() {
+        let config = GameConfig::default();
+        let analyzer = EconomyAnalyzer::new(config);
+
+        let mut production_rates = HashMap::new();
+        production_rates.insert("harvest_wood".to_string(), 2.0);
+
+        let mut productivity_factors = HashMap::new();
+        productivity_factors.insert("harvest_wood".to_string(), 0.5);
+
+        let flow_rates = analyzer
+            .calculate_flow_rates_with_productivity(&production_rates, &productivity_factors);
+
+        // Half the power satisfaction halves the effective rate: 2 recipes/s
+        // * 0.5 productivity * 2 wood/recipe = 2 wood/s, not 4.
+        assert_eq!(flow_rates.get("wood"), Some(&2.0));
+    }
+
+    #[test]
+    fn test_value_flow_monetizes_net_flow_at_given_prices() {
+        let config = GameConfig::default();
+        let analyzer = EconomyAnalyzer::new(config);
+
+        let mut production_rates = HashMap::new();
+        production_rates.insert("harvest_wood".to_string(), 2.0); // +4 wood/s
+
+        let mut prices = HashMap::new();
+        prices.insert("wood".to_string(), 3.0);
+
+        // 4 wood/s produced at 3.0/unit is worth 12.0/s, a pure earner since
+        // harvest_wood has no inputs to weigh against it.
+        assert_eq!(analyzer.value_flow(&production_rates, &prices), 12.0);
+    }
+
+    #[test]
+    fn test_suggest_profitable_chains_ranks_by_margin_and_penalizes_upkeep() {
+        use crate::resources::BuildingConfig;
+
+        let mut config = GameConfig::default();
+        config.recipes.clear();
+        config.buildings.clear();
+
+        config.buildings.insert(
+            "workshop".to_string(),
+            BuildingConfig {
+                name: "Workshop".to_string(),
+                construction_time: 1.0,
+                construction_cost: HashMap::new(),
+                worker_capacity: 1,
+                stockpile_capacity: 10,
+                size: (1, 1),
+                prerequisites: Vec::new(),
+                power_generation: 0.0,
+                power_demand: 0.0,
+            },
+        );
+
+        // Cheap input, valuable output, fast cycle.
+        config.recipes.insert(
+            "make_tool".to_string(),
+            RecipeConfig {
+                name: "Make Tool".to_string(),
+                production_time: 1.0,
+                inputs: [("scrap".to_string(), 1)].into(),
+                outputs: [("tool".to_string(), 1)].into(),
+                required_building: "workshop".to_string(),
+            },
+        );
+
+        // Same value ratio and the same building, so it earns the same margin.
+        config.recipes.insert(
+            "make_trinket".to_string(),
+            RecipeConfig {
+                name: "Make Trinket".to_string(),
+                production_time: 1.0,
+                inputs: [("scrap".to_string(), 1)].into(),
+                outputs: [("trinket".to_string(), 1)].into(),
+                required_building: "workshop".to_string(),
+            },
+        );
+
+        let analyzer = EconomyAnalyzer::new(config);
+
+        let mut prices = HashMap::new();
+        prices.insert("scrap".to_string(), 1.0);
+        prices.insert("tool".to_string(), 5.0);
+        prices.insert("trinket".to_string(), 5.0);
+
+        let mut building_upkeep = HashMap::new();
+        building_upkeep.insert("workshop".to_string(), 3.0);
+
+        // Rank once without upkeep and once with upkeep charged on the
+        // workshop both recipes run in.
+        let ranked = analyzer.suggest_profitable_chains(&prices, &HashMap::new());
+        let with_upkeep = analyzer.suggest_profitable_chains(&prices, &building_upkeep);
+
+        assert_eq!(ranked[0].margin_per_second, 4.0);
+        assert_eq!(ranked[1].margin_per_second, 4.0);
+
+        // Upkeep drags every recipe on that building down by the same amount.
+        assert_eq!(with_upkeep[0].margin_per_second, 1.0);
+        assert_eq!(with_upkeep[1].margin_per_second, 1.0);
+    }
+
+    #[test]
+    fn test_validate_belt_capacity_flags_only_under_provisioned_items() {
+        let config = GameConfig::default();
+        let analyzer = EconomyAnalyzer::new(config);
+
+        let mut flow_rates = HashMap::new();
+        flow_rates.insert("wood".to_string(), 4.0);
+        flow_rates.insert("planks".to_string(), 2.0);
+        // A negative flow rate is an input being consumed, not shipped out -
+        // it should never show up as a belt requirement.
+        flow_rates.insert("stone".to_string(), -1.0);
+
+        let mut belt_capacities = HashMap::new();
+        belt_capacities.insert("wood".to_string(), 3.0); // under-provisioned
+        belt_capacities.insert("planks".to_string(), 2.0); // exactly sufficient
+
+        let under_provisioned = analyzer.validate_belt_capacity(&flow_rates, &belt_capacities);
+
+        assert_eq!(under_provisioned.len(), 1);
+        assert_eq!(under_provisioned[0].item, "wood");
+        assert_eq!(under_provisioned[0].required_rate, 4.0);
+        assert_eq!(under_provisioned[0].available_capacity, 3.0);
+    }
 }
@@ -37,6 +37,10 @@ pub struct TransferResourceEvent {
 pub struct TaskCompletedEvent {
     pub worker: Entity,
     pub task_type: String,
+    /// The stockpile/flag the completed task delivered into or picked up
+    /// from, where applicable (e.g. `"pickup"`/`"delivery"`). `None` for
+    /// task types with no single associated entity (`"work"`, `"construction"`).
+    pub destination: Option<Entity>,
 }
 
 /// Event for building construction completion
@@ -62,9 +66,13 @@ pub struct PathfindingRequestEvent {
     pub from: Position,
     pub to: Position,
     pub priority: PathfindingPriority,
+    /// Which pheromone trail (if any) this route should both follow and reinforce.
+    pub channel: Option<crate::resources::PheromoneChannel>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Ordered low to high so a default `#[derive(Ord)]` sorts a batch of
+/// requests into the processing order `pathfinding_system`'s scheduler wants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum PathfindingPriority {
     Low,
     Normal,
@@ -72,11 +80,25 @@ pub enum PathfindingPriority {
     Critical,
 }
 
+/// Event for a pathfinding request that could not be resolved to any path
+/// (e.g. the goal is cut off from the start by blocked tiles).
+#[derive(Event, Debug, Clone)]
+pub struct PathfindingFailedEvent {
+    pub entity: Entity,
+    pub from: Position,
+    pub to: Position,
+}
+
 /// Event for map changes that invalidate pathfinding cache
 #[derive(Event, Debug, Clone)]
 pub struct MapChangedEvent {
     pub position: Position,
     pub change_type: MapChangeType,
+    /// Every tile whose passability or movement cost actually changed, so
+    /// cache invalidation and path repair can stay local to them instead of
+    /// wiping every cached route. Usually just `[position]`, but a
+    /// multi-tile edit (e.g. a footprint) can list more than one.
+    pub affected_tiles: Vec<Position>,
 }
 
 #[derive(Debug, Clone)]
@@ -126,6 +148,22 @@ pub struct LoadModEvent {
     pub mod_path: String,
 }
 
+/// Event posted by a building that needs more of an input item, kicking off
+/// flag-by-flag routing through the logistics network.
+#[derive(Event, Debug, Clone, Serialize, Deserialize)]
+pub struct WareRequestEvent {
+    pub requesting_building: Entity,
+    pub item: String,
+    pub amount: u32,
+}
+
+/// Event to build a `RoadSegment` connecting two buildings' flags.
+#[derive(Event, Debug, Clone, Serialize, Deserialize)]
+pub struct BuildRoadEvent {
+    pub from_building: Entity,
+    pub to_building: Entity,
+}
+
 /// Event for configuration reload (hot-reload)
 #[derive(Event, Debug, Clone)]
 pub struct ReloadConfigEvent;
\ No newline at end of file
@@ -0,0 +1,114 @@
+//! Coordinate math for the two tile layouts a map can use. `Position` stays
+//! `(i32, i32)` either way; under `GridShape::Hex` those two numbers are axial
+//! `(q, r)` coordinates instead of rectangular offsets.
+
+use crate::components::Position;
+use bevy::prelude::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// Which tile layout a map uses.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Default,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[archive(check_bytes)]
+pub enum GridShape {
+    #[default]
+    Square,
+    Hex,
+}
+
+/// Pixel size of one hex tile, analogous to the client's `TILE_SIZE` for the
+/// square grid.
+pub const HEX_SIZE: f32 = 32.0;
+
+/// The six axial neighbor offsets of a pointy-top hex, clockwise from east.
+pub const HEX_NEIGHBOR_OFFSETS: [(i32, i32); 6] =
+    [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+
+/// Enumerates the six axial neighbors of a hex tile.
+pub fn hex_neighbors(pos: Position) -> impl Iterator<Item = Position> {
+    HEX_NEIGHBOR_OFFSETS
+        .into_iter()
+        .map(move |(dq, dr)| Position::new(pos.x + dq, pos.y + dr))
+}
+
+/// Converts axial (q, r) grid coordinates to pixel coordinates for a
+/// pointy-top hex layout.
+pub fn hex_grid_to_world(pos: Position) -> Vec2 {
+    let q = pos.x as f32;
+    let r = pos.y as f32;
+    let x = HEX_SIZE * (3f32.sqrt() * q + 3f32.sqrt() / 2.0 * r);
+    let y = HEX_SIZE * (1.5 * r);
+    Vec2::new(x, y)
+}
+
+/// Converts pixel coordinates to the nearest axial hex: applies the inverse
+/// layout matrix, then cube-rounds the fractional result to snap to a whole
+/// hex.
+pub fn hex_world_to_grid(world: Vec2) -> Position {
+    let q = (3f32.sqrt() / 3.0 * world.x - world.y / 3.0) / HEX_SIZE;
+    let r = (2.0 / 3.0 * world.y) / HEX_SIZE;
+    let (q, r) = cube_round(q, r);
+    Position::new(q, r)
+}
+
+/// Rounds fractional axial coordinates to the nearest whole hex by rounding
+/// in cube space and correcting whichever component drifted the most.
+fn cube_round(q: f32, r: f32) -> (i32, i32) {
+    let x = q;
+    let z = r;
+    let y = -x - z;
+
+    let mut rx = x.round();
+    let mut ry = y.round();
+    let mut rz = z.round();
+
+    let x_diff = (rx - x).abs();
+    let y_diff = (ry - y).abs();
+    let z_diff = (rz - z).abs();
+
+    if x_diff > y_diff && x_diff > z_diff {
+        rx = -ry - rz;
+    } else if y_diff > z_diff {
+        ry = -rx - rz;
+    } else {
+        rz = -rx - ry;
+    }
+
+    (rx as i32, rz as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_neighbors_has_six_distinct_tiles() {
+        let center = Position::new(0, 0);
+        let neighbors: Vec<Position> = hex_neighbors(center).collect();
+        assert_eq!(neighbors.len(), 6);
+        assert!(!neighbors.contains(&center));
+    }
+
+    #[test]
+    fn hex_round_trip_recovers_the_same_tile() {
+        for &pos in &[
+            Position::new(0, 0),
+            Position::new(3, -2),
+            Position::new(-5, 4),
+        ] {
+            let world = hex_grid_to_world(pos);
+            assert_eq!(hex_world_to_grid(world), pos);
+        }
+    }
+}
@@ -13,13 +13,16 @@
 
 pub mod assets;
 pub mod components;
+pub mod config_cache;
 pub mod data;
 pub mod economy;
 pub mod events;
+pub mod grid;
 pub mod map;
 pub mod pathfinding;
 pub mod resources;
 pub mod save;
+pub mod scripting;
 pub mod simulation;
 pub mod systems;
 
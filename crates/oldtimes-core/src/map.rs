@@ -1,8 +1,11 @@
 use crate::{
-    components::{Tile, TileType},
-    resources::MapData,
+    components::{Position, Tile, TileType},
+    resources::{MapData, MapGenerationConfig},
 };
 use noise::{NoiseFn, Perlin};
+use pathfinding::prelude::astar;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::{HashSet, VecDeque};
 
 /// Generate a demo map with varied terrain
 pub fn generate_demo_map(map: &mut MapData) {
@@ -73,44 +76,362 @@ fn add_demo_roads(map: &mut MapData) {
     log::debug!("Added demo roads to map");
 }
 
-/// Generate map from configuration
-pub fn generate_map_from_config(config: &crate::resources::MapGenerationConfig) -> MapData {
+/// Deterministically generate a map from a `MapGenerationConfig`.
+///
+/// The same seed always produces byte-identical `MapData`: elevation, forest
+/// and stone all come from seeded Perlin octaves (no thread-local RNG), and
+/// water patches are flood-filled from seed points drawn from a `StdRng`
+/// seeded with `config.seed`. Layers are applied in a fixed order
+/// (elevation/forest/stone, then water) so results don't depend on anything
+/// but the config.
+pub fn generate_map(config: &MapGenerationConfig) -> MapData {
     let mut map = MapData::new(config.width, config.height);
-    
-    let perlin = Perlin::new(config.seed as u32);
-    
+
+    // Distinct seeds per layer so they don't come out as rescaled copies of
+    // one another.
+    let terrain_base_noise = Perlin::new(config.seed as u32);
+    let terrain_alt_noise = Perlin::new((config.seed as u32).wrapping_add(1));
+    let persist_noise = Perlin::new((config.seed as u32).wrapping_add(2));
+    let mountain_noise = Perlin::new((config.seed as u32).wrapping_add(3));
+    let moisture_noise = Perlin::new((config.seed as u32).wrapping_add(4));
+
     for y in 0..config.height {
         for x in 0..config.width {
             let nx = x as f64 / config.width as f64;
             let ny = y as f64 / config.height as f64;
-            
-            let elevation = perlin.get([nx * 4.0, ny * 4.0]) as f32;
-            let forest_noise = perlin.get([nx * 8.0, ny * 8.0]) as f32;
-            let stone_noise = perlin.get([nx * 6.0, ny * 6.0]) as f32;
-            
-            let tile_type = if elevation < -0.3 {
+
+            // Blend a low-frequency base elevation with a higher-frequency
+            // detail layer, weighted by a separate persistence mask so the
+            // detail only shows through in some regions instead of uniformly
+            // roughening the whole map.
+            let terrain_base = fractal_noise(&terrain_base_noise, nx, ny, config, 1.0);
+            let terrain_alt = fractal_noise(&terrain_alt_noise, nx, ny, config, 4.0);
+            let persist = (fractal_noise(&persist_noise, nx, ny, config, 0.5) + 1.0) / 2.0;
+            let elevation = terrain_base * (1.0 - persist) + terrain_alt * persist;
+
+            let mountain = fractal_noise(&mountain_noise, nx, ny, config, 3.0);
+            // Remap from [-1, 1] to [0, 1] so density configs read as plain
+            // "fraction of the map covered" thresholds.
+            let moisture = (fractal_noise(&moisture_noise, nx, ny, config, 2.0) + 1.0) / 2.0;
+
+            let tile_type = if elevation < -0.5 {
                 TileType::Water
-            } else if stone_noise > (1.0 - config.stone_density * 2.0) {
+            } else if mountain > 1.0 - config.stone_density {
                 TileType::Stone
-            } else if forest_noise > (1.0 - config.forest_density * 2.0) {
+            } else if moisture < config.forest_density && elevation < 0.5 {
                 TileType::Forest
             } else {
                 TileType::Grass
             };
-            
+
             let tile = Tile {
                 tile_type,
-                elevation: ((elevation + 1.0) * 127.5) as u8,
+                elevation: (((elevation + 1.0) / 2.0).clamp(0.0, 1.0) * 255.0) as u8,
             };
-            
+
             map.set_tile(x as i32, y as i32, tile);
         }
     }
-    
+
+    place_water_patches(&mut map, config);
+
     log::info!("Generated map {}x{} with seed {}", config.width, config.height, config.seed);
     map
 }
 
+/// Sums `config.octaves` octaves of Perlin noise at the given base frequency
+/// (scaled by `frequency_scale` so different layers sample at different
+/// rates), each successive octave scaled by `config.lacunarity` in frequency
+/// and `config.gain` in amplitude, and normalizes the result back to
+/// roughly `[-1, 1]`.
+fn fractal_noise(
+    noise: &Perlin,
+    nx: f64,
+    ny: f64,
+    config: &MapGenerationConfig,
+    frequency_scale: f64,
+) -> f32 {
+    let octaves = config.octaves.max(1);
+    let mut amplitude = 1.0;
+    let mut frequency = frequency_scale * 4.0;
+    let mut sum = 0.0;
+    let mut max_amplitude = 0.0;
+
+    for _ in 0..octaves {
+        sum += noise.get([nx * frequency, ny * frequency]) * amplitude;
+        max_amplitude += amplitude;
+        amplitude *= config.gain as f64;
+        frequency *= config.lacunarity as f64;
+    }
+
+    (sum / max_amplitude) as f32
+}
+
+/// Seed `config.water_patches` water bodies and flood-fill each outward a few
+/// tiles, using an RNG seeded from `config.seed` so the result is reproducible.
+fn place_water_patches(map: &mut MapData, config: &MapGenerationConfig) {
+    const PATCH_RADIUS: i32 = 4;
+
+    let mut rng = StdRng::seed_from_u64(config.seed);
+
+    for _ in 0..config.water_patches {
+        let start = Position::new(
+            rng.gen_range(0..map.width as i32),
+            rng.gen_range(0..map.height as i32),
+        );
+
+        let mut frontier = VecDeque::new();
+        let mut visited = HashSet::new();
+        frontier.push_back(start);
+        visited.insert(start);
+
+        while let Some(pos) = frontier.pop_front() {
+            let Some(tile) = map.get_tile(pos.x, pos.y) else {
+                continue;
+            };
+            map.set_tile(pos.x, pos.y, Tile {
+                tile_type: TileType::Water,
+                elevation: tile.elevation,
+            });
+
+            for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+                let next = Position::new(pos.x + dx, pos.y + dy);
+                let within_radius =
+                    (next.x - start.x).abs() + (next.y - start.y).abs() <= PATCH_RADIUS;
+                if within_radius && !visited.contains(&next) && rng.gen_bool(0.6) {
+                    visited.insert(next);
+                    frontier.push_back(next);
+                }
+            }
+        }
+    }
+
+    log::debug!("Placed {} water patches", config.water_patches);
+}
+
+/// A building plot stamped by `generate_town`: its footprint and the
+/// building role it was reserved for, ready for the game layer to spawn a
+/// matching `Building` entity on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TownPlot {
+    pub position: Position,
+    pub size: (u32, u32),
+    pub role: String,
+}
+
+const TOWN_BUILDING_ROLES: [&str; 5] = ["lumberjack", "farm", "mill", "bakery", "quarry"];
+
+/// Stamps a coherent starting village onto an already-generated map: `count`
+/// non-overlapping rectangular plots placed by rejection sampling inside a
+/// settlement region, each wired to a central spine road. Deterministic for
+/// a given `seed`.
+pub fn generate_town(map: &mut MapData, seed: u64, count: u32) -> Vec<TownPlot> {
+    const MAX_ATTEMPTS_PER_PLOT: u32 = 200;
+    const MIN_PLOT_SIZE: u32 = 2;
+    const MAX_PLOT_SIZE: u32 = 4;
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut occupied: HashSet<(i32, i32)> = HashSet::new();
+    let mut plots = Vec::new();
+
+    // Reserve a rectangular settlement region in the middle of the map,
+    // leaving a margin so plots never hug the edge.
+    let margin_x = (map.width / 8).max(1) as i32;
+    let margin_y = (map.height / 8).max(1) as i32;
+    let region_min = (margin_x, margin_y);
+    let region_max = (
+        map.width as i32 - margin_x - MAX_PLOT_SIZE as i32,
+        map.height as i32 - margin_y - MAX_PLOT_SIZE as i32,
+    );
+
+    if region_max.0 <= region_min.0 || region_max.1 <= region_min.1 {
+        log::warn!("Map too small to fit a town, skipping generation");
+        return plots;
+    }
+
+    for i in 0..count {
+        let role = TOWN_BUILDING_ROLES[i as usize % TOWN_BUILDING_ROLES.len()];
+
+        for _ in 0..MAX_ATTEMPTS_PER_PLOT {
+            let size = (
+                rng.gen_range(MIN_PLOT_SIZE..=MAX_PLOT_SIZE),
+                rng.gen_range(MIN_PLOT_SIZE..=MAX_PLOT_SIZE),
+            );
+            let x = rng.gen_range(region_min.0..=region_max.0);
+            let y = rng.gen_range(region_min.1..=region_max.1);
+
+            if !is_suitable_for_building(map, x, y, size) {
+                continue;
+            }
+            if plot_overlaps(&occupied, x, y, size) {
+                continue;
+            }
+
+            for dy in 0..size.1 as i32 {
+                for dx in 0..size.0 as i32 {
+                    occupied.insert((x + dx, y + dy));
+                }
+            }
+
+            // Door on the south edge, centered on the plot's width.
+            let door = Position::new(x + size.0 as i32 / 2, y + size.1 as i32);
+
+            plots.push(TownPlot {
+                position: Position::new(x, y),
+                size,
+                role: role.to_string(),
+            });
+
+            // Carve this plot's door straight to the spine row so every
+            // building connects to the network as it's placed.
+            let spine_y = region_min.1 + (region_max.1 - region_min.1) / 2;
+            carve_l_shaped_road(map, door, Position::new(door.x, spine_y));
+
+            break;
+        }
+    }
+
+    // Pave the spine row itself across every door column so the plots'
+    // roads join into one network.
+    let spine_y = region_min.1 + (region_max.1 - region_min.1) / 2;
+    let door_xs = plots.iter().map(|plot| plot.position.x + plot.size.0 as i32 / 2);
+    if let (Some(min_x), Some(max_x)) = (door_xs.clone().min(), door_xs.max()) {
+        carve_l_shaped_road(map, Position::new(min_x, spine_y), Position::new(max_x, spine_y));
+    }
+
+    log::info!("Generated town with {} of {} requested plots", plots.len(), count);
+    plots
+}
+
+fn plot_overlaps(occupied: &HashSet<(i32, i32)>, x: i32, y: i32, size: (u32, u32)) -> bool {
+    for dy in 0..size.1 as i32 {
+        for dx in 0..size.0 as i32 {
+            if occupied.contains(&(x + dx, y + dy)) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Carves an L-shaped road (horizontal leg then vertical leg) between two
+/// points, skipping any `Water` tile it crosses rather than paving over it.
+fn carve_l_shaped_road(map: &mut MapData, from: Position, to: Position) {
+    let mut x = from.x;
+    while x != to.x {
+        pave_road(map, x, from.y);
+        x += (to.x - x).signum();
+    }
+
+    let mut y = from.y;
+    while y != to.y {
+        pave_road(map, to.x, y);
+        y += (to.y - y).signum();
+    }
+
+    pave_road(map, to.x, to.y);
+}
+
+fn pave_road(map: &mut MapData, x: i32, y: i32) {
+    if let Some(tile) = map.get_tile(x, y) {
+        if !matches!(tile.tile_type, TileType::Water) {
+            map.set_tile(x, y, Tile {
+                tile_type: TileType::Road,
+                elevation: tile.elevation,
+            });
+        }
+    }
+}
+
+/// Grows a road network connecting every building `entrance` while
+/// minimizing total extra road length: repeatedly picks whichever
+/// unconnected entrance shortens the network the most (by `cost - distance`
+/// against its nearest already-connected entrance) and carves that path
+/// before recomputing the remaining candidates against the updated network.
+pub fn connect_buildings_with_roads(map: &mut MapData, entrances: &[(i32, i32)]) {
+    if entrances.len() < 2 {
+        return;
+    }
+
+    let mut connected: Vec<(i32, i32)> = vec![entrances[0]];
+    let mut unconnected: Vec<(i32, i32)> = entrances[1..].to_vec();
+
+    while !unconnected.is_empty() {
+        // For every unconnected flag, find its cheapest path to any
+        // already-connected flag, and how much that path overshoots the
+        // straight-line distance.
+        let mut best_candidate: Option<(usize, Vec<Position>, f32)> = None;
+        for (idx, &flag) in unconnected.iter().enumerate() {
+            let mut cheapest: Option<((i32, i32), Vec<Position>, f32)> = None;
+            for &target in &connected {
+                let Some((path, cost)) = find_road_path(map, flag, target) else {
+                    continue;
+                };
+                if cheapest.as_ref().map_or(true, |(_, _, best_cost)| cost < *best_cost) {
+                    cheapest = Some((target, path, cost));
+                }
+            }
+            let Some((target, path, cost)) = cheapest else {
+                continue;
+            };
+            let distance = manhattan_distance(flag, target);
+            let shortening = cost - distance;
+            let is_better = best_candidate
+                .as_ref()
+                .map_or(true, |(_, _, best_shortening)| shortening > *best_shortening);
+            if is_better {
+                best_candidate = Some((idx, path, shortening));
+            }
+        }
+
+        let Some((idx, path, _)) = best_candidate else {
+            log::warn!("{} building entrance(s) unreachable, stopping road network", unconnected.len());
+            break;
+        };
+
+        for pos in &path {
+            pave_road(map, pos.x, pos.y);
+        }
+
+        let flag = unconnected.remove(idx);
+        connected.push(flag);
+    }
+}
+
+fn find_road_path(
+    map: &MapData,
+    from: (i32, i32),
+    to: (i32, i32),
+) -> Option<(Vec<Position>, f32)> {
+    let start = Position::new(from.0, from.1);
+    let goal = Position::new(to.0, to.1);
+
+    let result = astar(
+        &start,
+        |pos| {
+            let mut neighbors = Vec::new();
+            for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+                let next = Position::new(pos.x + dx, pos.y + dy);
+                if let Some(tile) = map.get_tile(next.x, next.y) {
+                    if matches!(tile.tile_type, TileType::Grass | TileType::Road) {
+                        neighbors.push((next, 1u32));
+                    }
+                }
+            }
+            neighbors
+        },
+        |pos| pos.distance_to(&goal) as u32,
+        |pos| *pos == goal,
+    )?;
+
+    let (path, cost) = result;
+    Some((path, cost as f32))
+}
+
+fn manhattan_distance(a: (i32, i32), b: (i32, i32)) -> f32 {
+    ((a.0 - b.0).abs() + (a.1 - b.1).abs()) as f32
+}
+
 /// Check if a position is suitable for building placement
 pub fn is_suitable_for_building(map: &MapData, x: i32, y: i32, size: (u32, u32)) -> bool {
     for dy in 0..size.1 as i32 {
@@ -204,10 +525,121 @@ mod tests {
             stone_density: 0.2,
             water_patches: 2,
             seed: 54321,
+            octaves: 4,
+            lacunarity: 2.0,
+            gain: 0.5,
         };
         
-        let map = generate_map_from_config(&config);
+        let map = generate_map(&config);
         assert_eq!(map.width, 16);
         assert_eq!(map.height, 16);
     }
+
+    #[test]
+    fn test_generation_is_deterministic_for_same_seed() {
+        let config = crate::resources::MapGenerationConfig {
+            width: 32,
+            height: 32,
+            forest_density: 0.3,
+            stone_density: 0.1,
+            water_patches: 3,
+            seed: 777,
+            octaves: 4,
+            lacunarity: 2.0,
+            gain: 0.5,
+        };
+
+        let map_a = generate_map(&config);
+        let map_b = generate_map(&config);
+
+        for y in 0..config.height {
+            for x in 0..config.width {
+                assert_eq!(
+                    map_a.get_tile(x as i32, y as i32),
+                    map_b.get_tile(x as i32, y as i32)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_town_plots_do_not_overlap() {
+        let mut map = MapData::new(48, 48);
+        for y in 0..48 {
+            for x in 0..48 {
+                map.set_tile(x, y, Tile {
+                    tile_type: TileType::Grass,
+                    elevation: 100,
+                });
+            }
+        }
+
+        let plots = generate_town(&mut map, 42, 6);
+        assert!(!plots.is_empty());
+
+        let mut occupied = HashSet::new();
+        for plot in &plots {
+            for dy in 0..plot.size.1 as i32 {
+                for dx in 0..plot.size.0 as i32 {
+                    let cell = (plot.position.x + dx, plot.position.y + dy);
+                    assert!(occupied.insert(cell), "plot footprints overlap at {cell:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_town_generation_is_deterministic_for_same_seed() {
+        let mut map_a = MapData::new(48, 48);
+        let mut map_b = MapData::new(48, 48);
+        for map in [&mut map_a, &mut map_b] {
+            for y in 0..48 {
+                for x in 0..48 {
+                    map.set_tile(x, y, Tile {
+                        tile_type: TileType::Grass,
+                        elevation: 100,
+                    });
+                }
+            }
+        }
+
+        let plots_a = generate_town(&mut map_a, 99, 5);
+        let plots_b = generate_town(&mut map_b, 99, 5);
+
+        assert_eq!(plots_a, plots_b);
+    }
+
+    #[test]
+    fn test_connect_buildings_with_roads_links_every_entrance() {
+        let mut map = MapData::new(20, 20);
+        for y in 0..20 {
+            for x in 0..20 {
+                map.set_tile(x, y, Tile {
+                    tile_type: TileType::Grass,
+                    elevation: 100,
+                });
+            }
+        }
+
+        let entrances = [(2, 2), (10, 3), (15, 15)];
+        connect_buildings_with_roads(&mut map, &entrances);
+
+        // Every entrance should now be reachable from the others over roads.
+        let mut reached = HashSet::from([entrances[0]]);
+        let mut frontier = vec![entrances[0]];
+        while let Some((x, y)) = frontier.pop() {
+            for next in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+                let is_road = matches!(
+                    map.get_tile(next.0, next.1),
+                    Some(tile) if matches!(tile.tile_type, TileType::Road)
+                );
+                if is_road && reached.insert(next) {
+                    frontier.push(next);
+                }
+            }
+        }
+        for entrance in entrances {
+            assert!(reached.contains(&entrance), "{entrance:?} not linked by road");
+        }
+    }
 }
\ No newline at end of file
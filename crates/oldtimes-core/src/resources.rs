@@ -1,6 +1,9 @@
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::alloc::Layout;
+use std::cell::UnsafeCell;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// Game tick counter for deterministic simulation
 #[derive(Resource, Debug, Clone, Serialize, Deserialize)]
@@ -84,6 +87,103 @@ impl MapData {
     }
 }
 
+/// Discovery state of a single tile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VisibilityState {
+    /// Never seen.
+    Unexplored,
+    /// Seen before and remembered, but nothing is currently in sight of it.
+    Explored,
+    /// Currently in a building or worker's sight radius.
+    Visible,
+}
+
+impl Default for VisibilityState {
+    fn default() -> Self {
+        VisibilityState::Unexplored
+    }
+}
+
+/// Fog-of-war state, one `VisibilityState` per `MapData` tile.
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub struct Visibility {
+    pub width: u32,
+    pub height: u32,
+    tiles: Vec<Vec<VisibilityState>>,
+}
+
+impl Visibility {
+    pub fn new(width: u32, height: u32) -> Self {
+        let tiles = (0..height)
+            .map(|_| vec![VisibilityState::Unexplored; width as usize])
+            .collect();
+
+        Self {
+            width,
+            height,
+            tiles,
+        }
+    }
+
+    pub fn state_at(&self, pos: crate::components::Position) -> VisibilityState {
+        if pos.x < 0 || pos.y < 0 || (pos.x as u32) >= self.width || (pos.y as u32) >= self.height
+        {
+            return VisibilityState::Unexplored;
+        }
+        self.tiles[pos.y as usize][pos.x as usize]
+    }
+
+    pub fn is_visible(&self, pos: crate::components::Position) -> bool {
+        self.state_at(pos) == VisibilityState::Visible
+    }
+
+    /// Seen now or remembered from before - the set production/assignment UI
+    /// should treat as discovered.
+    pub fn is_explored(&self, pos: crate::components::Position) -> bool {
+        matches!(
+            self.state_at(pos),
+            VisibilityState::Explored | VisibilityState::Visible
+        )
+    }
+
+    /// Drops every currently `Visible` tile to `Explored`, so a subsequent
+    /// reveal pass only keeps tiles still in someone's sight radius lit up.
+    pub fn downgrade_visible_to_explored(&mut self) {
+        for row in &mut self.tiles {
+            for state in row.iter_mut() {
+                if *state == VisibilityState::Visible {
+                    *state = VisibilityState::Explored;
+                }
+            }
+        }
+    }
+
+    /// Marks every tile within `radius` of `center` as `Visible`.
+    pub fn reveal_around(&mut self, center: crate::components::Position, radius: f32) {
+        let r = radius.ceil() as i32;
+        for dy in -r..=r {
+            for dx in -r..=r {
+                let x = center.x + dx;
+                let y = center.y + dy;
+                if x < 0 || y < 0 || (x as u32) >= self.width || (y as u32) >= self.height {
+                    continue;
+                }
+
+                let distance = ((dx * dx + dy * dy) as f32).sqrt();
+                if distance <= radius {
+                    self.tiles[y as usize][x as usize] = VisibilityState::Visible;
+                }
+            }
+        }
+    }
+}
+
+impl Default for Visibility {
+    fn default() -> Self {
+        Self::new(0, 0)
+    }
+}
+
 /// Pathfinding cache for performance
 #[derive(Resource, Debug, Default)]
 pub struct PathfindingCache {
@@ -126,7 +226,15 @@ impl PathfindingCache {
     pub fn clear(&mut self) {
         self.cache.clear();
     }
-    
+
+    /// Drops only the cached paths that actually pass through one of
+    /// `changed_tiles`, instead of wiping the whole cache on every map edit.
+    /// Paths that never came near the change stay cached and usable.
+    pub fn invalidate_crossing(&mut self, changed_tiles: &[crate::components::Position]) {
+        self.cache
+            .retain(|_, path| !path.iter().any(|pos| changed_tiles.contains(pos)));
+    }
+
     pub fn hit_rate(&self) -> f32 {
         let total = self.cache_hits + self.cache_misses;
         if total > 0 {
@@ -137,6 +245,477 @@ impl PathfindingCache {
     }
 }
 
+/// A Dijkstra map: total movement cost from every tile the flood fill
+/// reached to its nearest goal, plus which neighbor to step toward to bring
+/// that cost down. Built once per goal set by
+/// `systems::pathfinding::get_or_build_flow_field` and then shared by every
+/// agent routed toward one of those goals, instead of running a fresh A*
+/// search per agent.
+#[derive(Debug, Clone, Default)]
+pub struct FlowField {
+    pub cost: HashMap<crate::components::Position, u32>,
+    pub next_step: HashMap<crate::components::Position, crate::components::Position>,
+}
+
+impl FlowField {
+    /// The neighbor to step to from `from` to make progress toward a goal,
+    /// or `None` if `from` wasn't reached by the flood fill.
+    pub fn next_step(&self, from: crate::components::Position) -> Option<crate::components::Position> {
+        self.next_step.get(&from).copied()
+    }
+
+    pub fn contains(&self, pos: crate::components::Position) -> bool {
+        self.cost.contains_key(&pos)
+    }
+}
+
+/// Caches `FlowField`s keyed by goal set, so `pathfinding_system` only pays
+/// for the Dijkstra expansion once per destination set rather than once per
+/// agent routed there. Cleared alongside `PathfindingCache` whenever
+/// `MapChangedEvent` fires.
+#[derive(Resource, Debug, Default)]
+pub struct FlowFieldCache {
+    fields: HashMap<Vec<crate::components::Position>, FlowField>,
+}
+
+impl FlowFieldCache {
+    /// Normalizes a goal set into its cache key - order shouldn't matter, so
+    /// callers don't have to agree on one.
+    pub fn key_for(goals: &[crate::components::Position]) -> Vec<crate::components::Position> {
+        let mut key = goals.to_vec();
+        key.sort_by_key(|pos| (pos.x, pos.y));
+        key.dedup();
+        key
+    }
+
+    pub fn get(&self, key: &[crate::components::Position]) -> Option<&FlowField> {
+        self.fields.get(key)
+    }
+
+    pub fn insert(&mut self, key: Vec<crate::components::Position>, field: FlowField) {
+        self.fields.insert(key, field);
+    }
+
+    pub fn clear(&mut self) {
+        self.fields.clear();
+    }
+
+    /// Drops only the fields whose flood fill actually reached one of
+    /// `changed_tiles` - a field that never flowed near the change is still
+    /// accurate and doesn't need rebuilding.
+    pub fn invalidate_crossing(&mut self, changed_tiles: &[crate::components::Position]) {
+        self.fields
+            .retain(|_, field| !changed_tiles.iter().any(|pos| field.contains(*pos)));
+    }
+}
+
+/// Requests `pathfinding_system` couldn't get to within a tick's search
+/// budget, carried forward to the next tick, where they're merged with that
+/// tick's newly arrived requests and resolved highest priority first; among
+/// requests of equal priority the carried-forward ones go first.
+#[derive(Resource, Debug, Default)]
+pub struct PendingPathfindingRequests {
+    pub requests: std::collections::VecDeque<crate::events::PathfindingRequestEvent>,
+}
+
+impl PendingPathfindingRequests {
+    /// Appends `new_requests` and reorders the whole queue so
+    /// `PathfindingPriority::Critical`/`High` requests - old or new - are
+    /// popped before `Normal`/`Low` ones.
+    pub fn extend_and_sort(
+        &mut self,
+        new_requests: impl Iterator<Item = crate::events::PathfindingRequestEvent>,
+    ) {
+        self.requests.extend(new_requests);
+        self.requests
+            .make_contiguous()
+            .sort_by(|a, b| b.priority.cmp(&a.priority));
+    }
+}
+
+/// Which activity a pheromone trail reinforces, kept as separate channels so
+/// haul traffic doesn't bias construction routes and vice versa.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PheromoneChannel {
+    Haul,
+    Construction,
+}
+
+/// Sparse, decaying trail of worker foot traffic. Workers carrying goods (or
+/// heading to a construction site) deposit onto the tiles they step through;
+/// the trail decays multiplicatively every tick so unused routes fade out.
+#[derive(Resource, Debug, Default)]
+pub struct PheromoneField {
+    haul: HashMap<crate::components::Position, f32>,
+    construction: HashMap<crate::components::Position, f32>,
+}
+
+impl PheromoneField {
+    const EPSILON: f32 = 0.01;
+
+    fn channel_map(&self, channel: PheromoneChannel) -> &HashMap<crate::components::Position, f32> {
+        match channel {
+            PheromoneChannel::Haul => &self.haul,
+            PheromoneChannel::Construction => &self.construction,
+        }
+    }
+
+    fn channel_map_mut(
+        &mut self,
+        channel: PheromoneChannel,
+    ) -> &mut HashMap<crate::components::Position, f32> {
+        match channel {
+            PheromoneChannel::Haul => &mut self.haul,
+            PheromoneChannel::Construction => &mut self.construction,
+        }
+    }
+
+    /// Current pheromone level on a tile for the given channel (0.0 if untouched).
+    pub fn level(&self, channel: PheromoneChannel, pos: crate::components::Position) -> f32 {
+        self.channel_map(channel).get(&pos).copied().unwrap_or(0.0)
+    }
+
+    /// Deposit onto a tile, saturating at `max_level` to prevent runaway reinforcement.
+    pub fn deposit(
+        &mut self,
+        channel: PheromoneChannel,
+        pos: crate::components::Position,
+        amount: f32,
+        max_level: f32,
+    ) {
+        let level = self.channel_map_mut(channel).entry(pos).or_insert(0.0);
+        *level = (*level + amount).min(max_level);
+    }
+
+    /// Multiplicatively decay every tracked tile, dropping entries once they fall
+    /// below the epsilon floor so the sparse maps don't grow without bound.
+    pub fn decay(&mut self, decay_rate: f32) {
+        for map in [&mut self.haul, &mut self.construction] {
+            map.retain(|_, level| {
+                *level *= decay_rate;
+                *level > Self::EPSILON
+            });
+        }
+    }
+}
+
+/// Tracks in-flight commitments against each stockpile so concurrent workers
+/// can't double-haul the same items or over-commit space that isn't free yet.
+/// Keyed by the stockpile's entity; the value pairs reserved-out items
+/// (committed to be picked up) with reserved-in space (committed to be
+/// delivered into). The sum of reservations against a stockpile must never
+/// exceed its physical contents (outgoing) or free capacity (incoming) -
+/// callers are responsible for releasing a reservation as soon as the task
+/// that claimed it completes or is abandoned.
+#[derive(Resource, Debug, Default)]
+pub struct Reservations {
+    entries: HashMap<Entity, (HashMap<String, u32>, u32)>,
+    /// Grid positions currently claimed as a task destination, keyed by the
+    /// tile and valued by the worker walking there. Lets idle-worker task
+    /// selection skip a candidate another worker has already committed to
+    /// reaching this tick, rather than sending two workers at the same tile.
+    target_claims: HashMap<crate::components::Position, Entity>,
+}
+
+impl Reservations {
+    /// Amount of `item` already claimed for an outgoing haul from `stockpile`.
+    pub fn reserved_out(&self, stockpile: Entity, item: &str) -> u32 {
+        self.entries
+            .get(&stockpile)
+            .and_then(|(out, _)| out.get(item))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Space on `stockpile` already claimed by goods in transit toward it.
+    pub fn reserved_in(&self, stockpile: Entity) -> u32 {
+        self.entries.get(&stockpile).map(|(_, inp)| *inp).unwrap_or(0)
+    }
+
+    /// Claim `amount` of `item` as committed to leave `stockpile`.
+    pub fn reserve_out(&mut self, stockpile: Entity, item: &str, amount: u32) {
+        if amount == 0 {
+            return;
+        }
+        let (out, _) = self.entries.entry(stockpile).or_default();
+        *out.entry(item.to_string()).or_insert(0) += amount;
+    }
+
+    /// Release a previously claimed outgoing reservation.
+    pub fn release_out(&mut self, stockpile: Entity, item: &str, amount: u32) {
+        if let Some((out, _)) = self.entries.get_mut(&stockpile) {
+            if let Some(level) = out.get_mut(item) {
+                *level = level.saturating_sub(amount);
+                if *level == 0 {
+                    out.remove(item);
+                }
+            }
+        }
+    }
+
+    /// Claim `amount` of incoming space on `stockpile` for goods in transit.
+    pub fn reserve_in(&mut self, stockpile: Entity, amount: u32) {
+        if amount == 0 {
+            return;
+        }
+        let (_, inp) = self.entries.entry(stockpile).or_default();
+        *inp += amount;
+    }
+
+    /// Release a previously claimed incoming space reservation.
+    pub fn release_in(&mut self, stockpile: Entity, amount: u32) {
+        if let Some((_, inp)) = self.entries.get_mut(&stockpile) {
+            *inp = inp.saturating_sub(amount);
+        }
+    }
+
+    /// Worker currently committed to reaching `position`, if any.
+    pub fn target_claimed_by(&self, position: crate::components::Position) -> Option<Entity> {
+        self.target_claims.get(&position).copied()
+    }
+
+    /// Claims `position` as `worker`'s task destination.
+    pub fn claim_target(&mut self, position: crate::components::Position, worker: Entity) {
+        self.target_claims.insert(position, worker);
+    }
+
+    /// Releases `worker`'s claim on `position`, if it's still the one holding it.
+    pub fn release_target(&mut self, position: crate::components::Position, worker: Entity) {
+        if self.target_claims.get(&position) == Some(&worker) {
+            self.target_claims.remove(&position);
+        }
+    }
+}
+
+/// Tile-bucketed occupancy grid so placement checks and "what's near me"
+/// queries don't have to scan every building entity. Maintained
+/// incrementally by `systems::spatial_index_system` - `Added<Building>`
+/// inserts its footprint, a removed `Building` clears whatever tiles it
+/// last claimed - rather than rebuilt from scratch every tick. This is what
+/// replaced the old O(buildings) per-placement scan:
+/// `building_placement_system` now calls `footprint_is_clear`, which only
+/// touches the tiles inside the candidate footprint (O(area)).
+#[derive(Resource, Debug, Default)]
+pub struct SpatialIndex {
+    /// Tile -> the entity whose footprint covers it.
+    occupancy: HashMap<crate::components::Position, Entity>,
+    /// Tiles that block movement/placement, tracked separately from
+    /// `occupancy` so a future obstacle that isn't a `Building` could still
+    /// mark a tile blocked.
+    blocked: HashSet<crate::components::Position>,
+    /// Reverse index of which tiles each entity last claimed, so a removed
+    /// entity's tiles can be cleared without re-deriving its footprint.
+    tiles_by_entity: HashMap<Entity, Vec<crate::components::Position>>,
+}
+
+impl SpatialIndex {
+    /// Claims every tile in `entity`'s footprint (its position plus `size`
+    /// in each direction) as occupied and blocked.
+    pub fn insert_building(
+        &mut self,
+        entity: Entity,
+        position: crate::components::Position,
+        size: (u32, u32),
+    ) {
+        self.remove_entity(entity);
+
+        let mut tiles = Vec::with_capacity((size.0 * size.1) as usize);
+        for dy in 0..size.1 as i32 {
+            for dx in 0..size.0 as i32 {
+                let tile = crate::components::Position::new(position.x + dx, position.y + dy);
+                self.occupancy.insert(tile, entity);
+                self.blocked.insert(tile);
+                tiles.push(tile);
+            }
+        }
+        self.tiles_by_entity.insert(entity, tiles);
+    }
+
+    /// The tiles `entity` currently claims, if it claims any.
+    pub fn footprint_of(&self, entity: Entity) -> Option<&[crate::components::Position]> {
+        self.tiles_by_entity.get(&entity).map(Vec::as_slice)
+    }
+
+    /// Releases every tile `entity` last claimed. A no-op if it never
+    /// claimed any (or was already removed).
+    pub fn remove_entity(&mut self, entity: Entity) {
+        if let Some(tiles) = self.tiles_by_entity.remove(&entity) {
+            for tile in tiles {
+                self.occupancy.remove(&tile);
+                self.blocked.remove(&tile);
+            }
+        }
+    }
+
+    /// Whether tile `(x, y)` is occupied or otherwise blocked.
+    pub fn is_tile_occupied(&self, x: i32, y: i32) -> bool {
+        self.blocked.contains(&crate::components::Position::new(x, y))
+    }
+
+    /// The entity occupying tile `(x, y)`, if any.
+    pub fn occupant_at(&self, x: i32, y: i32) -> Option<Entity> {
+        self.occupancy
+            .get(&crate::components::Position::new(x, y))
+            .copied()
+    }
+
+    /// Whether a `size`-footprint building at `position` would overlap any
+    /// already-occupied tile.
+    pub fn footprint_is_clear(&self, position: crate::components::Position, size: (u32, u32)) -> bool {
+        for dy in 0..size.1 as i32 {
+            for dx in 0..size.0 as i32 {
+                if self.is_tile_occupied(position.x + dx, position.y + dy) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Every distinct entity with at least one tile within Chebyshev
+    /// distance `radius` of `position` - cheap enough for the worker AI to
+    /// call when looking for nearby pickup/delivery candidates.
+    pub fn entities_in_radius(&self, position: crate::components::Position, radius: u32) -> Vec<Entity> {
+        let radius = radius as i32;
+        let mut seen = HashSet::new();
+        let mut result = Vec::new();
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                if let Some(entity) = self.occupant_at(position.x + dx, position.y + dy) {
+                    if seen.insert(entity) {
+                        result.push(entity);
+                    }
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Per-cluster electricity balance, keyed by building entity so lookups
+/// during production don't need to know which cluster a building landed in.
+/// Rebuilt wholesale by `systems::power_grid_system` on `MapChangedEvent`
+/// rather than patched incrementally - the same "recompute on invalidation"
+/// approach `PathfindingCache` uses for map edits.
+#[derive(Resource, Debug, Default)]
+pub struct PowerGrid {
+    power_cache: HashMap<Entity, f32>,
+}
+
+impl PowerGrid {
+    /// Throughput multiplier for `building`'s cluster, clamped to `[0, 1]`.
+    /// Buildings the grid hasn't seen yet (not constructed, or computed
+    /// before this tick's placement) default to full power.
+    pub fn satisfaction(&self, building: Entity) -> f32 {
+        self.power_cache.get(&building).copied().unwrap_or(1.0)
+    }
+
+    pub(crate) fn set_satisfaction(&mut self, building: Entity, satisfaction: f32) {
+        self.power_cache.insert(building, satisfaction);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.power_cache.clear();
+    }
+}
+
+/// Per-item prices, nudged every tick by `systems::market_system` in response
+/// to how much of each item the economy is net producing or consuming.
+/// Unlisted items read as [`MarketPrices::BASE_PRICE`] rather than `0.0`, so a
+/// recipe's first tick doesn't look worthless before the market has an
+/// opinion on it.
+#[derive(Resource, Debug)]
+pub struct MarketPrices {
+    prices: HashMap<String, f32>,
+}
+
+impl MarketPrices {
+    pub const BASE_PRICE: f32 = 1.0;
+    pub const MIN_PRICE: f32 = 0.1;
+
+    pub fn price(&self, item: &str) -> f32 {
+        self.prices.get(item).copied().unwrap_or(Self::BASE_PRICE)
+    }
+
+    /// Snapshot of every item this market has ever priced, for feeding into
+    /// [`crate::economy::EconomyAnalyzer`] calls that expect a price map.
+    pub fn as_map(&self) -> HashMap<String, f32> {
+        self.prices.clone()
+    }
+
+    /// Nudges `item`'s price down when it's in surplus (`net_flow > 0`) and up
+    /// when it's scarce (`net_flow < 0`), floored at [`Self::MIN_PRICE`] so a
+    /// glut can never push an item to free or negative.
+    pub(crate) fn drift(&mut self, item: &str, net_flow: f32, rate: f32) {
+        let price = self
+            .prices
+            .entry(item.to_string())
+            .or_insert(Self::BASE_PRICE);
+        *price = (*price - rate * net_flow).max(Self::MIN_PRICE);
+    }
+}
+
+impl Default for MarketPrices {
+    fn default() -> Self {
+        Self {
+            prices: HashMap::new(),
+        }
+    }
+}
+
+/// The player's cash on hand, earned (or spent) by `systems::market_system`
+/// selling net production into the market at the current [`MarketPrices`].
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct PlayerMoney {
+    pub balance: f32,
+}
+
+impl Default for PlayerMoney {
+    fn default() -> Self {
+        Self { balance: 100.0 }
+    }
+}
+
+/// One configured destination on a [`BeltNetwork`] route: where the belt
+/// goes and its already-normalized share of the source's output for `item`.
+#[derive(Debug, Clone)]
+pub struct BeltRoute {
+    pub to: Entity,
+    pub item: String,
+    pub capacity: u32,
+    pub share: f32,
+}
+
+/// Directed belt/splitter graph, keyed by source entity, rebuilt wholesale by
+/// `systems::belt_network_system` on `MapChangedEvent` - the same
+/// recompute-on-invalidation approach `PowerGrid` uses for connectivity
+/// changes. `resource_distribution_system` consults this before falling back
+/// to its naive nearest-need scan, so a placed belt always wins over
+/// whatever ad-hoc match the scan would have picked.
+#[derive(Resource, Debug, Default)]
+pub struct BeltNetwork {
+    routes: HashMap<Entity, Vec<BeltRoute>>,
+}
+
+impl BeltNetwork {
+    /// Every configured route out of `source` carrying `item`, in
+    /// round-robin/ratio order.
+    pub fn routes_for(&self, source: Entity, item: &str) -> Vec<&BeltRoute> {
+        self.routes
+            .get(&source)
+            .map(|routes| routes.iter().filter(|route| route.item == item).collect())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn set_routes(&mut self, source: Entity, routes: Vec<BeltRoute>) {
+        self.routes.insert(source, routes);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.routes.clear();
+    }
+}
+
 /// Performance metrics for debugging
 #[derive(Resource, Debug, Default)]
 pub struct PerformanceMetrics {
@@ -150,22 +729,364 @@ impl PerformanceMetrics {
     pub fn record_system_time(&mut self, system_name: String, time_ms: f32) {
         self.system_times.insert(system_name, time_ms);
     }
-    
+
     pub fn get_total_system_time(&self) -> f32 {
         self.system_times.values().sum()
     }
 }
 
+/// One tick's worth of per-system timing, in the order systems reported in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TickTimingSample {
+    pub tick: u64,
+    pub systems: Vec<(String, f32)>,
+}
+
+/// Bounded ring buffer of [`TickTimingSample`]s feeding the headless
+/// server's `--timing-report` HTML timeline. Bounded so a long-running
+/// server doesn't grow this resource without bound.
+#[derive(Resource, Debug)]
+pub struct TickTimingHistory {
+    samples: std::collections::VecDeque<TickTimingSample>,
+    capacity: usize,
+}
+
+impl TickTimingHistory {
+    pub const DEFAULT_CAPACITY: usize = 10_000;
+
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: std::collections::VecDeque::with_capacity(capacity.min(1024)),
+            capacity,
+        }
+    }
+
+    pub fn push(&mut self, sample: TickTimingSample) {
+        if self.samples.len() >= self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn samples(&self) -> impl Iterator<Item = &TickTimingSample> {
+        self.samples.iter()
+    }
+}
+
+impl Default for TickTimingHistory {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+/// Bump/arena allocator for scratch buffers whose lifetime is a single game
+/// tick (e.g. a temporary candidate list built up and immediately drained
+/// inside a hot per-tick system), so that kind of work no longer pressures
+/// the global allocator with a fresh heap allocation every tick. `reset()`
+/// runs once per tick, at the top of `advance_tick_system` - the first
+/// system in the tick's `.chain()` - which lands it right after every system
+/// that could have allocated from the *previous* tick has already finished
+/// and right before anything in the new tick can allocate again. The
+/// invariant this depends on: nothing returned by `alloc_slice`/`alloc_vec`
+/// may be held onto past that boundary, since the next `reset()` hands the
+/// same bytes back out.
+///
+/// `buffer` is wrapped in an `UnsafeCell` rather than a plain `Box<[u8]>`
+/// because `alloc_slice`/`alloc_vec` take only a shared `&self` but hand back
+/// a `&mut [T]` carved out of it - doing that over non-interior-mutable data
+/// would be UB the instant two calls are alive at once, since a `&mut` can
+/// never be legally derived from a shared reference without one. The
+/// `unsafe impl Sync` below is sound because every `&mut [T]` `bump` hands
+/// out covers a byte range reserved exclusively for that call by the
+/// compare-exchange loop, so two concurrent callers (Bevy may run systems
+/// that only take `Res<FrameAllocator>` in parallel) never alias; `reset()`
+/// takes `&mut self`, so the borrow checker (via the lifetime tying
+/// `alloc_slice`'s return to `&self`) already prevents it from running while
+/// any arena borrow is still alive.
+#[derive(Resource)]
+pub struct FrameAllocator {
+    buffer: UnsafeCell<Box<[u8]>>,
+    cursor: AtomicUsize,
+}
+
+// SAFETY: see the field-level safety note above the struct.
+unsafe impl Sync for FrameAllocator {}
+
+impl FrameAllocator {
+    /// Scratch space big enough for the vast majority of per-tick buffers
+    /// without falling back to the heap; sized generously rather than
+    /// tightly since it's claimed once at startup, not per tick.
+    pub const DEFAULT_CAPACITY_BYTES: usize = 1 << 20; // 1 MiB
+
+    pub fn new(capacity_bytes: usize) -> Self {
+        Self {
+            buffer: UnsafeCell::new(vec![0u8; capacity_bytes].into_boxed_slice()),
+            cursor: AtomicUsize::new(0),
+        }
+    }
+
+    /// Reclaims the whole arena for the next tick, logging how many bytes
+    /// the previous tick used at `trace` so normal runs stay silent.
+    pub fn reset(&mut self) {
+        let used = *self.cursor.get_mut();
+        if used > 0 {
+            log::trace!("FrameAllocator reclaimed {used} bytes");
+        }
+        *self.cursor.get_mut() = 0;
+    }
+
+    /// Bump-allocates room for `len` `T`s and returns it zeroed, valid until
+    /// the next `reset()`. Falls back to a normal heap allocation (logged at
+    /// `trace`, and dropped like any other `Vec` once the caller is done with
+    /// it) when the arena doesn't have `len` `T`s of space left, so callers
+    /// never have to handle an allocation failure themselves and a busy tick
+    /// can't leak memory.
+    pub fn alloc_slice<T: Default + Copy>(&self, len: usize) -> FrameSlice<'_, T> {
+        if len == 0 {
+            return FrameSlice::Arena(&mut []);
+        }
+
+        let layout = Layout::array::<T>(len).expect("FrameAllocator::alloc_slice layout overflow");
+        match self.bump(layout) {
+            Some(ptr) => unsafe {
+                let slice = std::slice::from_raw_parts_mut(ptr.cast::<T>(), len);
+                slice.fill(T::default());
+                FrameSlice::Arena(slice)
+            },
+            None => {
+                log::trace!(
+                    "FrameAllocator out of space for {len} x {}B, falling back to the heap",
+                    std::mem::size_of::<T>()
+                );
+                FrameSlice::Heap(vec![T::default(); len])
+            }
+        }
+    }
+
+    /// Bump-allocates a fixed-capacity, `Vec`-like scratch buffer for
+    /// building up a list of up to `capacity` items over the course of this
+    /// tick.
+    pub fn alloc_vec<T: Default + Copy>(&self, capacity: usize) -> FrameVec<'_, T> {
+        FrameVec {
+            slice: self.alloc_slice(capacity),
+            len: 0,
+        }
+    }
+
+    /// Reserves `layout.size()` arena bytes aligned to `layout.align()`,
+    /// returning `None` if the arena is out of space. Safe to call
+    /// concurrently: the compare-exchange loop only commits a reservation
+    /// once it's sure no other caller has already claimed that range, so two
+    /// systems bumping the same arena in parallel always get disjoint bytes.
+    ///
+    /// All pointer arithmetic here goes through `.add()` off the buffer's own
+    /// base pointer; the `as usize` casts are only ever used to *compute* an
+    /// offset (alignment padding), never cast back into a pointer, so the
+    /// returned pointer keeps the provenance it was derived from.
+    fn bump(&self, layout: Layout) -> Option<*mut u8> {
+        // SAFETY: goes through `&*self.buffer.get()` (a shared reference),
+        // never `&mut`, so this can't itself race with another concurrent
+        // `bump()` doing the same; only the `.add()`-derived pointer below is
+        // ever used to write, and only into a range this call alone reserved.
+        let base = unsafe { (*self.buffer.get()).as_ptr() as *mut u8 };
+        let capacity = unsafe { (*self.buffer.get()).len() };
+
+        loop {
+            let current = self.cursor.load(Ordering::Relaxed);
+            let unaligned_addr = base as usize + current;
+            let aligned_addr = (unaligned_addr + layout.align() - 1) & !(layout.align() - 1);
+            let padding = aligned_addr - unaligned_addr;
+            let new_cursor = current + padding + layout.size();
+
+            if new_cursor > capacity {
+                return None;
+            }
+
+            if self
+                .cursor
+                .compare_exchange_weak(current, new_cursor, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+            {
+                // SAFETY: `current + padding` is within `capacity` (checked
+                // above), so this offset stays within `buffer`'s allocation.
+                return Some(unsafe { base.add(current + padding) });
+            }
+        }
+    }
+}
+
+impl Default for FrameAllocator {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY_BYTES)
+    }
+}
+
+/// What `FrameAllocator::alloc_slice` hands back: space carved out of the
+/// arena in the common case, or an owned, normally-dropped heap allocation on
+/// the rare tick where the arena is already full. Callers use it exactly
+/// like a `&mut [T]` via `Deref`/`DerefMut` and never need to care which
+/// variant they got.
+pub enum FrameSlice<'a, T> {
+    Arena(&'a mut [T]),
+    Heap(Vec<T>),
+}
+
+impl<'a, T> std::ops::Deref for FrameSlice<'a, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        match self {
+            FrameSlice::Arena(slice) => slice,
+            FrameSlice::Heap(vec) => vec,
+        }
+    }
+}
+
+impl<'a, T> std::ops::DerefMut for FrameSlice<'a, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        match self {
+            FrameSlice::Arena(slice) => slice,
+            FrameSlice::Heap(vec) => vec,
+        }
+    }
+}
+
+/// A fixed-capacity, `Vec`-like scratch buffer backed by a `FrameAllocator`'s
+/// arena instead of the heap. Can't grow past the capacity it was allocated
+/// with - doing so would mean falling back to a fresh heap allocation and
+/// defeat the point of borrowing from the arena - so `push` panics instead of
+/// reallocating.
+pub struct FrameVec<'a, T> {
+    slice: FrameSlice<'a, T>,
+    len: usize,
+}
+
+impl<'a, T> FrameVec<'a, T> {
+    pub fn push(&mut self, value: T) {
+        assert!(
+            self.len < self.slice.len(),
+            "FrameVec pushed past its reserved capacity of {}",
+            self.slice.len()
+        );
+        self.slice[self.len] = value;
+        self.len += 1;
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<'a, T> std::ops::Deref for FrameVec<'a, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.slice[..self.len]
+    }
+}
+
+impl<'a, T> std::ops::DerefMut for FrameVec<'a, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.slice[..self.len]
+    }
+}
+
 /// Game configuration loaded from files
-#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+///
+/// Derives `rkyv` (de)serialization alongside `serde` so a validated config
+/// can be cached as a zero-copy archive (see [`crate::config_cache`]) instead
+/// of being re-parsed and re-validated from TOML on every load.
+#[derive(
+    Resource,
+    Debug,
+    Clone,
+    Serialize,
+    Deserialize,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[archive(check_bytes)]
 pub struct GameConfig {
     pub buildings: HashMap<String, BuildingConfig>,
     pub recipes: HashMap<String, RecipeConfig>,
     pub workers: HashMap<String, WorkerConfig>,
     pub map_generation: MapGenerationConfig,
+    pub pheromones: PheromoneConfig,
+    pub visibility: VisibilityConfig,
+    pub grid_shape: crate::grid::GridShape,
+    pub settlement: SettlementConfig,
+    pub pathfinding: PathfindingConfig,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Tuning knobs for `systems::pathfinding::pathfinding_system`'s per-tick
+/// search scheduling.
+#[derive(Debug, Clone, Serialize, Deserialize, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[archive(check_bytes)]
+pub struct PathfindingConfig {
+    /// Maximum number of requests resolved in a single tick, so a burst of
+    /// auto-distribution haul requests can't spike frame time; anything over
+    /// the budget waits for the next tick, highest priority first.
+    pub max_requests_per_tick: u32,
+}
+
+impl Default for PathfindingConfig {
+    fn default() -> Self {
+        Self {
+            max_requests_per_tick: 32,
+        }
+    }
+}
+
+/// Tuning knobs for the starting-settlement generator that replaces a fresh
+/// game's hardcoded worker spawn (see `systems::worker::spawn_workers_system`).
+#[derive(Debug, Clone, Serialize, Deserialize, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[archive(check_bytes)]
+pub struct SettlementConfig {
+    /// Number of building plots `map::generate_town` stamps down.
+    pub starting_buildings: u32,
+    /// Workers spawned per starting building, placed near the building
+    /// they'll service.
+    pub workers_per_building: u32,
+    /// Seed for settlement layout (plot placement and road carving),
+    /// independent of `map_generation.seed` so terrain and the town placed
+    /// on it can each be reshuffled on their own.
+    pub seed: u64,
+}
+
+impl Default for SettlementConfig {
+    fn default() -> Self {
+        Self {
+            starting_buildings: 5,
+            workers_per_building: 2,
+            seed: 2024,
+        }
+    }
+}
+
+/// Tuning knobs for fog-of-war sight radii.
+#[derive(Debug, Clone, Serialize, Deserialize, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[archive(check_bytes)]
+pub struct VisibilityConfig {
+    pub building_sight_radius: f32,
+    pub worker_sight_radius: f32,
+}
+
+impl Default for VisibilityConfig {
+    fn default() -> Self {
+        Self {
+            building_sight_radius: 6.0,
+            worker_sight_radius: 4.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[archive(check_bytes)]
 pub struct BuildingConfig {
     pub name: String,
     pub construction_time: f32,
@@ -173,9 +1094,21 @@ pub struct BuildingConfig {
     pub worker_capacity: u32,
     pub stockpile_capacity: u32,
     pub size: (u32, u32), // width, height in tiles
+    /// Building types (keys into `GameConfig::buildings`) that must already
+    /// be constructed somewhere before this one can be placed.
+    pub prerequisites: Vec<String>,
+    /// Power fed into this building's grid cluster per tick. 0.0 for
+    /// buildings that don't generate power (the common case).
+    #[serde(default)]
+    pub power_generation: f32,
+    /// Power this building needs to run at full speed. 0.0 means it's
+    /// unaffected by its cluster's power balance.
+    #[serde(default)]
+    pub power_demand: f32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[archive(check_bytes)]
 pub struct RecipeConfig {
     pub name: String,
     pub production_time: f32,
@@ -184,14 +1117,29 @@ pub struct RecipeConfig {
     pub required_building: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[archive(check_bytes)]
 pub struct WorkerConfig {
     pub name: String,
     pub movement_speed: f32,
     pub carrying_capacity: u32,
+    /// Skill gained per second of matching `Working` time.
+    pub learn_rate: f32,
+    /// Maximum skill value workers of this type can reach.
+    pub skill_cap: f32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(
+    Debug,
+    Clone,
+    Serialize,
+    Deserialize,
+    Default,
+    rkyv::Archive,
+    rkyv::Serialize,
+    rkyv::Deserialize,
+)]
+#[archive(check_bytes)]
 pub struct MapGenerationConfig {
     pub width: u32,
     pub height: u32,
@@ -199,6 +1147,39 @@ pub struct MapGenerationConfig {
     pub stone_density: f32,
     pub water_patches: u32,
     pub seed: u64,
+    /// Number of octaves summed into each fractal noise layer. More octaves
+    /// add finer detail at the cost of generation time.
+    pub octaves: u32,
+    /// Frequency multiplier applied to each successive octave.
+    pub lacunarity: f32,
+    /// Amplitude multiplier applied to each successive octave.
+    pub gain: f32,
+}
+
+/// Tuning knobs for the stigmergic pheromone trails hauling/construction
+/// workers lay down and follow.
+#[derive(Debug, Clone, Serialize, Deserialize, rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+#[archive(check_bytes)]
+pub struct PheromoneConfig {
+    /// Amount deposited on a tile each time a worker steps through it.
+    pub deposit_amount: f32,
+    /// Multiplicative decay applied to every tracked tile each tick.
+    pub decay_rate: f32,
+    /// Saturation cap so a single tile can't bias pathing to infinity.
+    pub max_level: f32,
+    /// `k` in `effective_cost = base_cost / (1.0 + k * pheromone_level)`.
+    pub bias_strength: f32,
+}
+
+impl Default for PheromoneConfig {
+    fn default() -> Self {
+        Self {
+            deposit_amount: 1.0,
+            decay_rate: 0.98,
+            max_level: 10.0,
+            bias_strength: 0.5,
+        }
+    }
 }
 
 impl Default for GameConfig {
@@ -215,8 +1196,11 @@ impl Default for GameConfig {
             worker_capacity: 2,
             stockpile_capacity: 20,
             size: (2, 2),
+            prerequisites: vec![],
+            power_generation: 0.0,
+            power_demand: 0.0,
         });
-        
+
         buildings.insert("sawmill".to_string(), BuildingConfig {
             name: "Sawmill".to_string(),
             construction_time: 45.0,
@@ -224,8 +1208,11 @@ impl Default for GameConfig {
             worker_capacity: 3,
             stockpile_capacity: 30,
             size: (3, 3),
+            prerequisites: vec!["lumberjack".to_string()],
+            power_generation: 0.0,
+            power_demand: 0.0,
         });
-        
+
         buildings.insert("farm".to_string(), BuildingConfig {
             name: "Farm".to_string(),
             construction_time: 40.0,
@@ -233,8 +1220,11 @@ impl Default for GameConfig {
             worker_capacity: 2,
             stockpile_capacity: 25,
             size: (4, 4),
+            prerequisites: vec![],
+            power_generation: 0.0,
+            power_demand: 0.0,
         });
-        
+
         buildings.insert("mill".to_string(), BuildingConfig {
             name: "Mill".to_string(),
             construction_time: 50.0,
@@ -242,8 +1232,11 @@ impl Default for GameConfig {
             worker_capacity: 2,
             stockpile_capacity: 20,
             size: (3, 3),
+            prerequisites: vec!["farm".to_string()],
+            power_generation: 0.0,
+            power_demand: 0.0,
         });
-        
+
         buildings.insert("bakery".to_string(), BuildingConfig {
             name: "Bakery".to_string(),
             construction_time: 35.0,
@@ -251,8 +1244,11 @@ impl Default for GameConfig {
             worker_capacity: 3,
             stockpile_capacity: 15,
             size: (2, 3),
+            prerequisites: vec!["mill".to_string()],
+            power_generation: 0.0,
+            power_demand: 0.0,
         });
-        
+
         buildings.insert("quarry".to_string(), BuildingConfig {
             name: "Quarry".to_string(),
             construction_time: 60.0,
@@ -260,6 +1256,9 @@ impl Default for GameConfig {
             worker_capacity: 4,
             stockpile_capacity: 40,
             size: (3, 3),
+            prerequisites: vec![],
+            power_generation: 0.0,
+            power_demand: 0.0,
         });
         
         // Default recipes
@@ -316,6 +1315,8 @@ impl Default for GameConfig {
             name: "Worker".to_string(),
             movement_speed: 1.0,
             carrying_capacity: 5,
+            learn_rate: 0.01,
+            skill_cap: 1.0,
         });
         
         Self {
@@ -329,7 +1330,15 @@ impl Default for GameConfig {
                 stone_density: 0.1,
                 water_patches: 3,
                 seed: 12345,
+                octaves: 4,
+                lacunarity: 2.0,
+                gain: 0.5,
             },
+            pheromones: PheromoneConfig::default(),
+            visibility: VisibilityConfig::default(),
+            grid_shape: crate::grid::GridShape::default(),
+            settlement: SettlementConfig::default(),
+            pathfinding: PathfindingConfig::default(),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,702 @@
+// Lua mod scripting subsystem
+//
+// `LoadModEvent` points at a Lua file that registers new building/recipe/worker
+// definitions and can hook into a handful of simulation events. Scripts never
+// touch the ECS directly: registrations are merged into `GameConfig`, and
+// anything a script wants to happen in the world (starting production,
+// transferring resources, assigning a worker) is enqueued and replayed through
+// the normal typed events so script-originated changes stay deterministic.
+
+use anyhow::{Context, Result};
+use bevy::prelude::*;
+use mlua::{Function, Lua, Table};
+use std::collections::HashMap;
+use std::fs;
+
+use crate::{
+    components::Stockpile,
+    events::{
+        AssignWorkerEvent, BuildingConstructedEvent, LoadModEvent, ProductionCompletedEvent,
+        ReloadConfigEvent, StartProductionEvent, TransferResourceEvent,
+    },
+    resources::{BuildingConfig, GameConfig, RecipeConfig, WorkerConfig},
+};
+
+/// Config entries a mod script registered, tracked so a hot-reload can
+/// retract them cleanly before the script is re-run.
+#[derive(Debug, Default, Clone)]
+struct ModRegistrations {
+    buildings: HashMap<String, BuildingConfig>,
+    recipes: HashMap<String, RecipeConfig>,
+    workers: HashMap<String, WorkerConfig>,
+}
+
+/// A game event a Lua callback asked to enqueue. Drained into real Bevy
+/// events after the callback returns, so a script never reaches into the ECS
+/// directly.
+#[derive(Debug, Clone)]
+enum PendingScriptEvent {
+    StartProduction {
+        building: Entity,
+        recipe_id: String,
+    },
+    TransferResource {
+        from: Entity,
+        to: Entity,
+        resource: String,
+        amount: u32,
+    },
+    AssignWorker {
+        worker: Entity,
+        building: Entity,
+    },
+}
+
+/// Scratch state a mod's Lua callbacks read/write through `Lua::app_data_*`,
+/// since the `game.*` closures can't capture `&mut World`.
+#[derive(Default)]
+struct ScriptAppData {
+    registrations: ModRegistrations,
+    pending_events: Vec<PendingScriptEvent>,
+}
+
+/// One loaded mod's Lua environment, plus the ids it last registered so a
+/// hot-reload can remove stale entries before re-applying the script.
+struct ScriptedMod {
+    path: String,
+    lua: Lua,
+    registered_buildings: Vec<String>,
+    registered_recipes: Vec<String>,
+    registered_workers: Vec<String>,
+    has_on_production_complete: bool,
+    has_on_building_constructed: bool,
+}
+
+impl ScriptedMod {
+    fn fire_on_production_complete(
+        &self,
+        event: &ProductionCompletedEvent,
+        recipe: Option<&RecipeConfig>,
+        stockpile_items: &HashMap<String, u32>,
+    ) -> mlua::Result<Vec<PendingScriptEvent>> {
+        self.lua.set_app_data(ScriptAppData::default());
+
+        if let Some(callback) = self
+            .lua
+            .named_registry_value::<Option<Function>>("on_production_complete")?
+        {
+            let outputs_table = self.lua.create_table()?;
+            for (item, amount) in &event.outputs {
+                outputs_table.set(item.as_str(), *amount)?;
+            }
+
+            let inputs_table = self.lua.create_table()?;
+            if let Some(recipe) = recipe {
+                for (item, amount) in &recipe.inputs {
+                    inputs_table.set(item.as_str(), *amount)?;
+                }
+            }
+
+            let stockpile_table = self.lua.create_table()?;
+            for (item, amount) in stockpile_items {
+                stockpile_table.set(item.as_str(), *amount)?;
+            }
+
+            callback.call::<_, ()>((
+                event.building.to_bits(),
+                event.recipe_id.clone(),
+                inputs_table,
+                outputs_table,
+                stockpile_table,
+            ))?;
+        }
+
+        Ok(self
+            .lua
+            .remove_app_data::<ScriptAppData>()
+            .unwrap_or_default()
+            .pending_events)
+    }
+
+    fn fire_on_building_constructed(
+        &self,
+        event: &BuildingConstructedEvent,
+    ) -> mlua::Result<Vec<PendingScriptEvent>> {
+        self.lua.set_app_data(ScriptAppData::default());
+
+        if let Some(callback) = self
+            .lua
+            .named_registry_value::<Option<Function>>("on_building_constructed")?
+        {
+            let position_table = self.lua.create_table()?;
+            position_table.set("x", event.position.x)?;
+            position_table.set("y", event.position.y)?;
+
+            callback.call::<_, ()>((
+                event.building.to_bits(),
+                event.building_type.clone(),
+                position_table,
+            ))?;
+        }
+
+        Ok(self
+            .lua
+            .remove_app_data::<ScriptAppData>()
+            .unwrap_or_default()
+            .pending_events)
+    }
+}
+
+/// Non-send because `mlua::Lua` wraps an interpreter handle that isn't
+/// `Send`; Bevy runs `NonSend` resources on the main thread for exactly this
+/// kind of external VM state.
+#[derive(Default)]
+pub struct ScriptHost {
+    mods: HashMap<String, ScriptedMod>,
+}
+
+fn table_to_amount_map(table: Table) -> mlua::Result<HashMap<String, u32>> {
+    let mut map = HashMap::new();
+    for pair in table.pairs::<String, u32>() {
+        let (item, amount) = pair?;
+        map.insert(item, amount);
+    }
+    Ok(map)
+}
+
+fn building_config_from_table(table: &Table) -> mlua::Result<BuildingConfig> {
+    let size_table: Table = table.get("size")?;
+    Ok(BuildingConfig {
+        name: table.get("name")?,
+        construction_time: table.get("construction_time")?,
+        construction_cost: table_to_amount_map(table.get("construction_cost")?)?,
+        worker_capacity: table.get("worker_capacity")?,
+        stockpile_capacity: table.get("stockpile_capacity")?,
+        size: (size_table.get(1)?, size_table.get(2)?),
+        prerequisites: table
+            .get::<_, Option<Vec<String>>>("prerequisites")?
+            .unwrap_or_default(),
+        power_generation: table
+            .get::<_, Option<f32>>("power_generation")?
+            .unwrap_or_default(),
+        power_demand: table
+            .get::<_, Option<f32>>("power_demand")?
+            .unwrap_or_default(),
+    })
+}
+
+fn recipe_config_from_table(table: &Table) -> mlua::Result<RecipeConfig> {
+    Ok(RecipeConfig {
+        name: table.get("name")?,
+        production_time: table.get("production_time")?,
+        inputs: table_to_amount_map(table.get("inputs")?)?,
+        outputs: table_to_amount_map(table.get("outputs")?)?,
+        required_building: table.get("required_building")?,
+    })
+}
+
+fn worker_config_from_table(table: &Table) -> mlua::Result<WorkerConfig> {
+    Ok(WorkerConfig {
+        name: table.get("name")?,
+        movement_speed: table.get("movement_speed")?,
+        carrying_capacity: table.get("carrying_capacity")?,
+        learn_rate: table.get("learn_rate")?,
+        skill_cap: table.get("skill_cap")?,
+    })
+}
+
+fn pending_event_from_table(kind: &str, table: &Table) -> mlua::Result<PendingScriptEvent> {
+    match kind {
+        "start_production" => Ok(PendingScriptEvent::StartProduction {
+            building: Entity::from_bits(table.get("building")?),
+            recipe_id: table.get("recipe_id")?,
+        }),
+        "transfer_resource" => Ok(PendingScriptEvent::TransferResource {
+            from: Entity::from_bits(table.get("from")?),
+            to: Entity::from_bits(table.get("to")?),
+            resource: table.get("resource")?,
+            amount: table.get("amount")?,
+        }),
+        "assign_worker" => Ok(PendingScriptEvent::AssignWorker {
+            worker: Entity::from_bits(table.get("worker")?),
+            building: Entity::from_bits(table.get("building")?),
+        }),
+        other => Err(mlua::Error::RuntimeError(format!(
+            "unknown scripted event kind: {}",
+            other
+        ))),
+    }
+}
+
+/// Strips wall-clock and RNG access from a mod's Lua environment. Script
+/// callbacks run at a fixed point in `SimulationApp`'s system chain, and
+/// `calculate_state_hash`/replay verification assume the same mods loaded
+/// against the same event sequence always produce the same result - reading
+/// `os.time`/`math.random` into a decision would break that for no benefit
+/// a deterministic `game.*` API doesn't already cover.
+fn sandbox_lua(lua: &Lua) -> mlua::Result<()> {
+    let os_table: Table = lua.globals().get("os")?;
+    for non_deterministic in ["time", "clock", "date", "difftime"] {
+        os_table.set(non_deterministic, mlua::Value::Nil)?;
+    }
+
+    let math_table: Table = lua.globals().get("math")?;
+    for non_deterministic in ["random", "randomseed"] {
+        math_table.set(non_deterministic, mlua::Value::Nil)?;
+    }
+
+    Ok(())
+}
+
+/// Installs the `game` table Lua mod scripts call into: registration
+/// functions that write into the running script's `ScriptAppData`, and hook
+/// registration functions that stash a callback in the Lua registry.
+fn register_game_api(lua: &Lua) -> mlua::Result<()> {
+    let game_table = lua.create_table()?;
+
+    game_table.set(
+        "register_building",
+        lua.create_function(|lua, (id, table): (String, Table)| {
+            let building = building_config_from_table(&table)?;
+            lua.app_data_mut::<ScriptAppData>()
+                .expect("ScriptAppData missing during mod load")
+                .registrations
+                .buildings
+                .insert(id, building);
+            Ok(())
+        })?,
+    )?;
+
+    game_table.set(
+        "register_recipe",
+        lua.create_function(|lua, (id, table): (String, Table)| {
+            let recipe = recipe_config_from_table(&table)?;
+            lua.app_data_mut::<ScriptAppData>()
+                .expect("ScriptAppData missing during mod load")
+                .registrations
+                .recipes
+                .insert(id, recipe);
+            Ok(())
+        })?,
+    )?;
+
+    game_table.set(
+        "register_worker",
+        lua.create_function(|lua, (id, table): (String, Table)| {
+            let worker = worker_config_from_table(&table)?;
+            lua.app_data_mut::<ScriptAppData>()
+                .expect("ScriptAppData missing during mod load")
+                .registrations
+                .workers
+                .insert(id, worker);
+            Ok(())
+        })?,
+    )?;
+
+    game_table.set(
+        "on_production_complete",
+        lua.create_function(|lua, callback: Function| {
+            lua.set_named_registry_value("on_production_complete", callback)
+        })?,
+    )?;
+
+    game_table.set(
+        "on_building_constructed",
+        lua.create_function(|lua, callback: Function| {
+            lua.set_named_registry_value("on_building_constructed", callback)
+        })?,
+    )?;
+
+    game_table.set(
+        "enqueue_event",
+        lua.create_function(|lua, (kind, table): (String, Table)| {
+            let event = pending_event_from_table(&kind, &table)?;
+            lua.app_data_mut::<ScriptAppData>()
+                .expect("ScriptAppData missing during hook dispatch")
+                .pending_events
+                .push(event);
+            Ok(())
+        })?,
+    )?;
+
+    lua.globals().set("game", game_table)?;
+    Ok(())
+}
+
+/// Loads (or re-loads) the mod script at `path`, retracting whatever it
+/// previously registered first so a hot-reload can rename or drop entries.
+fn load_or_reload_mod(host: &mut ScriptHost, path: &str, config: &mut GameConfig) -> Result<()> {
+    if let Some(old) = host.mods.remove(path) {
+        for id in &old.registered_buildings {
+            config.buildings.remove(id);
+        }
+        for id in &old.registered_recipes {
+            config.recipes.remove(id);
+        }
+        for id in &old.registered_workers {
+            config.workers.remove(id);
+        }
+    }
+
+    let source =
+        fs::read_to_string(path).with_context(|| format!("reading mod script {}", path))?;
+
+    let lua = Lua::new();
+    sandbox_lua(&lua).context("sandboxing mod Lua environment")?;
+    lua.set_app_data(ScriptAppData::default());
+    register_game_api(&lua).context("registering mod scripting API")?;
+    lua.load(&source)
+        .exec()
+        .with_context(|| format!("executing mod script {}", path))?;
+
+    let registrations = lua
+        .remove_app_data::<ScriptAppData>()
+        .unwrap_or_default()
+        .registrations;
+
+    let has_on_production_complete = lua
+        .named_registry_value::<Option<Function>>("on_production_complete")?
+        .is_some();
+    let has_on_building_constructed = lua
+        .named_registry_value::<Option<Function>>("on_building_constructed")?
+        .is_some();
+
+    for (id, building) in &registrations.buildings {
+        config.buildings.insert(id.clone(), building.clone());
+    }
+    for (id, recipe) in &registrations.recipes {
+        config.recipes.insert(id.clone(), recipe.clone());
+    }
+    for (id, worker) in &registrations.workers {
+        config.workers.insert(id.clone(), worker.clone());
+    }
+
+    log::info!(
+        "Mod script {} registered {} building(s), {} recipe(s), {} worker type(s)",
+        path,
+        registrations.buildings.len(),
+        registrations.recipes.len(),
+        registrations.workers.len(),
+    );
+
+    host.mods.insert(
+        path.to_string(),
+        ScriptedMod {
+            path: path.to_string(),
+            registered_buildings: registrations.buildings.keys().cloned().collect(),
+            registered_recipes: registrations.recipes.keys().cloned().collect(),
+            registered_workers: registrations.workers.keys().cloned().collect(),
+            has_on_production_complete,
+            has_on_building_constructed,
+            lua,
+        },
+    );
+
+    Ok(())
+}
+
+fn dispatch_pending_events(
+    pending: Vec<PendingScriptEvent>,
+    start_production_events: &mut EventWriter<StartProductionEvent>,
+    transfer_events: &mut EventWriter<TransferResourceEvent>,
+    assign_events: &mut EventWriter<AssignWorkerEvent>,
+) {
+    for event in pending {
+        match event {
+            PendingScriptEvent::StartProduction {
+                building,
+                recipe_id,
+            } => {
+                start_production_events.send(StartProductionEvent {
+                    building,
+                    recipe_id,
+                });
+            }
+            PendingScriptEvent::TransferResource {
+                from,
+                to,
+                resource,
+                amount,
+            } => {
+                transfer_events.send(TransferResourceEvent {
+                    from,
+                    to,
+                    resource,
+                    amount,
+                });
+            }
+            PendingScriptEvent::AssignWorker { worker, building } => {
+                assign_events.send(AssignWorkerEvent { worker, building });
+            }
+        }
+    }
+}
+
+/// System that loads a mod's Lua script on `LoadModEvent`.
+pub fn load_mod_script_system(
+    mut host: NonSendMut<ScriptHost>,
+    mut events: EventReader<LoadModEvent>,
+    mut config: ResMut<GameConfig>,
+) {
+    for event in events.read() {
+        if let Err(e) = load_or_reload_mod(&mut host, &event.mod_path, &mut config) {
+            log::warn!("Failed to load mod script {}: {}", event.mod_path, e);
+        }
+    }
+}
+
+/// System that re-runs every loaded mod script on `ReloadConfigEvent`, so
+/// editing a script and re-triggering reload replaces its definitions
+/// without restarting the simulation.
+pub fn reload_mod_scripts_system(
+    mut host: NonSendMut<ScriptHost>,
+    mut events: EventReader<ReloadConfigEvent>,
+    mut config: ResMut<GameConfig>,
+) {
+    if events.read().count() == 0 {
+        return;
+    }
+
+    let paths: Vec<String> = host.mods.keys().cloned().collect();
+    for path in paths {
+        if let Err(e) = load_or_reload_mod(&mut host, &path, &mut config) {
+            log::warn!("Failed to hot-reload mod script {}: {}", path, e);
+        }
+    }
+}
+
+/// System that fires each loaded mod's `on_production_complete` /
+/// `on_building_constructed` hooks and replays anything the callback
+/// enqueued as real simulation events.
+pub fn script_event_hook_system(
+    mut host: NonSendMut<ScriptHost>,
+    mut production_events: EventReader<ProductionCompletedEvent>,
+    mut construction_events: EventReader<BuildingConstructedEvent>,
+    config: Res<GameConfig>,
+    stockpiles: Query<&Stockpile>,
+    mut start_production_events: EventWriter<StartProductionEvent>,
+    mut transfer_events: EventWriter<TransferResourceEvent>,
+    mut assign_events: EventWriter<AssignWorkerEvent>,
+) {
+    let production_completions: Vec<_> = production_events.read().cloned().collect();
+    let building_completions: Vec<_> = construction_events.read().cloned().collect();
+
+    if production_completions.is_empty() && building_completions.is_empty() {
+        return;
+    }
+
+    for scripted_mod in host.mods.values_mut() {
+        if scripted_mod.has_on_production_complete {
+            for event in &production_completions {
+                let recipe = config.recipes.get(&event.recipe_id);
+                let stockpile_items = stockpiles
+                    .get(event.building)
+                    .map(|s| s.items.clone())
+                    .unwrap_or_default();
+
+                match scripted_mod.fire_on_production_complete(event, recipe, &stockpile_items) {
+                    Ok(pending) => dispatch_pending_events(
+                        pending,
+                        &mut start_production_events,
+                        &mut transfer_events,
+                        &mut assign_events,
+                    ),
+                    Err(e) => log::warn!(
+                        "on_production_complete hook failed in {}: {}",
+                        scripted_mod.path,
+                        e
+                    ),
+                }
+            }
+        }
+
+        if scripted_mod.has_on_building_constructed {
+            for event in &building_completions {
+                match scripted_mod.fire_on_building_constructed(event) {
+                    Ok(pending) => dispatch_pending_events(
+                        pending,
+                        &mut start_production_events,
+                        &mut transfer_events,
+                        &mut assign_events,
+                    ),
+                    Err(e) => log::warn!(
+                        "on_building_constructed hook failed in {}: {}",
+                        scripted_mod.path,
+                        e
+                    ),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_mod_registers_building_recipe_and_worker() {
+        let dir = tempfile::tempdir().unwrap();
+        let script_path = dir.path().join("mod.lua");
+        fs::write(
+            &script_path,
+            r#"
+                game.register_building("tannery", {
+                    name = "Tannery",
+                    construction_time = 10.0,
+                    construction_cost = { wood = 5 },
+                    worker_capacity = 2,
+                    stockpile_capacity = 20,
+                    size = {1, 1},
+                })
+
+                game.register_recipe("tan_leather", {
+                    name = "Tan Leather",
+                    production_time = 5.0,
+                    inputs = { hides = 2 },
+                    outputs = { leather = 1 },
+                    required_building = "tannery",
+                })
+
+                game.register_worker("tanner", {
+                    name = "Tanner",
+                    movement_speed = 1.0,
+                    carrying_capacity = 5,
+                    learn_rate = 0.02,
+                    skill_cap = 1.0,
+                })
+            "#,
+        )
+        .unwrap();
+
+        let mut host = ScriptHost::default();
+        let mut config = GameConfig::default();
+
+        load_or_reload_mod(
+            &mut host,
+            script_path.to_str().unwrap(),
+            &mut config,
+        )
+        .unwrap();
+
+        assert!(config.buildings.contains_key("tannery"));
+        assert!(config.recipes.contains_key("tan_leather"));
+        assert!(config.workers.contains_key("tanner"));
+    }
+
+    #[test]
+    fn test_sandbox_blocks_wall_clock_and_random_access() {
+        let dir = tempfile::tempdir().unwrap();
+        let script_path = dir.path().join("mod.lua");
+        fs::write(
+            &script_path,
+            r#"
+                assert(os.time == nil, "os.time should be sandboxed")
+                assert(os.clock == nil, "os.clock should be sandboxed")
+                assert(math.random == nil, "math.random should be sandboxed")
+                assert(math.randomseed == nil, "math.randomseed should be sandboxed")
+            "#,
+        )
+        .unwrap();
+
+        let mut host = ScriptHost::default();
+        let mut config = GameConfig::default();
+
+        load_or_reload_mod(&mut host, script_path.to_str().unwrap(), &mut config).unwrap();
+    }
+
+    #[test]
+    fn test_reload_retracts_stale_registrations() {
+        let dir = tempfile::tempdir().unwrap();
+        let script_path = dir.path().join("mod.lua");
+
+        fs::write(
+            &script_path,
+            r#"
+                game.register_building("tannery", {
+                    name = "Tannery",
+                    construction_time = 10.0,
+                    construction_cost = { wood = 5 },
+                    worker_capacity = 2,
+                    stockpile_capacity = 20,
+                    size = {1, 1},
+                })
+            "#,
+        )
+        .unwrap();
+
+        let mut host = ScriptHost::default();
+        let mut config = GameConfig::default();
+        load_or_reload_mod(&mut host, script_path.to_str().unwrap(), &mut config).unwrap();
+        assert!(config.buildings.contains_key("tannery"));
+
+        // Renaming the building id should drop the old entry on reload.
+        fs::write(
+            &script_path,
+            r#"
+                game.register_building("leatherworks", {
+                    name = "Leatherworks",
+                    construction_time = 10.0,
+                    construction_cost = { wood = 5 },
+                    worker_capacity = 2,
+                    stockpile_capacity = 20,
+                    size = {1, 1},
+                })
+            "#,
+        )
+        .unwrap();
+
+        load_or_reload_mod(&mut host, script_path.to_str().unwrap(), &mut config).unwrap();
+
+        assert!(!config.buildings.contains_key("tannery"));
+        assert!(config.buildings.contains_key("leatherworks"));
+    }
+
+    #[test]
+    fn test_production_hook_can_enqueue_follow_up_production() {
+        let dir = tempfile::tempdir().unwrap();
+        let script_path = dir.path().join("mod.lua");
+
+        fs::write(
+            &script_path,
+            r#"
+                game.on_production_complete(function(building, recipe_id, inputs, outputs, stockpile)
+                    if recipe_id == "harvest_wood" then
+                        game.enqueue_event("start_production", {
+                            building = building,
+                            recipe_id = "make_planks",
+                        })
+                    end
+                end)
+            "#,
+        )
+        .unwrap();
+
+        let mut host = ScriptHost::default();
+        let mut config = GameConfig::default();
+        load_or_reload_mod(&mut host, script_path.to_str().unwrap(), &mut config).unwrap();
+
+        let scripted_mod = host.mods.values().next().unwrap();
+        let event = ProductionCompletedEvent {
+            building: Entity::from_bits(42),
+            recipe_id: "harvest_wood".to_string(),
+            outputs: [("wood".to_string(), 3)].into(),
+        };
+
+        let pending = scripted_mod
+            .fire_on_production_complete(&event, None, &HashMap::new())
+            .unwrap();
+
+        assert_eq!(pending.len(), 1);
+        match &pending[0] {
+            PendingScriptEvent::StartProduction {
+                building,
+                recipe_id,
+            } => {
+                assert_eq!(*building, Entity::from_bits(42));
+                assert_eq!(recipe_id, "make_planks");
+            }
+            other => panic!("unexpected pending event: {:?}", other),
+        }
+    }
+}
@@ -1,4 +1,11 @@
-use crate::{events::*, resources::*, systems::*};
+use crate::{
+    events::*,
+    resources::*,
+    scripting::{
+        load_mod_script_system, reload_mod_scripts_system, script_event_hook_system, ScriptHost,
+    },
+    systems::*,
+};
 use bevy::prelude::*;
 
 /// Main simulation app that runs headless
@@ -17,8 +24,22 @@ impl SimulationApp {
         app.init_resource::<GameTick>()
             .init_resource::<MapData>()
             .init_resource::<PathfindingCache>()
+            .init_resource::<FlowFieldCache>()
+            .init_resource::<PendingPathfindingRequests>()
             .init_resource::<PerformanceMetrics>()
-            .init_resource::<GameConfig>();
+            .init_resource::<TickTimingHistory>()
+            .init_resource::<GameConfig>()
+            .init_resource::<PheromoneField>()
+            .init_resource::<Reservations>()
+            .init_resource::<Visibility>()
+            .init_resource::<ConsiderationRegistry>()
+            .init_resource::<SpatialIndex>()
+            .init_resource::<PowerGrid>()
+            .init_resource::<MarketPrices>()
+            .init_resource::<PlayerMoney>()
+            .init_resource::<BeltNetwork>()
+            .init_resource::<FrameAllocator>()
+            .init_non_send_resource::<ScriptHost>();
 
         // Add events
         app.add_event::<PlaceBuildingEvent>()
@@ -29,13 +50,16 @@ impl SimulationApp {
             .add_event::<BuildingConstructedEvent>()
             .add_event::<ProductionCompletedEvent>()
             .add_event::<PathfindingRequestEvent>()
+            .add_event::<PathfindingFailedEvent>()
             .add_event::<MapChangedEvent>()
             .add_event::<SaveGameEvent>()
             .add_event::<LoadGameEvent>()
             .add_event::<ReplayEvent>()
             .add_event::<ProfileEvent>()
             .add_event::<LoadModEvent>()
-            .add_event::<ReloadConfigEvent>();
+            .add_event::<ReloadConfigEvent>()
+            .add_event::<WareRequestEvent>()
+            .add_event::<BuildRoadEvent>();
 
         // Add systems in order of execution
         app.add_systems(
@@ -43,24 +67,42 @@ impl SimulationApp {
             (
                 // Core tick system
                 advance_tick_system,
+                // Mod scripting (loads/hot-reloads before the rest of the tick runs)
+                load_mod_script_system,
+                reload_mod_scripts_system,
                 // Input processing
-                building_placement_system,
-                worker_assignment_system,
-                start_production_system,
+                building_placement_system_timed,
+                spatial_index_system_timed,
+                flag_spawning_system_timed,
+                road_building_system_timed,
+                carrier_assignment_system_timed,
+                worker_assignment_system_timed,
+                start_production_system_timed,
+                power_grid_system_timed,
+                belt_network_system_timed,
                 // Simulation systems
-                construction_system,
-                production_system,
-                worker_ai_system,
-                pathfinding_system,
-                movement_system,
-                transport_system,
-                resource_distribution_system,
-                transport_completion_system,
+                construction_system_timed,
+                production_system_timed,
+                market_system_timed,
+                script_event_hook_system,
+                visibility_system_timed,
+                worker_ai_system_timed,
+                worker_destination_revalidation_system_timed,
+                carrier_arrival_system_timed,
+                ware_request_system_timed,
+                carrier_dispatch_system_timed,
+                pathfinding_system_timed,
+                movement_system_timed,
+                transport_system_timed,
+                resource_distribution_system_timed,
+                transport_completion_system_timed,
                 // Cleanup and maintenance
-                invalidate_pathfinding_cache_system,
+                pheromone_decay_system_timed,
+                invalidate_pathfinding_cache_system_timed,
+                repair_paths_system_timed,
                 profile_systems_system,
                 // Worker spawning (only at start)
-                spawn_workers_system,
+                spawn_workers_system_timed,
             )
                 .chain(),
         );
@@ -73,11 +115,24 @@ impl SimulationApp {
         // Generate demo map
         let mut map = MapData::new(64, 64);
         crate::map::generate_demo_map(&mut map);
+        self.app
+            .insert_resource(Visibility::new(map.width, map.height));
         self.app.insert_resource(map);
 
         log::info!("Demo simulation initialized");
     }
 
+    /// Initialize the simulation with a procedurally generated map, deterministic
+    /// for a given `MapGenerationConfig` (same config always yields the same map).
+    pub fn initialize_from_config(&mut self, config: MapGenerationConfig) {
+        let map = crate::map::generate_map(&config);
+        self.app
+            .insert_resource(Visibility::new(map.width, map.height));
+        self.app.insert_resource(map);
+
+        log::info!("Simulation initialized from generated map");
+    }
+
     /// Run a single simulation tick
     pub fn tick(&mut self) {
         self.app.update();
@@ -115,29 +170,44 @@ impl SimulationApp {
         self.app.world_mut().get_resource_mut::<T>()
     }
 
-    /// Calculate state hash for determinism verification
+    /// Calculate a full canonical state hash for determinism verification.
+    /// Folds together the same per-category digest [`Self::calculate_state_digest`]
+    /// computes, so two runs only hash equal when every component category
+    /// (position, stockpiles, construction/production progress, worker
+    /// assignments, ...) actually matches - not just the tick and entity
+    /// count.
     pub fn calculate_state_hash(&self) -> u64 {
         use std::collections::hash_map::DefaultHasher;
         use std::hash::{Hash, Hasher};
 
         let mut hasher = DefaultHasher::new();
-
-        // Hash game tick
-        self.app
-            .world()
-            .resource::<GameTick>()
-            .current
-            .hash(&mut hasher);
-
-        // Hash entity count (simple determinism check)
-        let entity_count = self.app.world().entities().len();
-        entity_count.hash(&mut hasher);
-
-        // In a full implementation, you'd hash all relevant component data
-
+        self.calculate_state_digest().hash(&mut hasher);
         hasher.finish()
     }
 
+    /// Calculate a per-category [`StateDigest`] of canonical simulation
+    /// state for this tick. Unlike [`Self::calculate_state_hash`], this
+    /// hashes each component category separately (sorted by position and
+    /// then by content, so neither query iteration order nor entity
+    /// allocation order affects the result), so a replay
+    /// verification failure can point at *which* category diverged rather
+    /// than just reporting a single mismatched number.
+    pub fn calculate_state_digest(&self) -> StateDigest {
+        let world = self.app.world();
+
+        StateDigest {
+            tick: world.resource::<GameTick>().current,
+            entity_count: world.entities().len() as u64,
+            position: hash_component_category::<crate::components::Position>(world),
+            building: hash_component_category::<crate::components::Building>(world),
+            stockpile: hash_stockpile_category(world),
+            worker: hash_worker_category(world),
+            producer: hash_component_category::<crate::components::Producer>(world),
+            tile: hash_component_category::<crate::components::Tile>(world),
+            road: hash_component_category::<crate::components::Road>(world),
+        }
+    }
+
     /// Save current state to file
     pub fn save_state(&mut self, filename: &str) -> anyhow::Result<()> {
         crate::save::save_game_state(self.app.world_mut(), filename)
@@ -155,6 +225,187 @@ impl Default for SimulationApp {
     }
 }
 
+/// Per-category digest of canonical simulation state at a single tick.
+///
+/// Each field hashes one component category independently (sorted by
+/// entity index for order-independence) so replay verification can report
+/// *which* category first diverged instead of just a single pass/fail bit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct StateDigest {
+    pub tick: u64,
+    pub entity_count: u64,
+    pub position: u64,
+    pub building: u64,
+    pub stockpile: u64,
+    pub worker: u64,
+    pub producer: u64,
+    pub tile: u64,
+    pub road: u64,
+}
+
+impl StateDigest {
+    /// Names of every component category whose digest differs between
+    /// `self` and `other`, in a stable order.
+    pub fn diverging_categories(&self, other: &StateDigest) -> Vec<&'static str> {
+        let mut categories = Vec::new();
+        if self.entity_count != other.entity_count {
+            categories.push("entity_count");
+        }
+        if self.position != other.position {
+            categories.push("position");
+        }
+        if self.building != other.building {
+            categories.push("building");
+        }
+        if self.stockpile != other.stockpile {
+            categories.push("stockpile");
+        }
+        if self.worker != other.worker {
+            categories.push("worker");
+        }
+        if self.producer != other.producer {
+            categories.push("producer");
+        }
+        if self.tile != other.tile {
+            categories.push("tile");
+        }
+        if self.road != other.road {
+            categories.push("road");
+        }
+        categories
+    }
+}
+
+/// A deterministic ordering key for an entity: just its `Position` when it
+/// has one, falling back to a constant for positionless entities - never the
+/// raw entity index, since that's an allocation-order artifact of however
+/// this particular `World` was built, not a property of game state. Two
+/// independently-constructed worlds reaching the same logical state (e.g. a
+/// live run vs. one replayed from an event log, where entities were
+/// allocated in a different arena) need to compare equal, including when
+/// multiple entities intentionally share a tile (workers and in-progress
+/// construction both allow this). Callers break remaining ties by sorting on
+/// each item's own hashed content instead, so same-position entities with
+/// identical content (truly indistinguishable game state) always land in the
+/// same relative order regardless of which one's the "first" by entity id,
+/// and same-position entities with different content still sort
+/// deterministically by that content.
+fn entity_sort_key(entity_ref: &bevy::ecs::world::EntityRef) -> (i32, i32) {
+    entity_ref
+        .get::<crate::components::Position>()
+        .map(|position| (position.x, position.y))
+        .unwrap_or((i32::MAX, i32::MAX))
+}
+
+/// Hashes every live entity's `T` component, sorted by [`entity_sort_key`]
+/// and then by each entity's own formatted content, so the result doesn't
+/// depend on archetype/query iteration order or on raw entity allocation
+/// order - including when two entities share a tile.
+fn hash_component_category<T: Component + std::fmt::Debug>(world: &World) -> u64 {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut items: Vec<((i32, i32), String)> = world
+        .iter_entities()
+        .filter_map(|entity_ref| {
+            entity_ref
+                .get::<T>()
+                .map(|component| (entity_sort_key(&entity_ref), format!("{component:?}")))
+        })
+        .collect();
+    items.sort();
+
+    let mut hasher = DefaultHasher::new();
+    for (key, debug_str) in &items {
+        key.hash(&mut hasher);
+        debug_str.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Hashes `Stockpile` components with their `items` map sorted by item key
+/// first - `HashMap`'s iteration order (and hence its `Debug` output) isn't
+/// stable across processes, which would otherwise make replay verification
+/// report a false divergence between two runs holding identical stock.
+fn hash_stockpile_category(world: &World) -> u64 {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut items: Vec<((i32, i32), u32, Vec<(String, u32)>)> = world
+        .iter_entities()
+        .filter_map(|entity_ref| {
+            entity_ref
+                .get::<crate::components::Stockpile>()
+                .map(|stockpile| {
+                    let mut sorted_items: Vec<(String, u32)> = stockpile
+                        .items
+                        .iter()
+                        .map(|(k, v)| (k.clone(), *v))
+                        .collect();
+                    sorted_items.sort_by(|(a, _), (b, _)| a.cmp(b));
+                    (
+                        entity_sort_key(&entity_ref),
+                        stockpile.capacity,
+                        sorted_items,
+                    )
+                })
+        })
+        .collect();
+    items.sort();
+
+    let mut hasher = DefaultHasher::new();
+    for (key, capacity, sorted_items) in &items {
+        key.hash(&mut hasher);
+        capacity.hash(&mut hasher);
+        sorted_items.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Hashes `Worker` components, sorting `skills` by recipe id and
+/// deliberately excluding `id` (a randomly generated `Uuid`, not reproducible
+/// across a replayed/reloaded run) so only the deterministic, replay-relevant
+/// fields - type, task, assignments, carried goods, movement speed, skills -
+/// participate.
+fn hash_worker_category(world: &World) -> u64 {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut items: Vec<((i32, i32), String)> = world
+        .iter_entities()
+        .filter_map(|entity_ref| {
+            entity_ref.get::<crate::components::Worker>().map(|worker| {
+                let mut sorted_skills: Vec<(String, String)> = worker
+                    .skills
+                    .iter()
+                    .map(|(recipe_id, level)| (recipe_id.clone(), format!("{level:?}")))
+                    .collect();
+                sorted_skills.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+                let summary = format!(
+                    "worker_type={:?} assigned_building={:?} current_task={:?} carrying={:?} movement_speed={:?} skills={:?} assigned_road_segment={:?}",
+                    worker.worker_type,
+                    worker.assigned_building,
+                    worker.current_task,
+                    worker.carrying,
+                    worker.movement_speed,
+                    sorted_skills,
+                    worker.assigned_road_segment,
+                );
+                (entity_sort_key(&entity_ref), summary)
+            })
+        })
+        .collect();
+    items.sort();
+
+    let mut hasher = DefaultHasher::new();
+    for (key, summary) in &items {
+        key.hash(&mut hasher);
+        summary.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -202,4 +453,66 @@ mod tests {
         // Verify building was placed (in a real test, you'd query for the building)
         assert!(sim.current_tick() > 0);
     }
+
+    #[test]
+    fn test_stockpile_hash_ignores_item_insertion_order() {
+        use crate::components::Stockpile;
+
+        let mut world_a = World::new();
+        let mut stockpile_a = Stockpile::new(10);
+        stockpile_a.items.insert("wood".to_string(), 3);
+        stockpile_a.items.insert("planks".to_string(), 1);
+        world_a.spawn((Position::new(1, 1), stockpile_a));
+
+        let mut world_b = World::new();
+        let mut stockpile_b = Stockpile::new(10);
+        stockpile_b.items.insert("planks".to_string(), 1);
+        stockpile_b.items.insert("wood".to_string(), 3);
+        world_b.spawn((Position::new(1, 1), stockpile_b));
+
+        assert_eq!(
+            hash_stockpile_category(&world_a),
+            hash_stockpile_category(&world_b)
+        );
+    }
+
+    #[test]
+    fn test_worker_hash_ignores_same_tile_spawn_order() {
+        use crate::components::Worker;
+
+        // Two workers intentionally sharing a tile (e.g. simultaneous
+        // construction builders) spawned in opposite order across two
+        // worlds - entity ids come out reversed between them, but the hash
+        // must still agree since it's the same logical state either way.
+        let mut world_a = World::new();
+        world_a.spawn((Position::new(3, 3), Worker::new("carrier".to_string())));
+        world_a.spawn((Position::new(3, 3), Worker::new("builder".to_string())));
+
+        let mut world_b = World::new();
+        world_b.spawn((Position::new(3, 3), Worker::new("builder".to_string())));
+        world_b.spawn((Position::new(3, 3), Worker::new("carrier".to_string())));
+
+        assert_eq!(
+            hash_worker_category(&world_a),
+            hash_worker_category(&world_b)
+        );
+    }
+
+    #[test]
+    fn test_worker_hash_ignores_random_id() {
+        use crate::components::Worker;
+
+        let mut world_a = World::new();
+        world_a.spawn((Position::new(2, 2), Worker::new("carrier".to_string())));
+
+        let mut world_b = World::new();
+        world_b.spawn((Position::new(2, 2), Worker::new("carrier".to_string())));
+
+        // Two freshly constructed workers get distinct `Uuid::new_v4` ids but
+        // are otherwise identical, so the replay-relevant hash must agree.
+        assert_eq!(
+            hash_worker_category(&world_a),
+            hash_worker_category(&world_b)
+        );
+    }
 }
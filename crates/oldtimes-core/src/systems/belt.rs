@@ -0,0 +1,154 @@
+use crate::{
+    components::{BeltSegment, Splitter},
+    events::MapChangedEvent,
+    resources::{BeltNetwork, BeltRoute},
+};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// Rebuilds the belt/splitter routing table from every placed `BeltSegment`,
+/// grouped by source and item and ratio-split per any `Splitter` attached to
+/// that source. Recomputed only when `MapChangedEvent` fires - the same
+/// recompute-on-invalidation approach `power_grid_system` uses, since placing
+/// or removing a belt is the only thing that can change this graph.
+pub fn belt_network_system(
+    mut network: ResMut<BeltNetwork>,
+    mut events: EventReader<MapChangedEvent>,
+    segments: Query<&BeltSegment>,
+    splitters: Query<&Splitter>,
+) {
+    if events.read().next().is_none() {
+        return;
+    }
+
+    let mut by_source_item: HashMap<(Entity, String), Vec<&BeltSegment>> = HashMap::new();
+    for segment in segments.iter() {
+        by_source_item
+            .entry((segment.from, segment.item.clone()))
+            .or_default()
+            .push(segment);
+    }
+
+    network.clear();
+    let mut routes_by_source: HashMap<Entity, Vec<BeltRoute>> = HashMap::new();
+
+    for ((source, item), outgoing) in by_source_item {
+        let splitter = splitters.get(source).ok();
+        let weights: Vec<f32> = outgoing
+            .iter()
+            .map(|segment| {
+                splitter
+                    .and_then(|splitter| splitter.ratios.get(&segment.to).copied())
+                    .unwrap_or(1.0)
+            })
+            .collect();
+        let total_weight: f32 = weights.iter().sum();
+
+        for (segment, weight) in outgoing.iter().zip(weights.iter()) {
+            let share = if total_weight > 0.0 {
+                weight / total_weight
+            } else {
+                0.0
+            };
+            routes_by_source.entry(source).or_default().push(BeltRoute {
+                to: segment.to,
+                item: item.clone(),
+                capacity: segment.capacity,
+                share,
+            });
+        }
+    }
+
+    for (source, routes) in routes_by_source {
+        network.set_routes(source, routes);
+    }
+
+    log::debug!("Belt network recomputed");
+}
+
+crate::timed_system!(
+    belt_network_system_timed,
+    belt_network_system,
+    "belt_network_system",
+    network: ResMut<BeltNetwork>,
+    events: EventReader<MapChangedEvent>,
+    segments: Query<&BeltSegment>,
+    splitters: Query<&Splitter>,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::MapChangeType;
+
+    #[test]
+    fn test_belt_network_splits_evenly_with_no_splitter_ratios() {
+        let mut world = World::new();
+        world.init_resource::<BeltNetwork>();
+        world.init_resource::<Events<MapChangedEvent>>();
+
+        let source = world.spawn_empty().id();
+        let dest_a = world.spawn_empty().id();
+        let dest_b = world.spawn_empty().id();
+
+        world.spawn(BeltSegment::new(source, dest_a, "wood".to_string(), 10));
+        world.spawn(BeltSegment::new(source, dest_b, "wood".to_string(), 10));
+
+        world
+            .resource_mut::<Events<MapChangedEvent>>()
+            .send(MapChangedEvent {
+                position: crate::components::Position::new(0, 0),
+                change_type: MapChangeType::BuildingPlaced,
+                affected_tiles: vec![crate::components::Position::new(0, 0)],
+            });
+
+        let mut system = IntoSystem::into_system(belt_network_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        let network = world.resource::<BeltNetwork>();
+        let routes = network.routes_for(source, "wood");
+        assert_eq!(routes.len(), 2);
+        assert!(routes
+            .iter()
+            .all(|route| (route.share - 0.5).abs() < f32::EPSILON));
+    }
+
+    #[test]
+    fn test_belt_network_honors_splitter_ratios() {
+        let mut world = World::new();
+        world.init_resource::<BeltNetwork>();
+        world.init_resource::<Events<MapChangedEvent>>();
+
+        let source = world.spawn_empty().id();
+        let dest_a = world.spawn_empty().id();
+        let dest_b = world.spawn_empty().id();
+
+        world.spawn(BeltSegment::new(source, dest_a, "wood".to_string(), 10));
+        world.spawn(BeltSegment::new(source, dest_b, "wood".to_string(), 10));
+
+        let mut ratios = std::collections::HashMap::new();
+        ratios.insert(dest_a, 3.0);
+        ratios.insert(dest_b, 1.0);
+        world.entity_mut(source).insert(Splitter { ratios });
+
+        world
+            .resource_mut::<Events<MapChangedEvent>>()
+            .send(MapChangedEvent {
+                position: crate::components::Position::new(0, 0),
+                change_type: MapChangeType::BuildingPlaced,
+                affected_tiles: vec![crate::components::Position::new(0, 0)],
+            });
+
+        let mut system = IntoSystem::into_system(belt_network_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        let network = world.resource::<BeltNetwork>();
+        let routes = network.routes_for(source, "wood");
+        let route_a = routes.iter().find(|route| route.to == dest_a).unwrap();
+        let route_b = routes.iter().find(|route| route.to == dest_b).unwrap();
+        assert_eq!(route_a.share, 0.75);
+        assert_eq!(route_b.share, 0.25);
+    }
+}
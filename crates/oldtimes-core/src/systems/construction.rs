@@ -1,7 +1,7 @@
 use crate::{
-    components::{Blocked, Building, Position, Stockpile},
-    events::{BuildingConstructedEvent, PlaceBuildingEvent},
-    resources::{GameConfig, GameTick},
+    components::{Blocked, Building, ConstructionMaterials, Position, Stockpile},
+    events::{BuildingConstructedEvent, MapChangeType, MapChangedEvent, PlaceBuildingEvent},
+    resources::{GameConfig, GameTick, Reservations, SpatialIndex},
 };
 use bevy::prelude::*;
 
@@ -10,7 +10,7 @@ pub fn building_placement_system(
     mut commands: Commands,
     mut events: EventReader<PlaceBuildingEvent>,
     config: Res<GameConfig>,
-    existing_buildings: Query<&Position, With<Building>>,
+    spatial_index: Res<SpatialIndex>,
 ) {
     for event in events.read() {
         let building_config = match config.buildings.get(&event.building_type) {
@@ -22,7 +22,7 @@ pub fn building_placement_system(
         };
 
         // Check if position is available
-        if is_position_occupied(&existing_buildings, event.position, building_config.size) {
+        if !spatial_index.footprint_is_clear(event.position, building_config.size) {
             log::warn!(
                 "Cannot place building at {:?} - position occupied",
                 event.position
@@ -36,6 +36,7 @@ pub fn building_placement_system(
                 event.position,
                 Building::new(event.building_type.clone(), building_config.worker_capacity),
                 Stockpile::new(building_config.stockpile_capacity),
+                ConstructionMaterials::new(building_config.construction_cost.clone()),
                 Blocked, // Buildings block movement
             ))
             .id();
@@ -44,12 +45,24 @@ pub fn building_placement_system(
     }
 }
 
+crate::timed_system!(
+    building_placement_system_timed,
+    building_placement_system,
+    "building_placement_system",
+    commands: Commands,
+    events: EventReader<PlaceBuildingEvent>,
+    config: Res<GameConfig>,
+    spatial_index: Res<SpatialIndex>,
+);
+
 /// System that handles building construction progress
 pub fn construction_system(
     mut query: Query<(Entity, &mut Building, &Position)>,
+    materials: Query<&ConstructionMaterials>,
     config: Res<GameConfig>,
     tick: Res<GameTick>,
     mut completed_events: EventWriter<BuildingConstructedEvent>,
+    mut reservations: ResMut<Reservations>,
 ) {
     let delta_time = tick.delta_time();
 
@@ -63,14 +76,27 @@ pub fn construction_system(
             None => continue,
         };
 
-        // For now, construction is automatic without requiring workers/materials
-        // In a full implementation, you'd check for assigned workers and materials
+        // Progress only accrues while a builder is actually assigned and
+        // every required material has been delivered; a building with no
+        // ledger (or an empty cost) has nothing to wait on materials-wise.
+        if building.assigned_workers == 0 {
+            continue;
+        }
+        let materials_ready = materials.get(entity).map_or(true, |m| m.is_fulfilled());
+        if !materials_ready {
+            continue;
+        }
+
         building.construction_progress += delta_time / building_config.construction_time;
 
         if building.construction_progress >= 1.0 {
             building.construction_progress = 1.0;
             building.is_constructed = true;
 
+            // Whatever materials were reserved for this build are now spent.
+            let total_cost: u32 = building_config.construction_cost.values().sum();
+            reservations.release_in(entity, total_cost);
+
             completed_events.send(BuildingConstructedEvent {
                 building: entity,
                 building_type: building.building_type.clone(),
@@ -86,30 +112,72 @@ pub fn construction_system(
     }
 }
 
-fn is_position_occupied(
-    existing_buildings: &Query<&Position, With<Building>>,
-    position: Position,
-    size: (u32, u32),
-) -> bool {
-    // Check if any existing building overlaps with the proposed building area
-    for existing_pos in existing_buildings.iter() {
-        // Simple overlap check - in a real game you'd check the actual building sizes
-        if existing_pos.x == position.x && existing_pos.y == position.y {
-            return true;
-        }
-
-        // Check if within building footprint
-        let dx = (existing_pos.x - position.x).abs() as u32;
-        let dy = (existing_pos.y - position.y).abs() as u32;
+crate::timed_system!(
+    construction_system_timed,
+    construction_system,
+    "construction_system",
+    query: Query<(Entity, &mut Building, &Position)>,
+    materials: Query<&ConstructionMaterials>,
+    config: Res<GameConfig>,
+    tick: Res<GameTick>,
+    completed_events: EventWriter<BuildingConstructedEvent>,
+    reservations: ResMut<Reservations>,
+);
 
-        if dx < size.0 && dy < size.1 {
-            return true;
+/// System that incrementally keeps `SpatialIndex` in sync with the world's
+/// buildings: a newly placed building (`Added<Building>`) has its footprint
+/// inserted, and one that's since been despawned (`RemovedComponents`) has
+/// whatever tiles it last claimed cleared. Runs every tick instead of
+/// rebuilding the whole index, since placement/removal is far rarer than
+/// the index is read.
+pub fn spatial_index_system(
+    mut index: ResMut<SpatialIndex>,
+    config: Res<GameConfig>,
+    added_buildings: Query<(Entity, &Position, &Building), Added<Building>>,
+    mut removed_buildings: RemovedComponents<Building>,
+    mut map_changed_events: EventWriter<MapChangedEvent>,
+) {
+    for (entity, position, building) in added_buildings.iter() {
+        let size = config
+            .buildings
+            .get(&building.building_type)
+            .map_or((1, 1), |building_config| building_config.size);
+        index.insert_building(entity, *position, size);
+        if let Some(tiles) = index.footprint_of(entity) {
+            map_changed_events.send(MapChangedEvent {
+                position: *position,
+                change_type: MapChangeType::BuildingPlaced,
+                affected_tiles: tiles.to_vec(),
+            });
         }
     }
 
-    false
+    for entity in removed_buildings.read() {
+        let affected_tiles = index.footprint_of(entity).map(<[Position]>::to_vec);
+        index.remove_entity(entity);
+        if let Some(affected_tiles) = affected_tiles {
+            if let Some(&position) = affected_tiles.first() {
+                map_changed_events.send(MapChangedEvent {
+                    position,
+                    change_type: MapChangeType::BuildingRemoved,
+                    affected_tiles,
+                });
+            }
+        }
+    }
 }
 
+crate::timed_system!(
+    spatial_index_system_timed,
+    spatial_index_system,
+    "spatial_index_system",
+    index: ResMut<SpatialIndex>,
+    config: Res<GameConfig>,
+    added_buildings: Query<(Entity, &Position, &Building), Added<Building>>,
+    removed_buildings: RemovedComponents<Building>,
+    map_changed_events: EventWriter<MapChangedEvent>,
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -128,4 +196,111 @@ mod tests {
         assert_eq!(positions.len(), 1);
         assert_eq!(positions[0], Position::new(5, 5));
     }
+
+    #[test]
+    fn test_spatial_index_tracks_added_and_removed_buildings() {
+        let mut world = World::new();
+        world.init_resource::<SpatialIndex>();
+        world.init_resource::<GameConfig>();
+        world.init_resource::<Events<MapChangedEvent>>();
+
+        let building_entity = world
+            .spawn((Position::new(5, 5), Building::new("test_building".to_string(), 1)))
+            .id();
+
+        let mut system = IntoSystem::into_system(spatial_index_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        {
+            let index = world.resource::<SpatialIndex>();
+            assert!(index.is_tile_occupied(5, 5));
+            assert_eq!(index.occupant_at(5, 5), Some(building_entity));
+        }
+        {
+            let events = world.resource::<Events<MapChangedEvent>>();
+            assert_eq!(events.len(), 1, "placement should announce its footprint");
+        }
+
+        world.despawn(building_entity);
+        system.run((), &mut world);
+
+        let index = world.resource::<SpatialIndex>();
+        assert!(!index.is_tile_occupied(5, 5));
+        assert_eq!(index.occupant_at(5, 5), None);
+
+        let events = world.resource::<Events<MapChangedEvent>>();
+        assert_eq!(events.len(), 2, "removal should also announce its footprint");
+    }
+
+    #[test]
+    fn test_construction_progress_waits_for_materials_then_completes() {
+        let mut world = World::new();
+        world.init_resource::<Events<BuildingConstructedEvent>>();
+        world.init_resource::<Reservations>();
+        world.init_resource::<GameConfig>();
+        world.insert_resource(GameTick::new(1)); // delta_time = 1.0s
+
+        let mut building = Building::new("lumberjack".to_string(), 2);
+        building.assigned_workers = 1;
+
+        let building_entity = world
+            .spawn((
+                Position::new(5, 5),
+                building,
+                ConstructionMaterials::new([("stone".to_string(), 5)].into()),
+            ))
+            .id();
+
+        let mut system = IntoSystem::into_system(construction_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        let building = world.get::<Building>(building_entity).unwrap();
+        assert_eq!(building.construction_progress, 0.0, "progress shouldn't start before materials arrive");
+
+        world
+            .get_mut::<ConstructionMaterials>(building_entity)
+            .unwrap()
+            .deliver("stone", 5);
+
+        system.run((), &mut world);
+
+        let building = world.get::<Building>(building_entity).unwrap();
+        assert!(building.construction_progress > 0.0, "progress should accrue once materials are fulfilled");
+    }
+
+    #[test]
+    fn test_construction_progress_waits_for_an_assigned_worker() {
+        let mut world = World::new();
+        world.init_resource::<Events<BuildingConstructedEvent>>();
+        world.init_resource::<Reservations>();
+        world.init_resource::<GameConfig>();
+        world.insert_resource(GameTick::new(1)); // delta_time = 1.0s
+
+        // Materials are already fully delivered, but no worker is assigned.
+        let mut materials = ConstructionMaterials::new([("stone".to_string(), 5)].into());
+        materials.deliver("stone", 5);
+
+        let building_entity = world
+            .spawn((
+                Position::new(5, 5),
+                Building::new("lumberjack".to_string(), 2),
+                materials,
+            ))
+            .id();
+
+        let mut system = IntoSystem::into_system(construction_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        let building = world.get::<Building>(building_entity).unwrap();
+        assert_eq!(building.construction_progress, 0.0, "progress shouldn't start with no worker assigned");
+
+        world.get_mut::<Building>(building_entity).unwrap().assigned_workers = 1;
+        system.run((), &mut world);
+
+        let building = world.get::<Building>(building_entity).unwrap();
+        assert!(building.construction_progress > 0.0, "progress should accrue once a worker is assigned");
+    }
 }
@@ -0,0 +1,550 @@
+use bevy::prelude::*;
+use crate::{
+    components::{Building, Flag, FlagQueue, Position, RoadSegment, Stockpile, WaitingWare, Worker, WorkerTask},
+    events::{BuildRoadEvent, PathfindingRequestEvent, PathfindingPriority, TaskCompletedEvent, WareRequestEvent},
+    resources::{PheromoneChannel, Reservations},
+};
+
+/// Widelands-style goods transport: wares wait at a building's `Flag` until
+/// a `carrier` worker bound to the `RoadSegment` toward their next hop picks
+/// them up. A carrier only ever walks its one segment back and forth - see
+/// `components::RoadSegment`/`WaitingWare` for the data model, and
+/// `worker::worker_ai_system`'s early-exit on `assigned_road_segment` for
+/// why carriers never show up as generic candidate-driven workers.
+
+/// Gives every newly placed building a flag at its own position to drop
+/// wares at. One flag per building, spawned once and never removed (mirrors
+/// how `Stockpile`/`Producer` are attached permanently at construction).
+pub fn flag_spawning_system(
+    mut commands: Commands,
+    buildings: Query<(Entity, &Position), Added<Building>>,
+) {
+    for (building_entity, position) in buildings.iter() {
+        commands.spawn((
+            Flag { building: building_entity },
+            FlagQueue::default(),
+            *position,
+        ));
+    }
+}
+
+crate::timed_system!(
+    flag_spawning_system_timed,
+    flag_spawning_system,
+    "flag_spawning_system",
+    commands: Commands,
+    buildings: Query<(Entity, &Position), Added<Building>>,
+);
+
+/// Handles `BuildRoadEvent`, connecting the two buildings' flags with a new
+/// `RoadSegment` (a standalone entity, same pattern as flags themselves).
+pub fn road_building_system(
+    mut commands: Commands,
+    mut events: EventReader<BuildRoadEvent>,
+    segments: Query<&RoadSegment>,
+    flags: Query<(Entity, &Flag)>,
+) {
+    for event in events.read() {
+        let Some(from_flag) = flags
+            .iter()
+            .find(|(_, flag)| flag.building == event.from_building)
+            .map(|(entity, _)| entity)
+        else {
+            log::warn!("Cannot build road: building {:?} has no flag yet", event.from_building);
+            continue;
+        };
+        let Some(to_flag) = flags
+            .iter()
+            .find(|(_, flag)| flag.building == event.to_building)
+            .map(|(entity, _)| entity)
+        else {
+            log::warn!("Cannot build road: building {:?} has no flag yet", event.to_building);
+            continue;
+        };
+
+        let already_connected = segments.iter().any(|segment| {
+            (segment.from_flag == from_flag && segment.to_flag == to_flag)
+                || (segment.from_flag == to_flag && segment.to_flag == from_flag)
+        });
+        if already_connected {
+            continue;
+        }
+
+        commands.spawn(RoadSegment::new(from_flag, to_flag));
+        log::info!(
+            "Built road between buildings {:?} and {:?}",
+            event.from_building, event.to_building
+        );
+    }
+}
+
+crate::timed_system!(
+    road_building_system_timed,
+    road_building_system,
+    "road_building_system",
+    commands: Commands,
+    events: EventReader<BuildRoadEvent>,
+    segments: Query<&RoadSegment>,
+    flags: Query<(Entity, &Flag)>,
+);
+
+/// Binds idle, unassigned workers to road segments that don't have a
+/// carrier yet, teleporting them to the segment's `from_flag` - the same
+/// "place directly via `Position`, no pathfinding" approach
+/// `worker::spawn_workers_system` already uses for brand new workers.
+pub fn carrier_assignment_system(
+    mut commands: Commands,
+    mut workers: Query<(Entity, &mut Worker)>,
+    mut segments: Query<(Entity, &mut RoadSegment)>,
+    flags: Query<&Position, With<Flag>>,
+) {
+    for (worker_entity, mut worker) in workers.iter_mut() {
+        if worker.assigned_road_segment.is_some() || worker.assigned_building.is_some() {
+            continue;
+        }
+        if !matches!(worker.current_task, WorkerTask::Idle) {
+            continue;
+        }
+
+        let Some((segment_entity, mut segment)) =
+            segments.iter_mut().find(|(_, segment)| segment.carrier.is_none())
+        else {
+            continue;
+        };
+
+        let Ok(from_pos) = flags.get(segment.from_flag) else {
+            continue;
+        };
+
+        segment.carrier = Some(worker_entity);
+        worker.assigned_road_segment = Some(segment_entity);
+        commands.entity(worker_entity).insert(*from_pos);
+
+        log::info!("Worker {:?} assigned as carrier for road segment {:?}", worker_entity, segment_entity);
+    }
+}
+
+crate::timed_system!(
+    carrier_assignment_system_timed,
+    carrier_assignment_system,
+    "carrier_assignment_system",
+    commands: Commands,
+    workers: Query<(Entity, &mut Worker)>,
+    segments: Query<(Entity, &mut RoadSegment)>,
+    flags: Query<&Position, With<Flag>>,
+);
+
+/// Handles `WareRequestEvent`: finds a stockpile (other than the requester)
+/// holding enough of the item, routes it across the road network to the
+/// requester's flag via breadth-first search, pulls it out of the source
+/// stockpile immediately, and queues it as a `WaitingWare` at the source's
+/// own flag for a carrier to start relaying.
+pub fn ware_request_system(
+    mut events: EventReader<WareRequestEvent>,
+    mut stockpiles: Query<(Entity, &mut Stockpile)>,
+    flags: Query<(Entity, &Flag)>,
+    mut flag_queues: Query<&mut FlagQueue>,
+    segments: Query<&RoadSegment>,
+    mut reservations: ResMut<Reservations>,
+) {
+    for event in events.read() {
+        let Some(dest_flag) = flags
+            .iter()
+            .find(|(_, flag)| flag.building == event.requesting_building)
+            .map(|(entity, _)| entity)
+        else {
+            log::warn!(
+                "Building {:?} requested {} but has no flag",
+                event.requesting_building, event.item
+            );
+            continue;
+        };
+
+        let source_entity = stockpiles
+            .iter()
+            .find(|(entity, stockpile)| {
+                *entity != event.requesting_building
+                    && stockpile.effective_item_count(&event.item, *entity, &reservations) >= event.amount
+            })
+            .map(|(entity, _)| entity);
+
+        let Some(source_entity) = source_entity else {
+            continue;
+        };
+
+        let Some(source_flag) = flags
+            .iter()
+            .find(|(_, flag)| flag.building == source_entity)
+            .map(|(entity, _)| entity)
+        else {
+            log::warn!("Stockpile {:?} has no flag, can't route {}", source_entity, event.item);
+            continue;
+        };
+
+        let Some(route) = find_flag_route(&segments, source_flag, dest_flag) else {
+            log::warn!(
+                "No road route from {:?} to {:?} for {}",
+                source_entity, event.requesting_building, event.item
+            );
+            continue;
+        };
+
+        let Ok((_, mut source_stockpile)) = stockpiles.get_mut(source_entity) else {
+            continue;
+        };
+        let removed = source_stockpile.remove_item(&event.item, event.amount);
+        if removed == 0 {
+            continue;
+        }
+
+        reservations.reserve_in(event.requesting_building, removed);
+
+        if let Ok(mut queue) = flag_queues.get_mut(source_flag) {
+            queue.waiting.push(WaitingWare {
+                item: event.item.clone(),
+                amount: removed,
+                route: route[1..].to_vec(),
+                destination_building: event.requesting_building,
+            });
+        }
+    }
+}
+
+crate::timed_system!(
+    ware_request_system_timed,
+    ware_request_system,
+    "ware_request_system",
+    events: EventReader<WareRequestEvent>,
+    stockpiles: Query<(Entity, &mut Stockpile)>,
+    flags: Query<(Entity, &Flag)>,
+    flag_queues: Query<&mut FlagQueue>,
+    segments: Query<&RoadSegment>,
+    reservations: ResMut<Reservations>,
+);
+
+/// Breadth-first search over the flag graph formed by every `RoadSegment`,
+/// returning the flags to visit from `from_flag` to `to_flag` inclusive, or
+/// `None` if they aren't connected yet. The network is small enough that
+/// rebuilding the adjacency on every call is cheaper than maintaining it as
+/// its own resource.
+fn find_flag_route(segments: &Query<&RoadSegment>, from_flag: Entity, to_flag: Entity) -> Option<Vec<Entity>> {
+    use std::collections::{HashMap, VecDeque};
+
+    if from_flag == to_flag {
+        return Some(vec![from_flag]);
+    }
+
+    let mut came_from: HashMap<Entity, Entity> = HashMap::new();
+    came_from.insert(from_flag, from_flag);
+    let mut queue = VecDeque::new();
+    queue.push_back(from_flag);
+
+    while let Some(current) = queue.pop_front() {
+        if current == to_flag {
+            break;
+        }
+        for segment in segments.iter() {
+            if let Some(neighbor) = segment.other_end(current) {
+                if !came_from.contains_key(&neighbor) {
+                    came_from.insert(neighbor, current);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+    }
+
+    if !came_from.contains_key(&to_flag) {
+        return None;
+    }
+
+    let mut route = vec![to_flag];
+    let mut current = to_flag;
+    while current != from_flag {
+        current = came_from[&current];
+        route.push(current);
+    }
+    route.reverse();
+    Some(route)
+}
+
+/// Dispatches idle carriers: if the flag a carrier is currently sitting at
+/// has a `WaitingWare` headed toward the other end of the carrier's
+/// segment, picks it up and starts the hop.
+pub fn carrier_dispatch_system(
+    mut workers: Query<(Entity, &mut Worker, &Position)>,
+    segments: Query<&RoadSegment>,
+    flags: Query<&Position, With<Flag>>,
+    mut flag_queues: Query<&mut FlagQueue>,
+    mut pathfinding_events: EventWriter<PathfindingRequestEvent>,
+) {
+    for (worker_entity, mut worker, worker_pos) in workers.iter_mut() {
+        let Some(segment_entity) = worker.assigned_road_segment else {
+            continue;
+        };
+        if !matches!(worker.current_task, WorkerTask::Idle) {
+            continue;
+        }
+
+        let Ok(segment) = segments.get(segment_entity) else {
+            continue;
+        };
+        let Ok(from_pos) = flags.get(segment.from_flag) else {
+            continue;
+        };
+        let Ok(to_pos) = flags.get(segment.to_flag) else {
+            continue;
+        };
+
+        let (current_flag, current_pos, other_flag, other_pos) = if worker_pos == from_pos {
+            (segment.from_flag, *from_pos, segment.to_flag, *to_pos)
+        } else if worker_pos == to_pos {
+            (segment.to_flag, *to_pos, segment.from_flag, *from_pos)
+        } else {
+            continue;
+        };
+
+        let Ok(mut queue) = flag_queues.get_mut(current_flag) else {
+            continue;
+        };
+
+        let Some(index) = queue.waiting.iter().position(|ware| ware.route.first() == Some(&other_flag)) else {
+            continue;
+        };
+        let ware = queue.waiting.remove(index);
+
+        worker.current_task = WorkerTask::Carrying {
+            from: current_pos,
+            to: other_pos,
+            destination: other_flag,
+            item: ware.item,
+            amount: ware.amount,
+            route: Some((ware.route[1..].to_vec(), ware.destination_building)),
+        };
+
+        pathfinding_events.send(PathfindingRequestEvent {
+            entity: worker_entity,
+            from: current_pos,
+            to: other_pos,
+            priority: PathfindingPriority::Normal,
+            channel: Some(PheromoneChannel::Haul),
+        });
+    }
+}
+
+crate::timed_system!(
+    carrier_dispatch_system_timed,
+    carrier_dispatch_system,
+    "carrier_dispatch_system",
+    workers: Query<(Entity, &mut Worker, &Position)>,
+    segments: Query<&RoadSegment>,
+    flags: Query<&Position, With<Flag>>,
+    flag_queues: Query<&mut FlagQueue>,
+    pathfinding_events: EventWriter<PathfindingRequestEvent>,
+);
+
+/// Resolves a carrier's arrival at the far end of its segment: either the
+/// ware has reached the building it was bound for and is deposited into its
+/// stockpile, or it's handed off onto the arrival flag's queue for the next
+/// carrier in the chain to pick up.
+pub fn carrier_arrival_system(
+    mut workers: Query<(Entity, &mut Worker, &Position)>,
+    mut stockpiles: Query<&mut Stockpile>,
+    mut flag_queues: Query<&mut FlagQueue>,
+    mut reservations: ResMut<Reservations>,
+    mut task_events: EventWriter<TaskCompletedEvent>,
+) {
+    for (worker_entity, mut worker, worker_pos) in workers.iter_mut() {
+        if worker.assigned_road_segment.is_none() {
+            continue;
+        }
+
+        let WorkerTask::Carrying { to, destination, item, amount, route, .. } = worker.current_task.clone() else {
+            continue;
+        };
+
+        if worker_pos.distance_to(&to) >= 1.0 {
+            continue;
+        }
+
+        match route {
+            Some((remaining, destination_building)) if remaining.is_empty() => {
+                if let Ok(mut stockpile) = stockpiles.get_mut(destination_building) {
+                    stockpile.add_item(item, amount);
+                }
+                reservations.release_in(destination_building, amount);
+
+                task_events.send(TaskCompletedEvent {
+                    worker: worker_entity,
+                    task_type: "delivery".to_string(),
+                    destination: Some(destination_building),
+                });
+            },
+            Some((remaining, destination_building)) => {
+                if let Ok(mut queue) = flag_queues.get_mut(destination) {
+                    queue.waiting.push(WaitingWare {
+                        item,
+                        amount,
+                        route: remaining,
+                        destination_building,
+                    });
+                }
+
+                task_events.send(TaskCompletedEvent {
+                    worker: worker_entity,
+                    task_type: "delivery".to_string(),
+                    destination: Some(destination),
+                });
+            },
+            None => {
+                log::warn!(
+                    "Carrier {:?} arrived at {:?} without a route; dropping {} {}",
+                    worker_entity, destination, amount, item
+                );
+            },
+        }
+
+        worker.carrying = None;
+        worker.current_task = WorkerTask::Idle;
+    }
+}
+
+crate::timed_system!(
+    carrier_arrival_system_timed,
+    carrier_arrival_system,
+    "carrier_arrival_system",
+    workers: Query<(Entity, &mut Worker, &Position)>,
+    stockpiles: Query<&mut Stockpile>,
+    flag_queues: Query<&mut FlagQueue>,
+    reservations: ResMut<Reservations>,
+    task_events: EventWriter<TaskCompletedEvent>,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ware_request_routes_through_road_network_and_queues_at_source_flag() {
+        let mut world = World::new();
+        world.init_resource::<Events<WareRequestEvent>>();
+        world.init_resource::<Reservations>();
+
+        let source_building = world.spawn(Stockpile::new(20)).id();
+        world.get_mut::<Stockpile>(source_building).unwrap().add_item("wood".to_string(), 10);
+        let dest_building = world.spawn(Stockpile::new(20)).id();
+
+        let source_flag = world
+            .spawn((Flag { building: source_building }, FlagQueue::default(), Position::new(0, 0)))
+            .id();
+        let dest_flag = world
+            .spawn((Flag { building: dest_building }, FlagQueue::default(), Position::new(5, 0)))
+            .id();
+        world.spawn(RoadSegment::new(source_flag, dest_flag));
+
+        world.resource_mut::<Events<WareRequestEvent>>().send(WareRequestEvent {
+            requesting_building: dest_building,
+            item: "wood".to_string(),
+            amount: 4,
+        });
+
+        let mut system = IntoSystem::into_system(ware_request_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        let source_stockpile = world.get::<Stockpile>(source_building).unwrap();
+        assert_eq!(source_stockpile.get_item_count("wood"), 6);
+
+        let source_queue = world.get::<FlagQueue>(source_flag).unwrap();
+        assert_eq!(source_queue.waiting.len(), 1);
+        assert_eq!(source_queue.waiting[0].amount, 4);
+        assert_eq!(source_queue.waiting[0].route, vec![dest_flag]);
+        assert_eq!(source_queue.waiting[0].destination_building, dest_building);
+
+        let reservations = world.resource::<Reservations>();
+        assert_eq!(reservations.reserved_in(dest_building), 4);
+    }
+
+    #[test]
+    fn test_carrier_dispatch_picks_up_waiting_ware_toward_its_segment() {
+        let mut world = World::new();
+        world.init_resource::<Events<PathfindingRequestEvent>>();
+
+        let flag_a = world
+            .spawn((Flag { building: Entity::from_raw(0) }, Position::new(0, 0)))
+            .id();
+        let flag_b = world
+            .spawn((Flag { building: Entity::from_raw(1) }, FlagQueue::default(), Position::new(5, 0)))
+            .id();
+
+        world.entity_mut(flag_a).insert(FlagQueue {
+            waiting: vec![WaitingWare {
+                item: "wood".to_string(),
+                amount: 4,
+                route: vec![flag_b],
+                destination_building: Entity::from_raw(1),
+            }],
+        });
+
+        let segment_entity = world.spawn(RoadSegment::new(flag_a, flag_b)).id();
+
+        let mut worker = Worker::new("worker".to_string());
+        worker.assigned_road_segment = Some(segment_entity);
+        let worker_entity = world.spawn((worker, Position::new(0, 0))).id();
+
+        let mut system = IntoSystem::into_system(carrier_dispatch_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        let worker = world.get::<Worker>(worker_entity).unwrap();
+        match &worker.current_task {
+            WorkerTask::Carrying { to, destination, item, amount, route, .. } => {
+                assert_eq!(*to, Position::new(5, 0));
+                assert_eq!(*destination, flag_b);
+                assert_eq!(item, "wood");
+                assert_eq!(*amount, 4);
+                assert_eq!(route.as_ref().unwrap().0, Vec::<Entity>::new());
+            },
+            other => panic!("expected worker to be carrying, got {:?}", other),
+        }
+
+        let flag_a_queue = world.get::<FlagQueue>(flag_a).unwrap();
+        assert!(flag_a_queue.waiting.is_empty());
+    }
+
+    #[test]
+    fn test_carrier_arrival_deposits_final_delivery_into_stockpile() {
+        let mut world = World::new();
+        world.init_resource::<Events<TaskCompletedEvent>>();
+        world.init_resource::<Reservations>();
+
+        let destination_building = world.spawn(Stockpile::new(20)).id();
+        let arrival_flag = world.spawn(FlagQueue::default()).id();
+        let segment_entity = world.spawn(RoadSegment::new(Entity::from_raw(0), arrival_flag)).id();
+
+        let mut worker = Worker::new("worker".to_string());
+        worker.assigned_road_segment = Some(segment_entity);
+        worker.current_task = WorkerTask::Carrying {
+            from: Position::new(0, 0),
+            to: Position::new(5, 0),
+            destination: arrival_flag,
+            item: "wood".to_string(),
+            amount: 4,
+            route: Some((Vec::new(), destination_building)),
+        };
+        world.resource_mut::<Reservations>().reserve_in(destination_building, 4);
+        let worker_entity = world.spawn((worker, Position::new(5, 0))).id();
+
+        let mut system = IntoSystem::into_system(carrier_arrival_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        let worker = world.get::<Worker>(worker_entity).unwrap();
+        assert!(matches!(worker.current_task, WorkerTask::Idle));
+
+        let stockpile = world.get::<Stockpile>(destination_building).unwrap();
+        assert_eq!(stockpile.get_item_count("wood"), 4);
+
+        let reservations = world.resource::<Reservations>();
+        assert_eq!(reservations.reserved_in(destination_building), 0);
+    }
+}
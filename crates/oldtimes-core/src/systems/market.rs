@@ -0,0 +1,128 @@
+use crate::{
+    components::Producer,
+    economy::EconomyAnalyzer,
+    resources::{GameConfig, GameTick, MarketPrices, PlayerMoney},
+};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// Per-tick settlement: turns every active producer's throughput into item
+/// flow, sells the net flow into the market at current prices to update
+/// `PlayerMoney`, then drifts `MarketPrices` so next tick's prices reflect
+/// this tick's supply/demand. `productivity_factor` (set by
+/// `production_system` from `PowerGrid::satisfaction`) feeds straight into
+/// the per-recipe rate, so a brownout shows up here as reduced income rather
+/// than the market assuming every producer always runs flat out.
+pub fn market_system(
+    mut prices: ResMut<MarketPrices>,
+    mut money: ResMut<PlayerMoney>,
+    config: Res<GameConfig>,
+    tick: Res<GameTick>,
+    producers: Query<&Producer>,
+) {
+    let mut production_rates: HashMap<String, f32> = HashMap::new();
+    for producer in producers.iter() {
+        if !producer.is_producing {
+            continue;
+        }
+        let Some(recipe) = config.recipes.get(&producer.recipe_id) else {
+            continue;
+        };
+        if recipe.production_time <= 0.0 {
+            continue;
+        }
+        let rate = producer.productivity_factor / recipe.production_time;
+        *production_rates
+            .entry(producer.recipe_id.clone())
+            .or_insert(0.0) += rate;
+    }
+
+    if production_rates.is_empty() {
+        return;
+    }
+
+    let analyzer = EconomyAnalyzer::new(config.clone());
+    let price_map = prices.as_map();
+    let flow_rates = analyzer.calculate_flow_rates(&production_rates);
+
+    money.balance += analyzer.value_flow(&production_rates, &price_map) * tick.delta_time();
+
+    const DRIFT_RATE: f32 = 0.001;
+    for (item, net_flow) in &flow_rates {
+        prices.drift(item, *net_flow, DRIFT_RATE);
+    }
+}
+
+crate::timed_system!(
+    market_system_timed,
+    market_system,
+    "market_system",
+    prices: ResMut<MarketPrices>,
+    money: ResMut<PlayerMoney>,
+    config: Res<GameConfig>,
+    tick: Res<GameTick>,
+    producers: Query<&Producer>,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::RecipeConfig;
+
+    fn config_with_recipe() -> GameConfig {
+        let mut config = GameConfig::default();
+        config.recipes.clear();
+        config.recipes.insert(
+            "harvest_wood".to_string(),
+            RecipeConfig {
+                name: "Harvest Wood".to_string(),
+                production_time: 1.0,
+                inputs: HashMap::new(),
+                outputs: [("wood".to_string(), 2)].into(),
+                required_building: "lumberjack".to_string(),
+            },
+        );
+        config
+    }
+
+    #[test]
+    fn test_market_system_credits_money_for_net_production() {
+        let mut world = World::new();
+        world.insert_resource(config_with_recipe());
+        world.init_resource::<GameTick>();
+        world.init_resource::<MarketPrices>();
+        world.insert_resource(PlayerMoney { balance: 0.0 });
+        world.init_resource::<Events<crate::events::ProfileEvent>>();
+
+        let mut producer = Producer::new("harvest_wood".to_string());
+        producer.is_producing = true;
+        producer.productivity_factor = 1.0;
+        world.spawn(producer);
+
+        let mut system = IntoSystem::into_system(market_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        // 1 recipe/s * base price 1.0/wood * 2 wood/recipe = 2.0/s, over a
+        // 1/20s tick at the default 20 TPS is 0.1.
+        let money = world.resource::<PlayerMoney>();
+        assert!(money.balance > 0.0);
+    }
+
+    #[test]
+    fn test_market_system_leaves_prices_untouched_with_no_active_producers() {
+        let mut world = World::new();
+        world.insert_resource(config_with_recipe());
+        world.init_resource::<GameTick>();
+        world.init_resource::<MarketPrices>();
+        world.init_resource::<PlayerMoney>();
+        world.init_resource::<Events<crate::events::ProfileEvent>>();
+
+        let mut system = IntoSystem::into_system(market_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        let prices = world.resource::<MarketPrices>();
+        assert_eq!(prices.price("wood"), MarketPrices::BASE_PRICE);
+    }
+}
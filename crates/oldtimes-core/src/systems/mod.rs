@@ -1,13 +1,25 @@
+pub mod belt;
 pub mod construction;
+pub mod logistics;
+pub mod market;
 pub mod pathfinding;
+pub mod power;
 pub mod production;
 pub mod tick;
 pub mod transport;
+pub mod visibility;
 pub mod worker;
+pub mod worker_ai;
 
+pub use belt::*;
 pub use construction::*;
+pub use logistics::*;
+pub use market::*;
 pub use pathfinding::*;
+pub use power::*;
 pub use production::*;
 pub use tick::*;
 pub use transport::*;
+pub use visibility::*;
 pub use worker::*;
+pub use worker_ai::*;
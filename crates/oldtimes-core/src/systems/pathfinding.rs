@@ -1,113 +1,724 @@
 use bevy::prelude::*;
 use pathfinding::prelude::astar;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 use crate::{
-    components::{Position, Pathfinding, Tile},
-    resources::{MapData, PathfindingCache},
-    events::{PathfindingRequestEvent, MapChangedEvent},
+    components::{Position, Pathfinding, MovementSpeed, Tile, DEFAULT_MOVEMENT_SPEED},
+    grid::GridShape,
+    resources::{FlowField, FlowFieldCache, FrameAllocator, GameConfig, MapData, PathfindingCache, PendingPathfindingRequests, PheromoneChannel, PheromoneField, SpatialIndex},
+    events::{PathfindingRequestEvent, PathfindingFailedEvent, MapChangedEvent, PathfindingPriority},
 };
 
-/// System that handles pathfinding requests
+/// System that handles pathfinding requests. Newly arrived requests join
+/// `pending` (already-queued leftovers from a previous tick that couldn't be
+/// serviced in time) and the combined queue is sorted highest-priority
+/// first, so a flood of `Normal`-priority auto-distribution hauls can't
+/// starve a `High`/`Critical` request behind it. Only
+/// `config.pathfinding.max_requests_per_tick` of them are resolved this
+/// tick; the rest stay queued for the next one instead of spiking frame
+/// time.
 pub fn pathfinding_system(
     mut commands: Commands,
     mut cache: ResMut<PathfindingCache>,
+    mut flow_fields: ResMut<FlowFieldCache>,
+    mut pending: ResMut<PendingPathfindingRequests>,
     map: Res<MapData>,
+    spatial_index: Res<SpatialIndex>,
+    pheromones: Res<PheromoneField>,
+    config: Res<GameConfig>,
     mut requests: EventReader<PathfindingRequestEvent>,
+    mut failed_events: EventWriter<PathfindingFailedEvent>,
+    frame_allocator: Res<FrameAllocator>,
 ) {
-    for request in requests.read() {
-        let path = find_path(&mut cache, &map, request.from, request.to);
-        
+    pending.extend_and_sort(requests.read().cloned());
+
+    let budget = config.pathfinding.max_requests_per_tick as usize;
+    for request in pending.requests.drain(..budget.min(pending.requests.len())) {
+        // Bulk hauls are usually many agents converging on a handful of
+        // stockpiles, so route them through a cached flow field instead of
+        // paying for a fresh A* each; one-off routes (construction fetches,
+        // revalidation probes, ...) keep using A* directly. A flow field
+        // miss (the goal is behind a wall cutting it off from `from`, say)
+        // still falls back to A* rather than leaving the agent stranded.
+        let path = if request.priority == PathfindingPriority::Normal {
+            let field = get_or_build_flow_field(&mut flow_fields, &map, &spatial_index, &[request.to], config.grid_shape, &frame_allocator);
+            path_from_flow_field(field, request.from, request.to).or_else(|| {
+                find_path(
+                    &mut cache,
+                    &map,
+                    &spatial_index,
+                    request.from,
+                    request.to,
+                    request.channel,
+                    &pheromones,
+                    config.pheromones.bias_strength,
+                    config.grid_shape,
+                    &frame_allocator,
+                )
+            })
+        } else {
+            find_path(
+                &mut cache,
+                &map,
+                &spatial_index,
+                request.from,
+                request.to,
+                request.channel,
+                &pheromones,
+                config.pheromones.bias_strength,
+                config.grid_shape,
+                &frame_allocator,
+            )
+        };
+
         if let Some(path) = path {
-            commands.entity(request.entity).insert(Pathfinding::new(path));
+            commands
+                .entity(request.entity)
+                .insert(Pathfinding::with_channel(path, request.channel));
         } else {
             log::warn!("No path found from {:?} to {:?}", request.from, request.to);
+            failed_events.send(PathfindingFailedEvent {
+                entity: request.entity,
+                from: request.from,
+                to: request.to,
+            });
         }
     }
 }
 
-/// System that moves entities along their paths
+crate::timed_system!(
+    pathfinding_system_timed,
+    pathfinding_system,
+    "pathfinding_system",
+    commands: Commands,
+    cache: ResMut<PathfindingCache>,
+    flow_fields: ResMut<FlowFieldCache>,
+    pending: ResMut<PendingPathfindingRequests>,
+    map: Res<MapData>,
+    spatial_index: Res<SpatialIndex>,
+    pheromones: Res<PheromoneField>,
+    config: Res<GameConfig>,
+    requests: EventReader<PathfindingRequestEvent>,
+    failed_events: EventWriter<PathfindingFailedEvent>,
+    frame_allocator: Res<FrameAllocator>,
+);
+
+/// System that moves entities along their paths. Each tick an entity spends
+/// `speed * delta_time` tiles of movement budget, consuming it segment by
+/// segment: a segment finished with budget to spare immediately rolls the
+/// leftover into the next one, so a fast unit can cross several tiles in a
+/// single tick, while a slow unit's partial progress carries over to the
+/// next tick via `Pathfinding::segment_progress` instead of being lost.
 pub fn movement_system(
-    mut query: Query<(&mut Position, &mut Pathfinding)>,
+    mut query: Query<(&mut Position, &mut Pathfinding, Option<&MovementSpeed>)>,
     tick: Res<crate::resources::GameTick>,
+    mut pheromones: ResMut<PheromoneField>,
+    config: Res<GameConfig>,
 ) {
     let delta_time = tick.delta_time();
-    
-    for (mut position, mut pathfinding) in query.iter_mut() {
+
+    for (mut position, mut pathfinding, speed) in query.iter_mut() {
         if pathfinding.is_complete() {
             continue;
         }
-        
-        if let Some(target) = pathfinding.current_target() {
-            // Simple movement - instantly move to next waypoint each tick
-            // In a real game, you'd interpolate based on movement speed
+
+        let mut budget = speed.map_or(DEFAULT_MOVEMENT_SPEED, |s| s.0) * delta_time;
+
+        while budget > 0.0 {
+            let Some(target) = pathfinding.current_target() else {
+                break;
+            };
+
+            let segment_length = position.distance_to(&target);
+            let remaining = (segment_length - pathfinding.segment_progress).max(0.0);
+
+            if budget < remaining {
+                pathfinding.segment_progress += budget;
+                break;
+            }
+
+            budget -= remaining;
             *position = target;
-            
+
+            if let Some(channel) = pathfinding.channel {
+                pheromones.deposit(
+                    channel,
+                    target,
+                    config.pheromones.deposit_amount,
+                    config.pheromones.max_level,
+                );
+            }
+
             if !pathfinding.advance_target() {
                 // Path completed, remove pathfinding component
                 // This would be done via commands in a real system
+                break;
             }
         }
     }
 }
 
-/// System that clears pathfinding cache when map changes
+crate::timed_system!(
+    movement_system_timed,
+    movement_system,
+    "movement_system",
+    query: Query<(&mut Position, &mut Pathfinding, Option<&MovementSpeed>)>,
+    tick: Res<crate::resources::GameTick>,
+    pheromones: ResMut<PheromoneField>,
+    config: Res<GameConfig>,
+);
+
+/// System that decays pheromone trails every tick so unused routes fade out.
+pub fn pheromone_decay_system(mut pheromones: ResMut<PheromoneField>, config: Res<GameConfig>) {
+    pheromones.decay(config.pheromones.decay_rate);
+}
+
+crate::timed_system!(
+    pheromone_decay_system_timed,
+    pheromone_decay_system,
+    "pheromone_decay_system",
+    pheromones: ResMut<PheromoneField>,
+    config: Res<GameConfig>,
+);
+
+/// System that invalidates stale cache entries when the map changes.
+/// Only paths and flow fields that actually pass through the changed
+/// tiles are dropped; an empty `affected_tiles` list (a full map regen)
+/// still wipes everything, since there's no locality to exploit there.
 pub fn invalidate_pathfinding_cache_system(
     mut cache: ResMut<PathfindingCache>,
+    mut flow_fields: ResMut<FlowFieldCache>,
+    mut events: EventReader<MapChangedEvent>,
+) {
+    for event in events.read() {
+        if event.affected_tiles.is_empty() {
+            cache.clear();
+            flow_fields.clear();
+            log::debug!("Pathfinding cache and flow fields cleared due to a full map change");
+        } else {
+            cache.invalidate_crossing(&event.affected_tiles);
+            flow_fields.invalidate_crossing(&event.affected_tiles);
+            log::debug!(
+                "Pathfinding cache and flow fields invalidated near {:?}",
+                event.affected_tiles
+            );
+        }
+    }
+}
+
+crate::timed_system!(
+    invalidate_pathfinding_cache_system_timed,
+    invalidate_pathfinding_cache_system,
+    "invalidate_pathfinding_cache_system",
+    cache: ResMut<PathfindingCache>,
+    flow_fields: ResMut<FlowFieldCache>,
+    events: EventReader<MapChangedEvent>,
+);
+
+/// System that patches `Pathfinding` paths a map change just cut through,
+/// instead of leaving the walker to march into a wall. Only the untraveled
+/// suffix of an affected path is recomputed - the waypoints already walked
+/// are left alone - using a D* Lite-style replan from the entity's current
+/// position to its original goal.
+pub fn repair_paths_system(
+    mut query: Query<(&Position, &mut Pathfinding)>,
+    map: Res<MapData>,
+    spatial_index: Res<SpatialIndex>,
+    config: Res<GameConfig>,
     mut events: EventReader<MapChangedEvent>,
+    frame_allocator: Res<FrameAllocator>,
 ) {
-    for _event in events.read() {
-        cache.clear();
-        log::debug!("Pathfinding cache cleared due to map change");
+    let changed_tiles: Vec<Position> = events
+        .read()
+        .flat_map(|event| event.affected_tiles.iter().copied())
+        .collect();
+    if changed_tiles.is_empty() {
+        return;
+    }
+
+    for (position, mut pathfinding) in query.iter_mut() {
+        if pathfinding.is_complete() {
+            continue;
+        }
+
+        let remaining = &pathfinding.path[pathfinding.current_target_index..];
+        if !remaining.iter().any(|pos| changed_tiles.contains(pos)) {
+            continue;
+        }
+
+        let Some(goal) = pathfinding.path.last().copied() else {
+            continue;
+        };
+
+        if let Some(mut suffix) = dstar_lite_repair(&map, &spatial_index, *position, goal, config.grid_shape, &frame_allocator) {
+            if suffix.first() == Some(position) {
+                suffix.remove(0);
+            }
+            pathfinding.path.truncate(pathfinding.current_target_index);
+            pathfinding.path.extend(suffix);
+        } else {
+            log::warn!(
+                "D* Lite repair found no route from {:?} to {:?}; leaving the stale path in place",
+                position,
+                goal
+            );
+        }
     }
 }
 
+crate::timed_system!(
+    repair_paths_system_timed,
+    repair_paths_system,
+    "repair_paths_system",
+    query: Query<(&Position, &mut Pathfinding)>,
+    map: Res<MapData>,
+    spatial_index: Res<SpatialIndex>,
+    config: Res<GameConfig>,
+    events: EventReader<MapChangedEvent>,
+    frame_allocator: Res<FrameAllocator>,
+);
+
+/// Cheap reachability probe: does *some* path from `from` to `to` currently
+/// exist? Used to revalidate an in-transit worker's destination without
+/// touching its `Pathfinding` component - a plain existence check, ignoring
+/// pheromone bias, so a worker isn't stranded walking toward a target a map
+/// edit just walled off.
+pub fn path_exists(
+    cache: &mut PathfindingCache,
+    map: &MapData,
+    spatial_index: &SpatialIndex,
+    from: Position,
+    to: Position,
+    grid_shape: GridShape,
+    frame_allocator: &FrameAllocator,
+) -> bool {
+    find_path(cache, map, spatial_index, from, to, None, &PheromoneField::default(), 0.0, grid_shape, frame_allocator).is_some()
+}
+
+#[allow(clippy::too_many_arguments)]
 fn find_path(
     cache: &mut PathfindingCache,
     map: &MapData,
+    spatial_index: &SpatialIndex,
     from: Position,
     to: Position,
+    channel: Option<PheromoneChannel>,
+    pheromones: &PheromoneField,
+    bias_strength: f32,
+    grid_shape: GridShape,
+    frame_allocator: &FrameAllocator,
 ) -> Option<Vec<Position>> {
-    // Check cache first
-    if let Some(cached_path) = cache.get(from, to) {
-        return Some(cached_path);
+    // Pheromone-biased routes are cached per (from, to) without regard to channel,
+    // and trail levels shift every tick, so they bypass the cache entirely.
+    if channel.is_none() {
+        if let Some(cached_path) = cache.get(from, to) {
+            return Some(cached_path);
+        }
     }
-    
+
     let result = astar(
         &from,
-        |pos| get_neighbors(map, *pos),
+        |pos| {
+            get_neighbors(
+                map,
+                spatial_index,
+                *pos,
+                channel,
+                pheromones,
+                bias_strength,
+                grid_shape,
+                &|candidate| candidate == to,
+                frame_allocator,
+            )
+        },
         |pos| pos.distance_to(&to) as u32,
         |pos| *pos == to,
     );
-    
+
     if let Some((path, _cost)) = result {
-        cache.insert(from, to, path.clone());
+        if channel.is_none() {
+            cache.insert(from, to, path.clone());
+        }
         Some(path)
     } else {
         None
     }
 }
 
-fn get_neighbors(map: &MapData, pos: Position) -> Vec<(Position, u32)> {
-    let mut neighbors = Vec::new();
-    
-    for dx in -1..=1 {
-        for dy in -1..=1 {
-            if dx == 0 && dy == 0 {
-                continue;
+/// Returns the cached flow field for `goals`, building it with a Dijkstra
+/// flood fill first if this goal set hasn't been seen since the last
+/// `MapChangedEvent`.
+fn get_or_build_flow_field<'a>(
+    flow_fields: &'a mut FlowFieldCache,
+    map: &MapData,
+    spatial_index: &SpatialIndex,
+    goals: &[Position],
+    grid_shape: GridShape,
+    frame_allocator: &FrameAllocator,
+) -> &'a FlowField {
+    let key = FlowFieldCache::key_for(goals);
+    if flow_fields.get(&key).is_none() {
+        let field = build_flow_field(map, spatial_index, &key, grid_shape, frame_allocator);
+        flow_fields.insert(key.clone(), field);
+    }
+    flow_fields.get(&key).expect("just inserted")
+}
+
+/// Uniform-cost (Dijkstra) expansion outward from `goals` over every
+/// passable tile, recording total movement cost back to the nearest goal
+/// and which neighbor to step toward to reduce it. Agents then need no
+/// per-agent search: they just follow `next_step` downhill.
+fn build_flow_field(map: &MapData, spatial_index: &SpatialIndex, goals: &[Position], grid_shape: GridShape, frame_allocator: &FrameAllocator) -> FlowField {
+    let mut field = FlowField::default();
+    let mut heap = BinaryHeap::new();
+
+    // Every one of `goals` is exempt from the occupancy filter below: a
+    // goal is virtually always a building/stockpile tile, and those are
+    // marked occupied in `SpatialIndex` the instant they're placed, so
+    // without this exemption the flood fill could never actually reach one.
+    let goal_set: std::collections::HashSet<Position> = goals.iter().copied().collect();
+    let is_goal = |pos: Position| goal_set.contains(&pos);
+
+    for &goal in goals {
+        if field.cost.contains_key(&goal) {
+            continue;
+        }
+        field.cost.insert(goal, 0);
+        heap.push(Reverse((0u32, goal)));
+    }
+
+    while let Some(Reverse((cost, pos))) = heap.pop() {
+        if cost > field.cost[&pos] {
+            continue;
+        }
+
+        for (neighbor, step_cost) in successors(map, spatial_index, pos, grid_shape, &is_goal, frame_allocator) {
+            let next_cost = cost + step_cost;
+            if field.cost.get(&neighbor).is_none_or(|&best| next_cost < best) {
+                field.cost.insert(neighbor, next_cost);
+                field.next_step.insert(neighbor, pos);
+                heap.push(Reverse((next_cost, neighbor)));
+            }
+        }
+    }
+
+    field
+}
+
+/// Walks `field.next_step` from `from` down to `to`, or `None` if `from`
+/// wasn't reached by the flood fill (e.g. it's cut off from every goal).
+fn path_from_flow_field(field: &FlowField, from: Position, to: Position) -> Option<Vec<Position>> {
+    if !field.contains(from) {
+        return None;
+    }
+
+    let mut path = vec![from];
+    let mut current = from;
+    while current != to {
+        current = field.next_step(current)?;
+        path.push(current);
+    }
+    Some(path)
+}
+
+const DSTAR_INFINITY: u32 = u32::MAX;
+
+/// D* Lite priority key, as in Koenig & Likhachev's formulation: nodes are
+/// popped in order of `[min(g,rhs) + h, min(g,rhs)]`, lexicographically.
+fn dstar_key(pos: Position, start: Position, g: u32, rhs: u32) -> (u32, u32) {
+    let min_g_rhs = g.min(rhs);
+    let h = pos.distance_to(&start) as u32;
+    (min_g_rhs.saturating_add(h), min_g_rhs)
+}
+
+/// All grid-adjacent positions of `pos`, with no passability filtering -
+/// used to enumerate both successors and predecessors, since the edge
+/// *cost* (not the adjacency) is what differs by direction.
+fn neighbor_positions(pos: Position, grid_shape: GridShape) -> Vec<Position> {
+    match grid_shape {
+        GridShape::Square => {
+            let mut positions = Vec::new();
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    if dx == 0 && dy == 0 {
+                        continue;
+                    }
+                    positions.push(Position::new(pos.x + dx, pos.y + dy));
+                }
+            }
+            positions
+        }
+        GridShape::Hex => crate::grid::hex_neighbors(pos).collect(),
+    }
+}
+
+/// Cost of stepping directly from `from` into `to`, or `None` if they
+/// aren't adjacent or `to` isn't passable. Delegates to `get_neighbors` so
+/// this always agrees with the cost A* and the flow field use. `is_destination`
+/// is forwarded to `get_neighbors` so the search's actual goal tile(s) stay
+/// reachable even though they're occupied (see `get_neighbors`).
+fn edge_cost(
+    map: &MapData,
+    spatial_index: &SpatialIndex,
+    from: Position,
+    to: Position,
+    grid_shape: GridShape,
+    is_destination: &dyn Fn(Position) -> bool,
+    frame_allocator: &FrameAllocator,
+) -> Option<u32> {
+    get_neighbors(
+        map,
+        spatial_index,
+        from,
+        None,
+        &PheromoneField::default(),
+        0.0,
+        grid_shape,
+        is_destination,
+        frame_allocator,
+    )
+    .into_iter()
+    .find(|&(pos, _)| pos == to)
+    .map(|(_, cost)| cost)
+}
+
+/// Predecessors of `pos`: grid neighbors `n` for which stepping `n -> pos`
+/// is a valid move, along with that move's cost.
+fn predecessors(
+    map: &MapData,
+    spatial_index: &SpatialIndex,
+    pos: Position,
+    grid_shape: GridShape,
+    is_destination: &dyn Fn(Position) -> bool,
+    frame_allocator: &FrameAllocator,
+) -> Vec<(Position, u32)> {
+    neighbor_positions(pos, grid_shape)
+        .into_iter()
+        .filter_map(|n| edge_cost(map, spatial_index, n, pos, grid_shape, is_destination, frame_allocator).map(|cost| (n, cost)))
+        .collect()
+}
+
+/// Successors of `pos`, i.e. the same thing `pathfinding_system` asks A*
+/// and the flow field for: each passable neighbor (occupied or not, if it's
+/// the search's destination per `is_destination`) and the cost to step into
+/// it.
+fn successors(
+    map: &MapData,
+    spatial_index: &SpatialIndex,
+    pos: Position,
+    grid_shape: GridShape,
+    is_destination: &dyn Fn(Position) -> bool,
+    frame_allocator: &FrameAllocator,
+) -> Vec<(Position, u32)> {
+    get_neighbors(
+        map,
+        spatial_index,
+        pos,
+        None,
+        &PheromoneField::default(),
+        0.0,
+        grid_shape,
+        is_destination,
+        frame_allocator,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn dstar_update_vertex(
+    u: Position,
+    start: Position,
+    goal: Position,
+    map: &MapData,
+    spatial_index: &SpatialIndex,
+    grid_shape: GridShape,
+    g: &mut std::collections::HashMap<Position, u32>,
+    rhs: &mut std::collections::HashMap<Position, u32>,
+    open: &mut BinaryHeap<Reverse<(u32, u32, Position)>>,
+    frame_allocator: &FrameAllocator,
+) {
+    if u != goal {
+        let best = successors(map, spatial_index, u, grid_shape, &|pos| pos == goal, frame_allocator)
+            .into_iter()
+            .filter_map(|(s, cost)| g.get(&s).map(|&g_s| g_s.saturating_add(cost)))
+            .min()
+            .unwrap_or(DSTAR_INFINITY);
+        rhs.insert(u, best);
+    }
+
+    let g_u = g.get(&u).copied().unwrap_or(DSTAR_INFINITY);
+    let rhs_u = rhs.get(&u).copied().unwrap_or(DSTAR_INFINITY);
+    if g_u != rhs_u {
+        let key = dstar_key(u, start, g_u, rhs_u);
+        open.push(Reverse((key.0, key.1, u)));
+    }
+}
+
+/// Repairs the path from `start` to `goal` using a D* Lite-style replan:
+/// `g` is the search's cost-so-far, `rhs` the one-step lookahead used to
+/// detect inconsistent nodes, and the open queue is ordered by
+/// `[min(g,rhs)+h, min(g,rhs)]` so the search stays focused around `start`
+/// and `goal` rather than flooding the whole map. This call always starts
+/// `ComputeShortestPath` from an empty `g`/`rhs` table - `Pathfinding`
+/// doesn't carry one between ticks - so it behaves like a single replan
+/// rather than an incrementally-reused one, but follows the same update
+/// rule a persistent D* Lite instance would use to patch around the tiles
+/// a `MapChangedEvent` just invalidated.
+fn dstar_lite_repair(
+    map: &MapData,
+    spatial_index: &SpatialIndex,
+    start: Position,
+    goal: Position,
+    grid_shape: GridShape,
+    frame_allocator: &FrameAllocator,
+) -> Option<Vec<Position>> {
+    if start == goal {
+        return Some(vec![start]);
+    }
+
+    let mut g: std::collections::HashMap<Position, u32> = std::collections::HashMap::new();
+    let mut rhs: std::collections::HashMap<Position, u32> = std::collections::HashMap::new();
+    let mut open: BinaryHeap<Reverse<(u32, u32, Position)>> = BinaryHeap::new();
+
+    rhs.insert(goal, 0);
+    let key = dstar_key(goal, start, DSTAR_INFINITY, 0);
+    open.push(Reverse((key.0, key.1, goal)));
+
+    let calculate_key = |pos: Position, g: &std::collections::HashMap<Position, u32>, rhs: &std::collections::HashMap<Position, u32>| {
+        dstar_key(
+            pos,
+            start,
+            g.get(&pos).copied().unwrap_or(DSTAR_INFINITY),
+            rhs.get(&pos).copied().unwrap_or(DSTAR_INFINITY),
+        )
+    };
+
+    loop {
+        let g_start = g.get(&start).copied().unwrap_or(DSTAR_INFINITY);
+        let rhs_start = rhs.get(&start).copied().unwrap_or(DSTAR_INFINITY);
+        let start_key = calculate_key(start, &g, &rhs);
+
+        let Some(&Reverse((top_a, top_b, _))) = open.peek() else {
+            break;
+        };
+        if (top_a, top_b) >= start_key && g_start == rhs_start {
+            break;
+        }
+
+        let Reverse((k_old_a, k_old_b, u)) = open.pop().unwrap();
+        let k_new = calculate_key(u, &g, &rhs);
+
+        if (k_old_a, k_old_b) < k_new {
+            open.push(Reverse((k_new.0, k_new.1, u)));
+            continue;
+        }
+
+        let g_u = g.get(&u).copied().unwrap_or(DSTAR_INFINITY);
+        let rhs_u = rhs.get(&u).copied().unwrap_or(DSTAR_INFINITY);
+
+        if g_u > rhs_u {
+            g.insert(u, rhs_u);
+            for (pred, _cost) in predecessors(map, spatial_index, u, grid_shape, &|pos| pos == goal, frame_allocator) {
+                dstar_update_vertex(pred, start, goal, map, spatial_index, grid_shape, &mut g, &mut rhs, &mut open, frame_allocator);
+            }
+        } else {
+            g.insert(u, DSTAR_INFINITY);
+            dstar_update_vertex(u, start, goal, map, spatial_index, grid_shape, &mut g, &mut rhs, &mut open, frame_allocator);
+            for (pred, _cost) in predecessors(map, spatial_index, u, grid_shape, &|pos| pos == goal, frame_allocator) {
+                dstar_update_vertex(pred, start, goal, map, spatial_index, grid_shape, &mut g, &mut rhs, &mut open, frame_allocator);
             }
-            
-            let new_pos = Position::new(pos.x + dx, pos.y + dy);
-            
-            if let Some(tile) = map.get_tile(new_pos.x, new_pos.y) {
-                if tile.is_passable() {
-                    let cost = (tile.movement_cost() * 100.0) as u32;
+        }
+    }
+
+    if rhs.get(&start).copied().unwrap_or(DSTAR_INFINITY) == DSTAR_INFINITY {
+        return None;
+    }
+
+    // Walk from `start` to `goal`, at each step taking the successor that
+    // minimizes g(successor) + edge cost - the standard D* Lite path
+    // extraction once the shortest-path values are consistent.
+    let mut path = vec![start];
+    let mut current = start;
+    let max_steps = (map.width as usize * map.height as usize).max(1) * 4;
+    for _ in 0..max_steps {
+        if current == goal {
+            return Some(path);
+        }
+        let next = successors(map, spatial_index, current, grid_shape, &|pos| pos == goal, frame_allocator)
+            .into_iter()
+            .filter_map(|(pos, cost)| g.get(&pos).map(|&g_pos| (pos, g_pos.saturating_add(cost))))
+            .min_by_key(|&(_, total)| total)
+            .map(|(pos, _)| pos)?;
+        path.push(next);
+        current = next;
+    }
+    None
+}
+
+/// Both grid shapes ever produce at most this many raw candidates (8 for
+/// `Square`, 6 for `Hex`), so this is the capacity every `get_neighbors` call
+/// bump-allocates its scratch `candidates` buffer with.
+const MAX_NEIGHBOR_CANDIDATES: usize = 8;
+
+#[allow(clippy::too_many_arguments)]
+fn get_neighbors(
+    map: &MapData,
+    spatial_index: &SpatialIndex,
+    pos: Position,
+    channel: Option<PheromoneChannel>,
+    pheromones: &PheromoneField,
+    bias_strength: f32,
+    grid_shape: GridShape,
+    is_destination: &dyn Fn(Position) -> bool,
+    frame_allocator: &FrameAllocator,
+) -> Vec<(Position, u32)> {
+    let mut candidates = frame_allocator.alloc_vec::<(Position, u32)>(MAX_NEIGHBOR_CANDIDATES);
+    match grid_shape {
+        GridShape::Square => {
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    if dx == 0 && dy == 0 {
+                        continue;
+                    }
                     // Diagonal movement costs more
                     let diagonal_cost = if dx != 0 && dy != 0 { 141 } else { 100 };
-                    neighbors.push((new_pos, cost * diagonal_cost / 100));
+                    candidates.push((Position::new(pos.x + dx, pos.y + dy), diagonal_cost));
+                }
+            }
+        }
+        GridShape::Hex => {
+            for neighbor in crate::grid::hex_neighbors(pos) {
+                candidates.push((neighbor, 100));
+            }
+        }
+    };
+
+    let mut neighbors = Vec::new();
+    for &(new_pos, diagonal_cost) in candidates.iter() {
+        // A tile occupied by a building is still a valid step when it's the
+        // search's actual destination: `spatial_index_system` marks a
+        // building's whole footprint occupied the instant it's placed
+        // (`construction.rs`), before it's even finished constructing, so
+        // every building/stockpile goal would otherwise be permanently
+        // unreachable.
+        if spatial_index.is_tile_occupied(new_pos.x, new_pos.y) && !is_destination(new_pos) {
+            continue;
+        }
+        if let Some(tile) = map.get_tile(new_pos.x, new_pos.y) {
+            if tile.is_passable() {
+                let mut cost = tile.movement_cost() * 100.0;
+                // Worn trails make a tile cheaper to traverse, but never
+                // free and never enough to make an impassable tile passable
+                // (that filter already happened above).
+                if let Some(channel) = channel {
+                    let level = pheromones.level(channel, new_pos);
+                    cost /= 1.0 + bias_strength * level;
                 }
+                let cost = cost as u32;
+                neighbors.push((new_pos, cost * diagonal_cost / 100));
             }
         }
     }
-    
+
     neighbors
 }
 
@@ -123,8 +734,9 @@ mod tests {
         
         let from = Position::new(0, 0);
         let to = Position::new(5, 5);
-        
-        let path = find_path(&mut cache, &map, from, to);
+        let spatial_index = SpatialIndex::default();
+
+        let path = find_path(&mut cache, &map, &spatial_index, from, to, None, &PheromoneField::default(), 0.5, GridShape::Square, &FrameAllocator::default());
         assert!(path.is_some());
         
         let path = path.unwrap();
@@ -132,6 +744,26 @@ mod tests {
         assert_eq!(path.last(), Some(&to));
     }
     
+    #[test]
+    fn test_path_exists_reaches_an_occupied_destination() {
+        // Every building/stockpile tile is marked occupied in `SpatialIndex`
+        // the instant it's placed, so a search whose destination *is* that
+        // tile must still be able to reach it.
+        let map = MapData::new(10, 10);
+        let mut cache = PathfindingCache::new(100);
+
+        let from = Position::new(0, 0);
+        let to = Position::new(5, 5);
+
+        let mut spatial_index = SpatialIndex::default();
+        spatial_index.insert_building(Entity::from_raw(0), to, (1, 1));
+
+        assert!(path_exists(&mut cache, &map, &spatial_index, from, to, GridShape::Square, &FrameAllocator::default()));
+
+        let path = find_path(&mut cache, &map, &spatial_index, from, to, None, &PheromoneField::default(), 0.5, GridShape::Square, &FrameAllocator::default()).unwrap();
+        assert_eq!(path.last(), Some(&to));
+    }
+
     #[test]
     fn test_pathfinding_with_obstacles() {
         let mut map = MapData::new(10, 10);
@@ -147,8 +779,9 @@ mod tests {
         let mut cache = PathfindingCache::new(100);
         let from = Position::new(0, 5);
         let to = Position::new(9, 5);
-        
-        let path = find_path(&mut cache, &map, from, to);
+        let spatial_index = SpatialIndex::default();
+
+        let path = find_path(&mut cache, &map, &spatial_index, from, to, None, &PheromoneField::default(), 0.5, GridShape::Square, &FrameAllocator::default());
         assert!(path.is_some());
         
         // Path should go around the obstacle
@@ -163,15 +796,160 @@ mod tests {
         
         let from = Position::new(0, 0);
         let to = Position::new(5, 5);
-        
+        let spatial_index = SpatialIndex::default();
+
         // First call should miss cache
-        let _path1 = find_path(&mut cache, &map, from, to);
+        let _path1 = find_path(&mut cache, &map, &spatial_index, from, to, None, &PheromoneField::default(), 0.5, GridShape::Square, &FrameAllocator::default());
         assert_eq!(cache.cache_misses, 1);
         assert_eq!(cache.cache_hits, 0);
-        
+
         // Second call should hit cache
-        let _path2 = find_path(&mut cache, &map, from, to);
+        let _path2 = find_path(&mut cache, &map, &spatial_index, from, to, None, &PheromoneField::default(), 0.5, GridShape::Square, &FrameAllocator::default());
         assert_eq!(cache.cache_misses, 1);
         assert_eq!(cache.cache_hits, 1);
     }
+
+    #[test]
+    fn test_pheromone_trail_biases_route_choice() {
+        let map = MapData::new(10, 10);
+        let mut pheromones = PheromoneField::default();
+        let mut cache = PathfindingCache::new(100);
+
+        let from = Position::new(0, 5);
+        let to = Position::new(9, 5);
+
+        // Lay a heavy trail straight along y=5, the direct route.
+        for x in 0..10 {
+            pheromones.deposit(PheromoneChannel::Haul, Position::new(x, 5), 10.0, 10.0);
+        }
+
+        let spatial_index = SpatialIndex::default();
+        let path = find_path(
+            &mut cache,
+            &map,
+            &spatial_index,
+            from,
+            to,
+            Some(PheromoneChannel::Haul),
+            &pheromones,
+            0.5,
+            GridShape::Square,
+            &FrameAllocator::default(),
+        )
+        .unwrap();
+
+        // Every step should stay on the reinforced row since it's now the cheapest route.
+        assert!(path.iter().all(|pos| pos.y == 5));
+    }
+
+    #[test]
+    fn test_pheromone_never_makes_impassable_tile_passable() {
+        let mut map = MapData::new(10, 10);
+        map.set_tile(5, 5, Tile {
+            tile_type: TileType::Water,
+            elevation: 0,
+        });
+
+        let mut pheromones = PheromoneField::default();
+        pheromones.deposit(PheromoneChannel::Haul, Position::new(5, 5), 1000.0, 1000.0);
+
+        let spatial_index = SpatialIndex::default();
+        let neighbors = get_neighbors(&map, &spatial_index, Position::new(4, 5), Some(PheromoneChannel::Haul), &pheromones, 0.5, GridShape::Square, &|_| false, &FrameAllocator::default());
+        assert!(!neighbors.iter().any(|(pos, _)| *pos == Position::new(5, 5)));
+    }
+
+    #[test]
+    fn test_flow_field_reaches_goal_and_routes_around_obstacles() {
+        let mut map = MapData::new(10, 10);
+
+        // Wall off the goal except for a gap at rows y=8 and y=9.
+        for y in 0..8 {
+            map.set_tile(5, y, Tile {
+                tile_type: TileType::Water,
+                elevation: 0,
+            });
+        }
+
+        let goal = Position::new(9, 0);
+        let spatial_index = SpatialIndex::default();
+        let field = build_flow_field(&map, &spatial_index, &[goal], GridShape::Square, &FrameAllocator::default());
+
+        assert_eq!(field.cost[&goal], 0);
+
+        let path = path_from_flow_field(&field, Position::new(0, 0), goal).unwrap();
+        assert_eq!(path.first(), Some(&Position::new(0, 0)));
+        assert_eq!(path.last(), Some(&goal));
+        // Every step must follow the carved gap, never the walled-off column.
+        assert!(path.iter().all(|pos| pos.x != 5 || pos.y == 8));
+    }
+
+    #[test]
+    fn test_flow_field_cache_reuses_field_for_same_goal_set() {
+        let map = MapData::new(10, 10);
+        let mut flow_fields = FlowFieldCache::default();
+        let goal = Position::new(5, 5);
+
+        let spatial_index = SpatialIndex::default();
+        let first = get_or_build_flow_field(&mut flow_fields, &map, &spatial_index, &[goal], GridShape::Square, &FrameAllocator::default()).clone();
+        let second = get_or_build_flow_field(&mut flow_fields, &map, &spatial_index, &[goal], GridShape::Square, &FrameAllocator::default());
+        assert_eq!(first.cost, second.cost);
+    }
+
+    #[test]
+    fn test_dstar_lite_repair_routes_around_a_newly_blocked_tile() {
+        let mut map = MapData::new(10, 10);
+
+        // Wall off the goal except for a gap at rows y=8 and y=9, the same shape a map
+        // change (e.g. a building placed at x=5) would carve into a route.
+        for y in 0..8 {
+            map.set_tile(5, y, Tile {
+                tile_type: TileType::Water,
+                elevation: 0,
+            });
+        }
+
+        let from = Position::new(0, 0);
+        let to = Position::new(9, 0);
+        let spatial_index = SpatialIndex::default();
+        let path = dstar_lite_repair(&map, &spatial_index, from, to, GridShape::Square, &FrameAllocator::default()).unwrap();
+
+        assert_eq!(path.first(), Some(&from));
+        assert_eq!(path.last(), Some(&to));
+        assert!(path.iter().all(|pos| pos.x != 5 || pos.y == 8));
+    }
+
+    #[test]
+    fn test_dstar_lite_repair_returns_none_when_goal_is_unreachable() {
+        let mut map = MapData::new(10, 10);
+        for y in 0..10 {
+            map.set_tile(5, y, Tile {
+                tile_type: TileType::Water,
+                elevation: 0,
+            });
+        }
+
+        let spatial_index = SpatialIndex::default();
+        let path = dstar_lite_repair(&map, &spatial_index, Position::new(0, 0), Position::new(9, 0), GridShape::Square, &FrameAllocator::default());
+        assert!(path.is_none());
+    }
+
+    #[test]
+    fn test_invalidate_crossing_only_drops_affected_paths() {
+        let mut cache = PathfindingCache::new(100);
+        cache.insert(
+            Position::new(0, 0),
+            Position::new(5, 0),
+            vec![Position::new(0, 0), Position::new(3, 0), Position::new(5, 0)],
+        );
+        cache.insert(
+            Position::new(0, 9),
+            Position::new(5, 9),
+            vec![Position::new(0, 9), Position::new(3, 9), Position::new(5, 9)],
+        );
+
+        cache.invalidate_crossing(&[Position::new(3, 0)]);
+
+        assert!(cache.cache.get(&(Position::new(0, 0), Position::new(5, 0))).is_none());
+        assert!(cache.cache.get(&(Position::new(0, 9), Position::new(5, 9))).is_some());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,226 @@
+use crate::{
+    components::{Building, Flag, RoadSegment},
+    events::{BuildingConstructedEvent, MapChangedEvent},
+    resources::{GameConfig, PowerGrid},
+};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+/// Rebuilds every power cluster's generation/demand balance. A cluster is a
+/// set of constructed buildings transitively linked by `RoadSegment`s - the
+/// same flag/road graph `logistics` routes goods over - found via
+/// union-find over building entities. Recomputed only when something that
+/// can change connectivity or totals happens - a `MapChangedEvent` (a
+/// building placed or removed), a newly built `RoadSegment`, or a
+/// `BuildingConstructedEvent` (a finished building starts to count) - not
+/// every tick.
+pub fn power_grid_system(
+    mut grid: ResMut<PowerGrid>,
+    mut events: EventReader<MapChangedEvent>,
+    mut constructed_events: EventReader<BuildingConstructedEvent>,
+    config: Res<GameConfig>,
+    buildings: Query<(Entity, &Building)>,
+    flags: Query<(Entity, &Flag)>,
+    segments: Query<&RoadSegment>,
+    new_segments: Query<(), Added<RoadSegment>>,
+) {
+    let map_changed = events.read().count() > 0;
+    let building_finished = constructed_events.read().count() > 0;
+    if !map_changed && !building_finished && new_segments.is_empty() {
+        return;
+    }
+
+    let flag_building: HashMap<Entity, Entity> = flags
+        .iter()
+        .map(|(flag_entity, flag)| (flag_entity, flag.building))
+        .collect();
+
+    let mut parent: HashMap<Entity, Entity> = buildings
+        .iter()
+        .map(|(entity, _)| (entity, entity))
+        .collect();
+
+    for segment in segments.iter() {
+        let (Some(&from_building), Some(&to_building)) = (
+            flag_building.get(&segment.from_flag),
+            flag_building.get(&segment.to_flag),
+        ) else {
+            continue;
+        };
+        union(&mut parent, from_building, to_building);
+    }
+
+    let mut generation_by_cluster: HashMap<Entity, f32> = HashMap::new();
+    let mut demand_by_cluster: HashMap<Entity, f32> = HashMap::new();
+    let mut cluster_of: HashMap<Entity, Entity> = HashMap::new();
+
+    for (entity, building) in buildings.iter() {
+        if !building.is_constructed {
+            continue;
+        }
+        let Some(building_config) = config.buildings.get(&building.building_type) else {
+            continue;
+        };
+
+        let root = find(&mut parent, entity);
+        cluster_of.insert(entity, root);
+        *generation_by_cluster.entry(root).or_insert(0.0) += building_config.power_generation;
+        *demand_by_cluster.entry(root).or_insert(0.0) += building_config.power_demand;
+    }
+
+    grid.clear();
+    for (entity, root) in &cluster_of {
+        let generation = generation_by_cluster.get(root).copied().unwrap_or(0.0);
+        let demand = demand_by_cluster.get(root).copied().unwrap_or(0.0);
+        grid.set_satisfaction(*entity, satisfaction_ratio(generation, demand));
+    }
+
+    log::debug!(
+        "Power grid recomputed: {} cluster(s)",
+        generation_by_cluster.len()
+    );
+}
+
+crate::timed_system!(
+    power_grid_system_timed,
+    power_grid_system,
+    "power_grid_system",
+    grid: ResMut<PowerGrid>,
+    events: EventReader<MapChangedEvent>,
+    constructed_events: EventReader<BuildingConstructedEvent>,
+    config: Res<GameConfig>,
+    buildings: Query<(Entity, &Building)>,
+    flags: Query<(Entity, &Flag)>,
+    segments: Query<&RoadSegment>,
+    new_segments: Query<(), Added<RoadSegment>>,
+);
+
+/// A cluster with no demand runs every building at full speed regardless of
+/// generation; otherwise generation covers demand up to parity, clamped so a
+/// surplus can't push throughput past 1.0.
+fn satisfaction_ratio(generation: f32, demand: f32) -> f32 {
+    if demand <= 0.0 {
+        1.0
+    } else {
+        (generation / demand).clamp(0.0, 1.0)
+    }
+}
+
+fn find(parent: &mut HashMap<Entity, Entity>, entity: Entity) -> Entity {
+    let mut root = entity;
+    while parent[&root] != root {
+        root = parent[&root];
+    }
+
+    let mut node = entity;
+    while parent[&node] != root {
+        let next = parent[&node];
+        parent.insert(node, root);
+        node = next;
+    }
+
+    root
+}
+
+fn union(parent: &mut HashMap<Entity, Entity>, a: Entity, b: Entity) {
+    let root_a = find(parent, a);
+    let root_b = find(parent, b);
+    if root_a != root_b {
+        parent.insert(root_a, root_b);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{FlagQueue, Position};
+    use crate::resources::BuildingConfig;
+
+    fn building_config(power_generation: f32, power_demand: f32) -> BuildingConfig {
+        BuildingConfig {
+            name: "test".to_string(),
+            construction_time: 1.0,
+            construction_cost: std::collections::HashMap::new(),
+            worker_capacity: 1,
+            stockpile_capacity: 10,
+            size: (1, 1),
+            prerequisites: Vec::new(),
+            power_generation,
+            power_demand,
+        }
+    }
+
+    #[test]
+    fn test_power_grid_splits_unconnected_clusters() {
+        let mut world = World::new();
+        world.init_resource::<PowerGrid>();
+        world.init_resource::<Events<MapChangedEvent>>();
+        world.init_resource::<Events<BuildingConstructedEvent>>();
+
+        let mut config = GameConfig::default();
+        config
+            .buildings
+            .insert("generator".to_string(), building_config(10.0, 0.0));
+        config
+            .buildings
+            .insert("factory".to_string(), building_config(0.0, 10.0));
+        world.insert_resource(config);
+
+        let mut powered = Building::new("generator".to_string(), 1);
+        powered.is_constructed = true;
+        let generator = world.spawn(powered).id();
+
+        let mut starved = Building::new("factory".to_string(), 1);
+        starved.is_constructed = true;
+        let unconnected_factory = world.spawn(starved.clone()).id();
+
+        // Connected to the generator via a road between their flags.
+        let mut connected = starved.clone();
+        connected.is_constructed = true;
+        let connected_factory = world.spawn(connected).id();
+
+        let generator_flag = world
+            .spawn((
+                Flag {
+                    building: generator,
+                },
+                FlagQueue::default(),
+                Position::new(0, 0),
+            ))
+            .id();
+        let connected_flag = world
+            .spawn((
+                Flag {
+                    building: connected_factory,
+                },
+                FlagQueue::default(),
+                Position::new(1, 0),
+            ))
+            .id();
+        world.spawn(RoadSegment::new(generator_flag, connected_flag));
+
+        let mut events = world.resource_mut::<Events<MapChangedEvent>>();
+        events.send(MapChangedEvent {
+            position: Position::new(0, 0),
+            change_type: crate::events::MapChangeType::RoadBuilt,
+            affected_tiles: vec![Position::new(0, 0)],
+        });
+
+        let mut system = IntoSystem::into_system(power_grid_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        let grid = world.resource::<PowerGrid>();
+        assert_eq!(grid.satisfaction(connected_factory), 1.0);
+        // Not connected to any generator, so its cluster has zero generation
+        // against nonzero demand.
+        assert_eq!(grid.satisfaction(unconnected_factory), 0.0);
+    }
+
+    #[test]
+    fn test_satisfaction_ratio_clamps_to_unit_range() {
+        assert_eq!(satisfaction_ratio(0.0, 0.0), 1.0);
+        assert_eq!(satisfaction_ratio(5.0, 10.0), 0.5);
+        assert_eq!(satisfaction_ratio(20.0, 10.0), 1.0);
+    }
+}
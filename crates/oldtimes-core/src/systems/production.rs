@@ -1,15 +1,18 @@
 use crate::{
-    components::{Building, Producer, Stockpile},
+    components::{Building, Producer, Stockpile, Worker},
     events::{ProductionCompletedEvent, StartProductionEvent},
-    resources::{GameConfig, GameTick},
+    resources::{GameConfig, GameTick, PowerGrid, Reservations},
 };
 use bevy::prelude::*;
 
 /// System that handles production in buildings
 pub fn production_system(
     mut query: Query<(Entity, &Building, &mut Producer, &mut Stockpile)>,
+    workers: Query<&Worker>,
     config: Res<GameConfig>,
     tick: Res<GameTick>,
+    power_grid: Res<PowerGrid>,
+    mut reservations: ResMut<Reservations>,
     mut completed_events: EventWriter<ProductionCompletedEvent>,
 ) {
     let delta_time = tick.delta_time();
@@ -29,28 +32,42 @@ pub fn production_system(
             }
         };
 
-        // Check if we have required inputs
-        if !has_required_inputs(&stockpile, recipe) {
-            producer.is_producing = false;
-            producer.production_progress = 0.0;
-            continue;
-        }
+        if !producer.is_producing {
+            // Reserve the full input cost atomically before committing to a
+            // cycle, so a second producer drawing on the same stockpile
+            // can't also pass this check against stock that's already
+            // spoken for.
+            if !has_required_inputs(entity, &stockpile, recipe, &mut reservations) {
+                producer.production_progress = 0.0;
+                continue;
+            }
 
-        // Check if we have space for outputs
-        if !has_space_for_outputs(&stockpile, recipe) {
-            producer.is_producing = false;
-            continue;
-        }
+            if !has_space_for_outputs(&stockpile, recipe) {
+                release_reserved_inputs(entity, recipe, &mut reservations);
+                continue;
+            }
 
-        // Start or continue production
-        if !producer.is_producing {
             producer.is_producing = true;
             producer.production_progress = 0.0;
+        } else if !has_space_for_outputs(&stockpile, recipe) {
+            // Output bin filled up mid-cycle; cancel and free the inputs we
+            // reserved rather than hold them forever.
+            release_reserved_inputs(entity, recipe, &mut reservations);
+            producer.is_producing = false;
+            continue;
         }
 
-        // Advance production
+        // Advance production, scaled by how skilled the assigned crew is at
+        // this recipe - a fresh crew runs at half speed, a maxed one at 1.5x -
+        // and by the building's cluster power satisfaction, so a brown-out
+        // slows a building down instead of stalling it outright.
         let production_speed = building.assigned_workers as f32 / building.worker_capacity as f32;
-        producer.production_progress += (delta_time / recipe.production_time) * production_speed;
+        let skill_multiplier = crew_skill_multiplier(&workers, entity, &producer.recipe_id);
+        producer.productivity_factor = power_grid.satisfaction(entity);
+        producer.production_progress += (delta_time / recipe.production_time)
+            * production_speed
+            * skill_multiplier
+            * producer.productivity_factor;
 
         // Complete production
         if producer.production_progress >= 1.0 {
@@ -59,12 +76,26 @@ pub fn production_system(
                 &mut producer,
                 &mut stockpile,
                 recipe,
+                &mut reservations,
                 &mut completed_events,
             );
         }
     }
 }
 
+crate::timed_system!(
+    production_system_timed,
+    production_system,
+    "production_system",
+    query: Query<(Entity, &Building, &mut Producer, &mut Stockpile)>,
+    workers: Query<&Worker>,
+    config: Res<GameConfig>,
+    tick: Res<GameTick>,
+    power_grid: Res<PowerGrid>,
+    reservations: ResMut<Reservations>,
+    completed_events: EventWriter<ProductionCompletedEvent>,
+);
+
 /// System that handles production start events
 pub fn start_production_system(
     mut events: EventReader<StartProductionEvent>,
@@ -79,15 +110,68 @@ pub fn start_production_system(
     }
 }
 
-fn has_required_inputs(stockpile: &Stockpile, recipe: &crate::resources::RecipeConfig) -> bool {
+crate::timed_system!(
+    start_production_system_timed,
+    start_production_system,
+    "start_production_system",
+    events: EventReader<StartProductionEvent>,
+    query: Query<&mut Producer>,
+);
+
+/// Averages the crew's proficiency at `recipe_id` into the `0.5 + skill`
+/// multiplier, the same curve `Worker::effective_production_rate` uses.
+/// An unstaffed building falls back to the unskilled baseline.
+fn crew_skill_multiplier(workers: &Query<&Worker>, building: Entity, recipe_id: &str) -> f32 {
+    let mut total_skill = 0.0;
+    let mut count = 0;
+
+    for worker in workers.iter() {
+        if worker.assigned_building == Some(building) {
+            total_skill += worker.skill_for(recipe_id);
+            count += 1;
+        }
+    }
+
+    if count == 0 {
+        return 0.5;
+    }
+
+    0.5 + (total_skill / count as f32)
+}
+
+/// Checks whether `stockpile` can cover every input `recipe` needs, counting
+/// only stock that isn't already reserved by another in-progress cycle, and
+/// atomically reserves the full input cost on success so the next caller
+/// sees the reduced availability.
+fn has_required_inputs(
+    stockpile_entity: Entity,
+    stockpile: &Stockpile,
+    recipe: &crate::resources::RecipeConfig,
+    reservations: &mut Reservations,
+) -> bool {
     for (item, required_amount) in &recipe.inputs {
-        if stockpile.get_item_count(item) < *required_amount {
+        if stockpile.effective_item_count(item, stockpile_entity, reservations) < *required_amount
+        {
             return false;
         }
     }
+
+    for (item, amount) in &recipe.inputs {
+        reservations.reserve_out(stockpile_entity, item, *amount);
+    }
     true
 }
 
+fn release_reserved_inputs(
+    stockpile_entity: Entity,
+    recipe: &crate::resources::RecipeConfig,
+    reservations: &mut Reservations,
+) {
+    for (item, amount) in &recipe.inputs {
+        reservations.release_out(stockpile_entity, item, *amount);
+    }
+}
+
 fn has_space_for_outputs(stockpile: &Stockpile, recipe: &crate::resources::RecipeConfig) -> bool {
     let total_outputs: u32 = recipe.outputs.values().sum();
     stockpile.available_space() >= total_outputs
@@ -98,12 +182,14 @@ fn complete_production(
     producer: &mut Producer,
     stockpile: &mut Stockpile,
     recipe: &crate::resources::RecipeConfig,
+    reservations: &mut Reservations,
     completed_events: &mut EventWriter<ProductionCompletedEvent>,
 ) {
-    // Consume inputs
+    // Consume inputs; the reservation held against them is spent too.
     for (item, amount) in &recipe.inputs {
         stockpile.remove_item(item, *amount);
     }
+    release_reserved_inputs(entity, recipe, reservations);
 
     // Produce outputs
     let mut outputs = std::collections::HashMap::new();
@@ -138,6 +224,10 @@ mod tests {
 
     #[test]
     fn test_has_required_inputs() {
+        let mut world = World::new();
+        let building = world.spawn_empty().id();
+        let mut reservations = Reservations::default();
+
         let mut stockpile = Stockpile::new(100);
         stockpile.add_item("wood".to_string(), 5);
         stockpile.add_item("stone".to_string(), 3);
@@ -150,7 +240,7 @@ mod tests {
             required_building: "test".to_string(),
         };
 
-        assert!(has_required_inputs(&stockpile, &recipe));
+        assert!(has_required_inputs(building, &stockpile, &recipe, &mut reservations));
 
         let recipe_insufficient = RecipeConfig {
             name: "Test Recipe".to_string(),
@@ -160,7 +250,57 @@ mod tests {
             required_building: "test".to_string(),
         };
 
-        assert!(!has_required_inputs(&stockpile, &recipe_insufficient));
+        assert!(!has_required_inputs(
+            building,
+            &stockpile,
+            &recipe_insufficient,
+            &mut reservations
+        ));
+    }
+
+    #[test]
+    fn test_second_producer_denied_scarce_input_already_reserved() {
+        let mut world = World::new();
+        let stockpile_entity = world.spawn_empty().id();
+        let mut reservations = Reservations::default();
+
+        // Only enough wood on hand for one job.
+        let mut stockpile = Stockpile::new(100);
+        stockpile.add_item("wood".to_string(), 5);
+
+        let recipe = RecipeConfig {
+            name: "Planks".to_string(),
+            production_time: 10.0,
+            inputs: [("wood".to_string(), 5)].into(),
+            outputs: HashMap::new(),
+            required_building: "test".to_string(),
+        };
+
+        // First producer reserves the entire stock of wood for its cycle.
+        assert!(has_required_inputs(
+            stockpile_entity,
+            &stockpile,
+            &recipe,
+            &mut reservations
+        ));
+
+        // A second producer checking the same stockpile for the same scarce
+        // item is denied instead of also passing and over-consuming it.
+        assert!(!has_required_inputs(
+            stockpile_entity,
+            &stockpile,
+            &recipe,
+            &mut reservations
+        ));
+
+        // Releasing the first reservation frees the wood back up.
+        release_reserved_inputs(stockpile_entity, &recipe, &mut reservations);
+        assert!(has_required_inputs(
+            stockpile_entity,
+            &stockpile,
+            &recipe,
+            &mut reservations
+        ));
     }
 
     #[test]
@@ -188,4 +328,43 @@ mod tests {
 
         assert!(!has_space_for_outputs(&stockpile, &recipe_too_much));
     }
+
+    #[test]
+    fn test_crew_skill_multiplier_scales_with_proficiency() {
+        let mut world = World::new();
+        let building = world.spawn_empty().id();
+
+        let mut novice = Worker::new("worker".to_string());
+        novice.assigned_building = Some(building);
+        world.spawn(novice);
+
+        let mut expert = Worker::new("worker".to_string());
+        expert.assigned_building = Some(building);
+        expert.skills.insert("make_planks".to_string(), 1.0);
+        world.spawn(expert);
+
+        let mut system = IntoSystem::into_system(
+            move |workers: Query<&Worker>| crew_skill_multiplier(&workers, building, "make_planks"),
+        );
+        system.initialize(&mut world);
+        let multiplier = system.run((), &mut world);
+
+        // Novice (skill 0.0) and expert (skill 1.0) average to 0.5 skill,
+        // giving a 0.5 + 0.5 = 1.0 multiplier.
+        assert!((multiplier - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_crew_skill_multiplier_defaults_to_unskilled_when_unstaffed() {
+        let mut world = World::new();
+        let building = world.spawn_empty().id();
+
+        let mut system = IntoSystem::into_system(
+            move |workers: Query<&Worker>| crew_skill_multiplier(&workers, building, "make_planks"),
+        );
+        system.initialize(&mut world);
+        let multiplier = system.run((), &mut world);
+
+        assert!((multiplier - 0.5).abs() < f32::EPSILON);
+    }
 }
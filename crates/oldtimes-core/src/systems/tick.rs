@@ -1,14 +1,23 @@
-use crate::resources::{GameTick, PerformanceMetrics};
+use crate::resources::{
+    FrameAllocator, GameTick, PerformanceMetrics, TickTimingHistory, TickTimingSample,
+};
 use bevy::prelude::*;
 
 /// System that advances the game tick
 pub fn advance_tick_system(
     mut tick: ResMut<GameTick>,
     mut metrics: ResMut<PerformanceMetrics>,
+    mut frame_allocator: ResMut<FrameAllocator>,
     query: Query<Entity>,
 ) {
     let start_time = std::time::Instant::now();
 
+    // Reclaim last tick's scratch allocations before anything in this tick
+    // can claim new ones; this system is first in the `.chain()`'d Update
+    // schedule, so every system that could have used the arena last tick has
+    // already finished by the time we get here.
+    frame_allocator.reset();
+
     tick.tick();
     metrics.entities_count = query.iter().count() as u32;
 
@@ -25,17 +34,33 @@ pub fn advance_tick_system(
     }
 }
 
-/// System that profiles other systems performance
+/// System that profiles other systems' performance. Collects every
+/// `ProfileEvent` sent during this tick (by the `timed_system!` wrappers
+/// chained earlier in `Update`) into `PerformanceMetrics::system_times` for
+/// the live debug overlay, and into `TickTimingHistory`'s ring buffer so
+/// `--timing-report` can render a per-system timeline after the fact.
 pub fn profile_systems_system(
     mut metrics: ResMut<PerformanceMetrics>,
+    mut history: ResMut<TickTimingHistory>,
+    tick: Res<GameTick>,
     mut events: EventReader<crate::events::ProfileEvent>,
 ) {
+    let mut systems = Vec::new();
+
     for event in events.read() {
         metrics.record_system_time(event.system_name.clone(), event.duration_ms);
+        systems.push((event.system_name.clone(), event.duration_ms));
+    }
+
+    if !systems.is_empty() {
+        history.push(TickTimingSample {
+            tick: tick.current,
+            systems,
+        });
     }
 }
 
-/// Macro to wrap systems with profiling
+/// Macro to wrap an exclusive (`&mut World`) system with profiling.
 #[macro_export]
 macro_rules! profile_system {
     ($system:expr, $name:expr) => {
@@ -55,3 +80,27 @@ macro_rules! profile_system {
         }
     };
 }
+
+/// Wraps an ordinary (typed-`SystemParam`) system so it also times its own
+/// execution and reports the result as a `ProfileEvent`. Generates a new
+/// `$wrapper` system taking exactly `$system`'s params plus an
+/// `EventWriter<ProfileEvent>`, running `$system` with them and sending the
+/// measured duration tagged `$name`. This is what actually feeds
+/// `profile_systems_system` for the non-exclusive systems making up most of
+/// the simulation's `Update` schedule.
+#[macro_export]
+macro_rules! timed_system {
+    ($wrapper:ident, $system:path, $name:expr, $($param:ident : $ty:ty),+ $(,)?) => {
+        pub fn $wrapper(
+            $($param: $ty,)+
+            mut profile_events: EventWriter<crate::events::ProfileEvent>,
+        ) {
+            let start = std::time::Instant::now();
+            $system($($param),+);
+            profile_events.send(crate::events::ProfileEvent {
+                system_name: $name.to_string(),
+                duration_ms: start.elapsed().as_secs_f32() * 1000.0,
+            });
+        }
+    };
+}
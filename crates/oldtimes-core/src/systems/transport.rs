@@ -1,50 +1,88 @@
 use bevy::prelude::*;
 use crate::{
-    components::{Position, Stockpile, Worker, WorkerTask, TaskPurpose},
+    components::{PendingDelivery, Position, Stockpile, TaskPurpose, Worker, WorkerTask},
     events::{TransferResourceEvent, PathfindingRequestEvent, PathfindingPriority},
+    resources::Reservations,
 };
 
 /// System that handles resource transport between stockpiles
 pub fn transport_system(
+    mut commands: Commands,
     mut events: EventReader<TransferResourceEvent>,
     mut stockpiles: Query<&mut Stockpile>,
     positions: Query<&Position>,
     mut workers: Query<(Entity, &mut Worker, &Position)>,
     mut pathfinding_events: EventWriter<PathfindingRequestEvent>,
+    mut reservations: ResMut<Reservations>,
 ) {
     for event in events.read() {
         // Find available worker for transport
         let available_worker = workers.iter_mut()
             .find(|(_, worker, _)| matches!(worker.current_task, WorkerTask::Idle) && worker.carrying.is_none());
-        
+
         if let Some((worker_entity, mut worker, worker_pos)) = available_worker {
             // Get source and destination positions
             let source_pos = positions.get(event.from).ok();
             let dest_pos = positions.get(event.to).ok();
-            
-            if let (Some(source_pos), Some(dest_pos)) = (source_pos, dest_pos) {
-                // Check if source has the resource
+
+            if let (Some(source_pos), Some(_dest_pos)) = (source_pos, dest_pos) {
+                // Check if source has the resource, after subtracting what other
+                // workers have already claimed for pickup.
                 if let Ok(source_stockpile) = stockpiles.get(event.from) {
-                    if source_stockpile.get_item_count(&event.resource) >= event.amount {
+                    if source_stockpile.effective_item_count(
+                        &event.resource,
+                        event.from,
+                        &reservations,
+                    ) >= event.amount
+                    {
+                        // Clamp to the destination's free space, after
+                        // subtracting what other in-flight deliveries already
+                        // claimed, so this haul can never reserve more than
+                        // the destination can actually hold.
+                        let available_space = stockpiles
+                            .get(event.to)
+                            .map(|dest| dest.effective_available_space(event.to, &reservations))
+                            .unwrap_or(0);
+                        let amount = event.amount.min(available_space);
+
+                        if amount == 0 {
+                            log::debug!(
+                                "Destination {:?} has no room for {}, skipping transfer",
+                                event.to,
+                                event.resource
+                            );
+                            continue;
+                        }
+
+                        reservations.reserve_out(event.from, &event.resource, amount);
+                        reservations.reserve_in(event.to, amount);
+                        commands.entity(worker_entity).insert(PendingDelivery {
+                            destination: event.to,
+                            item: event.resource.clone(),
+                            amount,
+                        });
+
                         // Assign transport task
                         worker.current_task = WorkerTask::MovingTo {
                             target: *source_pos,
                             purpose: TaskPurpose::PickupResource {
+                                source: event.from,
                                 item: event.resource.clone(),
-                                amount: event.amount,
+                                amount,
                             },
                         };
-                        
+
                         // Request pathfinding to source
                         pathfinding_events.send(PathfindingRequestEvent {
                             entity: worker_entity,
                             from: *worker_pos,
                             to: *source_pos,
                             priority: PathfindingPriority::Normal,
+                            channel: Some(crate::resources::PheromoneChannel::Haul),
                         });
-                        
-                        log::debug!("Assigned transport task: {} {} from {:?} to {:?}", 
-                                   event.amount, event.resource, event.from, event.to);
+
+                        log::debug!("Assigned transport task: {} {} from {:?} to {:?}",
+                                   amount, event.resource, event.from, event.to);
                     }
                 }
             }
@@ -54,34 +92,98 @@ pub fn transport_system(
     }
 }
 
+crate::timed_system!(
+    transport_system_timed,
+    transport_system,
+    "transport_system",
+    commands: Commands,
+    events: EventReader<TransferResourceEvent>,
+    stockpiles: Query<&mut Stockpile>,
+    positions: Query<&Position>,
+    workers: Query<(Entity, &mut Worker, &Position)>,
+    pathfinding_events: EventWriter<PathfindingRequestEvent>,
+    reservations: ResMut<Reservations>,
+);
+
 /// System that handles automatic resource distribution
 pub fn resource_distribution_system(
     stockpiles: Query<(Entity, &Stockpile, &Position)>,
     mut transfer_events: EventWriter<TransferResourceEvent>,
     tick: Res<crate::resources::GameTick>,
+    belt_network: Res<crate::resources::BeltNetwork>,
+    reservations: Res<Reservations>,
 ) {
     // Run distribution logic every 5 seconds
     if tick.current % (tick.target_tps as u64 * 5) != 0 {
         return;
     }
-    
+
     let stockpiles_vec: Vec<_> = stockpiles.iter().collect();
-    
+    let stockpiles_by_entity: std::collections::HashMap<Entity, &Stockpile> = stockpiles_vec
+        .iter()
+        .map(|(entity, stockpile, _)| (*entity, *stockpile))
+        .collect();
+
     // Simple distribution: move excess resources to stockpiles that need them
     for (source_entity, source_stockpile, _source_pos) in &stockpiles_vec {
-        for (item, &amount) in &source_stockpile.items {
-            if amount > 10 { // If we have excess (more than 10)
+        for item in source_stockpile.items.keys() {
+            // Excess after subtracting what other workers have already
+            // claimed for pickup, so a source isn't scheduled for another
+            // haul on top of one still in flight.
+            let effective_amount =
+                source_stockpile.effective_item_count(item, *source_entity, &reservations);
+            // If we have excess (more than 10)
+            if effective_amount > 10 {
+                let available_to_move = effective_amount - 10;
+                let belt_routes = belt_network.routes_for(*source_entity, item);
+
+                if !belt_routes.is_empty() {
+                    // A placed belt network always wins over the naive scan
+                    // below - route the excess according to its configured
+                    // ratios, skipping (back-pressure) any destination with
+                    // no room this cycle rather than stalling the whole split.
+                    for route in belt_routes {
+                        let Some(dest_stockpile) = stockpiles_by_entity.get(&route.to) else {
+                            continue;
+                        };
+                        let desired = ((available_to_move as f32) * route.share).round() as u32;
+                        let transfer_amount = desired
+                            .min(route.capacity)
+                            .min(dest_stockpile.effective_available_space(route.to, &reservations));
+
+                        if transfer_amount > 0 {
+                            transfer_events.send(TransferResourceEvent {
+                                from: *source_entity,
+                                to: route.to,
+                                resource: item.clone(),
+                                amount: transfer_amount,
+                            });
+
+                            log::debug!("Belt-distributing {} {} from {:?} to {:?}",
+                                       transfer_amount, item, source_entity, route.to);
+                        } else {
+                            log::debug!("Belt from {:?} to {:?} backed up, skipping {} this cycle",
+                                       source_entity, route.to, item);
+                        }
+                    }
+                    continue;
+                }
+
+                // No belt configured for this source/item - fall back to the
+                // original nearest-need scan.
                 // Find a stockpile that needs this resource
                 for (dest_entity, dest_stockpile, _dest_pos) in &stockpiles_vec {
                     if source_entity == dest_entity {
                         continue;
                     }
-                    
+
                     let dest_amount = dest_stockpile.get_item_count(item);
-                    if dest_amount < 5 && dest_stockpile.available_space() > 0 {
+                    let dest_space =
+                        dest_stockpile.effective_available_space(*dest_entity, &reservations);
+                    if dest_amount < 5 && dest_space > 0 {
                         // Transfer some resources
-                        let transfer_amount = (amount - 10).min(5).min(dest_stockpile.available_space());
-                        
+                        let transfer_amount = available_to_move.min(5).min(dest_space);
+
                         if transfer_amount > 0 {
                             transfer_events.send(TransferResourceEvent {
                                 from: *source_entity,
@@ -89,8 +191,8 @@ pub fn resource_distribution_system(
                                 resource: item.clone(),
                                 amount: transfer_amount,
                             });
-                            
-                            log::debug!("Auto-distributing {} {} from {:?} to {:?}", 
+
+                            log::debug!("Auto-distributing {} {} from {:?} to {:?}",
                                        transfer_amount, item, source_entity, dest_entity);
                             break; // Only one transfer per item per tick
                         }
@@ -101,60 +203,89 @@ pub fn resource_distribution_system(
     }
 }
 
+crate::timed_system!(
+    resource_distribution_system_timed,
+    resource_distribution_system,
+    "resource_distribution_system",
+    stockpiles: Query<(Entity, &Stockpile, &Position)>,
+    transfer_events: EventWriter<TransferResourceEvent>,
+    tick: Res<crate::resources::GameTick>,
+    belt_network: Res<crate::resources::BeltNetwork>,
+    reservations: Res<Reservations>,
+);
+
 /// System that processes completed transport tasks
 pub fn transport_completion_system(
+    mut commands: Commands,
     mut task_events: EventReader<crate::events::TaskCompletedEvent>,
     mut workers: Query<&mut Worker>,
-    stockpiles: Query<(Entity, &Stockpile)>,
+    pending_deliveries: Query<&PendingDelivery>,
     positions: Query<&Position>,
     mut pathfinding_events: EventWriter<PathfindingRequestEvent>,
 ) {
     for event in task_events.read() {
         if event.task_type == "pickup" {
+            // Only a pickup `transport_system` itself dispatched carries a
+            // `PendingDelivery` - anything else (e.g. a worker_ai-originated
+            // construction fetch) is none of our business; its own system
+            // decides where the item goes.
+            let Ok(pending) = pending_deliveries.get(event.worker) else {
+                continue;
+            };
+            let destination = pending.destination;
+            let item = pending.item.clone();
+            let amount = pending.amount;
+            commands.entity(event.worker).remove::<PendingDelivery>();
+
             if let Ok(mut worker) = workers.get_mut(event.worker) {
-                if let Some((item, amount)) = &worker.carrying {
-                    // Find destination for the carried item
-                    // For now, just find any stockpile with space
-                    let mut dest_entity = None;
-                    for (entity, stockpile) in stockpiles.iter() {
-                        if stockpile.available_space() >= *amount {
-                            dest_entity = Some(entity);
-                            break;
-                        }
-                    }
-                    
-                    if let Some(dest_entity) = dest_entity {
-                        if let Ok(dest_pos) = positions.get(dest_entity) {
-                            if let Ok(worker_pos) = positions.get(event.worker) {
-                                worker.current_task = WorkerTask::Carrying {
-                                    from: *worker_pos,
-                                    to: *dest_pos,
-                                    item: item.clone(),
-                                    amount: *amount,
-                                };
-                                
-                                pathfinding_events.send(PathfindingRequestEvent {
-                                    entity: event.worker,
-                                    from: *worker_pos,
-                                    to: *dest_pos,
-                                    priority: PathfindingPriority::Normal,
-                                });
-                            }
-                        }
-                    }
+                if let (Ok(dest_pos), Ok(worker_pos)) =
+                    (positions.get(destination), positions.get(event.worker))
+                {
+                    // The destination's space was already reserved back when
+                    // `transport_system` dispatched this haul, so there's
+                    // nothing left to check or claim here - just start
+                    // carrying it there.
+                    worker.current_task = WorkerTask::Carrying {
+                        from: *worker_pos,
+                        to: *dest_pos,
+                        destination,
+                        item,
+                        amount,
+                        route: None,
+                    };
+
+                    pathfinding_events.send(PathfindingRequestEvent {
+                        entity: event.worker,
+                        from: *worker_pos,
+                        to: *dest_pos,
+                        priority: PathfindingPriority::Normal,
+                        channel: Some(crate::resources::PheromoneChannel::Haul),
+                    });
                 }
             }
         } else if event.task_type == "delivery" {
-            // Handle completed delivery - remove from source, add to destination
-            if let Ok(worker) = workers.get(event.worker) {
-                // In a real implementation, you'd track the source and destination
-                // and actually transfer the resources between stockpiles
-                log::debug!("Delivery completed by worker {:?}", event.worker);
-            }
+            // No-op: `worker_ai_system`'s own `WorkerTask::Carrying` arrival
+            // handling already does the real work (releasing the incoming
+            // reservation and crediting the destination stockpile) for every
+            // carrying worker, regardless of which system put it into that
+            // task, so there's nothing left to do for this event.
+            log::debug!("Delivery completed by worker {:?}", event.worker);
         }
     }
 }
 
+crate::timed_system!(
+    transport_completion_system_timed,
+    transport_completion_system,
+    "transport_completion_system",
+    commands: Commands,
+    task_events: EventReader<crate::events::TaskCompletedEvent>,
+    workers: Query<&mut Worker>,
+    pending_deliveries: Query<&PendingDelivery>,
+    positions: Query<&Position>,
+    pathfinding_events: EventWriter<PathfindingRequestEvent>,
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -173,4 +304,263 @@ mod tests {
         assert!(dest_stockpile.get_item_count("wood") < 5);
         assert!(dest_stockpile.available_space() > 0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_resource_distribution_prefers_belt_route_over_naive_scan() {
+        use crate::resources::{BeltNetwork, BeltRoute, GameTick};
+
+        let mut world = World::new();
+        world.init_resource::<GameTick>();
+        world.init_resource::<Events<TransferResourceEvent>>();
+        world.init_resource::<Reservations>();
+
+        let mut source_stockpile = Stockpile::new(100);
+        source_stockpile.add_item("wood".to_string(), 20);
+        let source = world.spawn((source_stockpile, Position::new(0, 0))).id();
+
+        // Would also qualify under the naive "needs < 5" scan, but the belt
+        // route below should be the one actually used.
+        let belt_dest = world
+            .spawn((Stockpile::new(100), Position::new(1, 0)))
+            .id();
+        let scan_dest = world
+            .spawn((Stockpile::new(100), Position::new(2, 0)))
+            .id();
+
+        let mut network = BeltNetwork::default();
+        network.set_routes(
+            source,
+            vec![BeltRoute {
+                to: belt_dest,
+                item: "wood".to_string(),
+                capacity: 100,
+                share: 1.0,
+            }],
+        );
+        world.insert_resource(network);
+
+        let mut system = IntoSystem::into_system(resource_distribution_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        let mut events = world.resource_mut::<Events<TransferResourceEvent>>();
+        let sent: Vec<_> = events.drain().collect();
+
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].to, belt_dest);
+        assert_ne!(sent[0].to, scan_dest);
+    }
+
+    #[test]
+    fn test_reservations_prevent_double_hauling() {
+        let mut world = World::new();
+
+        let mut stockpile = Stockpile::new(100);
+        stockpile.add_item("wood".to_string(), 10);
+        let stockpile_entity = world.spawn(stockpile).id();
+
+        let mut reservations = Reservations::default();
+
+        // First worker claims all 10 wood.
+        let available = {
+            let stockpile = world.get::<Stockpile>(stockpile_entity).unwrap();
+            stockpile.effective_item_count("wood", stockpile_entity, &reservations)
+        };
+        assert_eq!(available, 10);
+        reservations.reserve_out(stockpile_entity, "wood", 10);
+
+        // A second worker sees nothing left to claim.
+        let remaining = {
+            let stockpile = world.get::<Stockpile>(stockpile_entity).unwrap();
+            stockpile.effective_item_count("wood", stockpile_entity, &reservations)
+        };
+        assert_eq!(remaining, 0);
+
+        // Once the first worker's pickup is released, it's claimable again.
+        reservations.release_out(stockpile_entity, "wood", 10);
+        let remaining = {
+            let stockpile = world.get::<Stockpile>(stockpile_entity).unwrap();
+            stockpile.effective_item_count("wood", stockpile_entity, &reservations)
+        };
+        assert_eq!(remaining, 10);
+    }
+
+    #[test]
+    fn test_reservations_cap_incoming_space() {
+        let mut world = World::new();
+        let stockpile = world.spawn(Stockpile::new(10)).id();
+
+        let mut reservations = Reservations::default();
+        reservations.reserve_in(stockpile, 6);
+
+        let effective = {
+            let stockpile = world.get::<Stockpile>(stockpile).unwrap();
+            stockpile.effective_available_space(stockpile, &reservations)
+        };
+        assert_eq!(effective, 4);
+
+        reservations.release_in(stockpile, 6);
+        let effective = {
+            let stockpile = world.get::<Stockpile>(stockpile).unwrap();
+            stockpile.effective_available_space(stockpile, &reservations)
+        };
+        assert_eq!(effective, 10);
+    }
+
+    #[test]
+    fn test_transport_system_reserves_both_ends_and_attaches_pending_delivery() {
+        let mut world = World::new();
+        world.init_resource::<Reservations>();
+        world.init_resource::<Events<PathfindingRequestEvent>>();
+        world.init_resource::<Events<TransferResourceEvent>>();
+
+        let mut source_stockpile = Stockpile::new(100);
+        source_stockpile.add_item("wood".to_string(), 10);
+        let source = world.spawn((source_stockpile, Position::new(0, 0))).id();
+        let dest = world.spawn((Stockpile::new(100), Position::new(5, 5))).id();
+        let worker = world
+            .spawn((Worker::new("hauler".to_string()), Position::new(1, 1)))
+            .id();
+
+        world
+            .resource_mut::<Events<TransferResourceEvent>>()
+            .send(TransferResourceEvent {
+                from: source,
+                to: dest,
+                resource: "wood".to_string(),
+                amount: 5,
+            });
+
+        let mut system = IntoSystem::into_system(transport_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+        system.apply_deferred(&mut world);
+
+        let reservations = world.resource::<Reservations>();
+        assert_eq!(reservations.reserved_out(source, "wood"), 5);
+        assert_eq!(reservations.reserved_in(dest), 5);
+
+        let pending = world.get::<PendingDelivery>(worker).unwrap();
+        assert_eq!(pending.destination, dest);
+        assert_eq!(pending.item, "wood");
+        assert_eq!(pending.amount, 5);
+
+        let worker_comp = world.get::<Worker>(worker).unwrap();
+        assert!(matches!(
+            worker_comp.current_task,
+            WorkerTask::MovingTo {
+                purpose: TaskPurpose::PickupResource { amount: 5, .. },
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_transport_system_clamps_dispatch_to_destination_space() {
+        let mut world = World::new();
+        world.init_resource::<Reservations>();
+        world.init_resource::<Events<PathfindingRequestEvent>>();
+        world.init_resource::<Events<TransferResourceEvent>>();
+
+        let mut source_stockpile = Stockpile::new(100);
+        source_stockpile.add_item("wood".to_string(), 10);
+        let source = world.spawn((source_stockpile, Position::new(0, 0))).id();
+        // Only 3 units of room, despite a request for 10.
+        let dest = world.spawn((Stockpile::new(3), Position::new(5, 5))).id();
+        let worker = world
+            .spawn((Worker::new("hauler".to_string()), Position::new(1, 1)))
+            .id();
+
+        world
+            .resource_mut::<Events<TransferResourceEvent>>()
+            .send(TransferResourceEvent {
+                from: source,
+                to: dest,
+                resource: "wood".to_string(),
+                amount: 10,
+            });
+
+        let mut system = IntoSystem::into_system(transport_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+        system.apply_deferred(&mut world);
+
+        let reservations = world.resource::<Reservations>();
+        assert_eq!(reservations.reserved_out(source, "wood"), 3);
+        assert_eq!(reservations.reserved_in(dest), 3);
+
+        let pending = world.get::<PendingDelivery>(worker).unwrap();
+        assert_eq!(pending.amount, 3);
+        let _ = worker;
+    }
+
+    #[test]
+    fn test_transport_completion_uses_pending_delivery_destination_not_a_scan() {
+        let mut world = World::new();
+        world.init_resource::<Events<PathfindingRequestEvent>>();
+        world.init_resource::<Events<crate::events::TaskCompletedEvent>>();
+
+        // A generously-sized decoy a naive scan would pick first.
+        let _decoy = world.spawn((Stockpile::new(100), Position::new(9, 9))).id();
+        let reserved_destination = world.spawn((Stockpile::new(100), Position::new(5, 5))).id();
+
+        let mut worker = Worker::new("hauler".to_string());
+        worker.carrying = Some(("wood".to_string(), 5));
+        let worker_entity = world.spawn((worker, Position::new(1, 1))).id();
+        world.entity_mut(worker_entity).insert(PendingDelivery {
+            destination: reserved_destination,
+            item: "wood".to_string(),
+            amount: 5,
+        });
+
+        world
+            .resource_mut::<Events<crate::events::TaskCompletedEvent>>()
+            .send(crate::events::TaskCompletedEvent {
+                worker: worker_entity,
+                task_type: "pickup".to_string(),
+                destination: None,
+            });
+
+        let mut system = IntoSystem::into_system(transport_completion_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+        system.apply_deferred(&mut world);
+
+        let worker_comp = world.get::<Worker>(worker_entity).unwrap();
+        assert!(matches!(
+            worker_comp.current_task,
+            WorkerTask::Carrying { destination, .. } if destination == reserved_destination
+        ));
+        assert!(world.get::<PendingDelivery>(worker_entity).is_none());
+    }
+
+    #[test]
+    fn test_transport_completion_ignores_pickups_it_did_not_dispatch() {
+        let mut world = World::new();
+        world.init_resource::<Events<PathfindingRequestEvent>>();
+        world.init_resource::<Events<crate::events::TaskCompletedEvent>>();
+
+        // No `PendingDelivery` attached - this models a worker_ai-originated
+        // pickup (e.g. a construction-material fetch), which owns its own
+        // destination decision and isn't this system's business.
+        let mut worker = Worker::new("builder".to_string());
+        worker.carrying = Some(("planks".to_string(), 2));
+        let worker_entity = world.spawn((worker, Position::new(1, 1))).id();
+
+        world
+            .resource_mut::<Events<crate::events::TaskCompletedEvent>>()
+            .send(crate::events::TaskCompletedEvent {
+                worker: worker_entity,
+                task_type: "pickup".to_string(),
+                destination: None,
+            });
+
+        let mut system = IntoSystem::into_system(transport_completion_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+        system.apply_deferred(&mut world);
+
+        let worker_comp = world.get::<Worker>(worker_entity).unwrap();
+        assert!(matches!(worker_comp.current_task, WorkerTask::Idle));
+    }
+}
@@ -0,0 +1,86 @@
+use crate::{
+    components::{Building, Position, Worker},
+    resources::{GameConfig, Visibility},
+};
+use bevy::prelude::*;
+
+/// System that recomputes fog-of-war each tick: everything currently lit stays
+/// `Explored` as a memory, then constructed buildings and workers project their
+/// sight radius back out to `Visible`.
+pub fn visibility_system(
+    mut visibility: ResMut<Visibility>,
+    config: Res<GameConfig>,
+    buildings: Query<(&Position, &Building)>,
+    workers: Query<&Position, With<Worker>>,
+) {
+    visibility.downgrade_visible_to_explored();
+
+    for (position, building) in buildings.iter() {
+        if !building.is_constructed {
+            continue;
+        }
+        visibility.reveal_around(*position, config.visibility.building_sight_radius);
+    }
+
+    for position in workers.iter() {
+        visibility.reveal_around(*position, config.visibility.worker_sight_radius);
+    }
+}
+
+crate::timed_system!(
+    visibility_system_timed,
+    visibility_system,
+    "visibility_system",
+    visibility: ResMut<Visibility>,
+    config: Res<GameConfig>,
+    buildings: Query<(&Position, &Building)>,
+    workers: Query<&Position, With<Worker>>,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visibility_system_reveals_around_constructed_building() {
+        let mut world = World::new();
+        world.insert_resource(Visibility::new(10, 10));
+        world.insert_resource(GameConfig::default());
+
+        world.spawn((
+            Position::new(5, 5),
+            Building::new("lumberjack".to_string(), 2),
+        ));
+
+        let mut building = world
+            .query::<&mut Building>()
+            .single_mut(&mut world);
+        building.is_constructed = true;
+        building.construction_progress = 1.0;
+
+        let mut system = IntoSystem::into_system(visibility_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        let visibility = world.resource::<Visibility>();
+        assert!(visibility.is_visible(Position::new(5, 5)));
+        assert!(!visibility.is_visible(Position::new(0, 0)));
+    }
+
+    #[test]
+    fn test_visibility_system_downgrades_out_of_range_tiles() {
+        let mut world = World::new();
+        let mut visibility = Visibility::new(10, 10);
+        visibility.reveal_around(Position::new(9, 9), 1.0);
+        world.insert_resource(visibility);
+        world.insert_resource(GameConfig::default());
+
+        let mut system = IntoSystem::into_system(visibility_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        let visibility = world.resource::<Visibility>();
+        assert!(!visibility.is_visible(Position::new(9, 9)));
+        assert!(visibility.is_explored(Position::new(9, 9)));
+    }
+}
@@ -1,44 +1,144 @@
 use bevy::prelude::*;
 use crate::{
-    components::{Worker, Position, Building, WorkerTask, TaskPurpose},
-    resources::GameTick,
+    components::{Worker, Position, Building, Blocked, ConstructionMaterials, PendingDelivery, Producer, Stockpile, WorkerTask, TaskPurpose},
+    resources::{FrameAllocator, GameConfig, GameTick, MapData, PathfindingCache, PheromoneChannel, Reservations, SpatialIndex},
     events::{AssignWorkerEvent, TaskCompletedEvent, PathfindingRequestEvent, PathfindingPriority},
+    systems::{
+        pathfinding::path_exists,
+        worker_ai::{self, Candidate, ConsiderationRegistry},
+    },
 };
 
 /// System that manages worker AI and task execution
 pub fn worker_ai_system(
     mut workers: Query<(Entity, &mut Worker, &mut Position)>,
     buildings: Query<(Entity, &Building, &Position), Without<Worker>>,
+    producers: Query<&Producer>,
+    mut stockpiles: Query<(Entity, &mut Stockpile, &Position)>,
+    config: Res<GameConfig>,
     tick: Res<GameTick>,
+    registry: Res<ConsiderationRegistry>,
     mut task_events: EventWriter<TaskCompletedEvent>,
     mut pathfinding_events: EventWriter<PathfindingRequestEvent>,
+    mut reservations: ResMut<Reservations>,
+    mut materials: Query<&mut ConstructionMaterials>,
 ) {
     let delta_time = tick.delta_time();
-    
+
     for (worker_entity, mut worker, mut worker_pos) in workers.iter_mut() {
+        // Carriers are driven entirely by `systems::logistics` - they never
+        // pick up generic candidate work and never leave their road segment.
+        if worker.assigned_road_segment.is_some() {
+            continue;
+        }
+
         match &mut worker.current_task {
             WorkerTask::Idle => {
-                // Try to find work
-                if let Some(building_entity) = worker.assigned_building {
-                    if let Ok((_, building, building_pos)) = buildings.get(building_entity) {
-                        if building.is_constructed {
-                            // Move to assigned building
+                // Score every candidate action (go to work, fetch a low
+                // input, deliver what's already carried, help construction)
+                // and act on the highest-scoring one; see `systems::worker_ai`.
+                let candidates = worker_ai::enumerate_candidates(
+                    worker_entity,
+                    &worker,
+                    &buildings,
+                    &producers,
+                    &stockpiles,
+                    &config,
+                    &reservations,
+                    &materials,
+                );
+
+                if let Some(candidate) =
+                    worker_ai::pick_best_candidate(&registry, &worker, *worker_pos, candidates)
+                {
+                    match candidate {
+                        Candidate::GoToWork { position, .. } => {
                             worker.current_task = WorkerTask::MovingTo {
-                                target: *building_pos,
+                                target: position,
                                 purpose: TaskPurpose::GoToWork,
                             };
-                            
+
                             pathfinding_events.send(PathfindingRequestEvent {
                                 entity: worker_entity,
                                 from: *worker_pos,
-                                to: *building_pos,
+                                to: position,
                                 priority: PathfindingPriority::Normal,
+                                channel: None,
                             });
-                        }
+                        },
+                        Candidate::PickupResource { source, position, item, amount, .. } => {
+                            reservations.reserve_out(source, &item, amount);
+                            reservations.claim_target(position, worker_entity);
+
+                            // A fetch toward an unconstructed assigned building is
+                            // a construction-material delivery; commit it to that
+                            // building's ledger so other idle workers see the
+                            // remaining demand shrink and don't all fetch a load.
+                            if let Some(building_entity) = worker.assigned_building {
+                                if buildings.get(building_entity).map_or(false, |(_, b, _)| !b.is_constructed) {
+                                    if let Ok(mut ledger) = materials.get_mut(building_entity) {
+                                        ledger.reserve(&item, amount);
+                                    }
+                                }
+                            }
+
+                            worker.current_task = WorkerTask::MovingTo {
+                                target: position,
+                                purpose: TaskPurpose::PickupResource { source, item, amount },
+                            };
+
+                            pathfinding_events.send(PathfindingRequestEvent {
+                                entity: worker_entity,
+                                from: *worker_pos,
+                                to: position,
+                                priority: PathfindingPriority::Normal,
+                                channel: Some(PheromoneChannel::Haul),
+                            });
+                        },
+                        Candidate::DeliverResource { destination, position, item, amount } => {
+                            // Mirrors `transport_completion_system`'s own
+                            // pickup -> carrying transition, so a worker that
+                            // system couldn't find a destination for (and
+                            // would otherwise sit idle forever, still
+                            // carrying the item) gets a second chance here.
+                            reservations.reserve_in(destination, amount);
+                            reservations.claim_target(position, worker_entity);
+
+                            worker.current_task = WorkerTask::Carrying {
+                                from: *worker_pos,
+                                to: position,
+                                destination,
+                                item,
+                                amount,
+                                route: None,
+                            };
+
+                            pathfinding_events.send(PathfindingRequestEvent {
+                                entity: worker_entity,
+                                from: *worker_pos,
+                                to: position,
+                                priority: PathfindingPriority::Normal,
+                                channel: Some(PheromoneChannel::Haul),
+                            });
+                        },
+                        Candidate::Construction { position, .. } => {
+                            worker.current_task = WorkerTask::MovingTo {
+                                target: position,
+                                purpose: TaskPurpose::Construction,
+                            };
+
+                            pathfinding_events.send(PathfindingRequestEvent {
+                                entity: worker_entity,
+                                from: *worker_pos,
+                                to: position,
+                                priority: PathfindingPriority::Normal,
+                                channel: None,
+                            });
+                        },
                     }
                 }
             },
-            
+
             WorkerTask::MovingTo { target, purpose } => {
                 // Check if we've reached the target
                 if worker_pos.distance_to(target) < 1.0 {
@@ -53,14 +153,18 @@ pub fn worker_ai_system(
                                 worker.current_task = WorkerTask::Idle;
                             }
                         },
-                        TaskPurpose::PickupResource { item, amount } => {
-                            // Handle resource pickup
+                        TaskPurpose::PickupResource { source, item, amount } => {
+                            // Handle resource pickup - the item has now physically
+                            // left the source stockpile, so its reservation is spent.
                             worker.carrying = Some((item.clone(), *amount));
+                            reservations.release_out(*source, item, *amount);
+                            reservations.release_target(*target, worker_entity);
                             worker.current_task = WorkerTask::Idle;
-                            
+
                             task_events.send(TaskCompletedEvent {
                                 worker: worker_entity,
                                 task_type: "pickup".to_string(),
+                                destination: Some(*source),
                             });
                         },
                         TaskPurpose::DeliverResource { item, amount } => {
@@ -71,15 +175,17 @@ pub fn worker_ai_system(
                             task_events.send(TaskCompletedEvent {
                                 worker: worker_entity,
                                 task_type: "delivery".to_string(),
+                                destination: None,
                             });
                         },
                         TaskPurpose::Construction => {
                             // Handle construction work
                             worker.current_task = WorkerTask::Idle;
-                            
+
                             task_events.send(TaskCompletedEvent {
                                 worker: worker_entity,
                                 task_type: "construction".to_string(),
+                                destination: None,
                             });
                         },
                     }
@@ -89,27 +195,48 @@ pub fn worker_ai_system(
             WorkerTask::Working { building, progress } => {
                 // Work at the assigned building
                 *progress += delta_time * 1.0; // Fixed movement speed for now
-                
+
+                // Practice slowly raises skill at whatever recipe the building
+                // is working toward, up to the worker type's configured cap.
+                if let Ok(producer) = producers.get(*building) {
+                    if let Some(worker_config) = config.workers.get(&worker.worker_type) {
+                        let skill = worker.skills.entry(producer.recipe_id.clone()).or_insert(0.0);
+                        *skill = (*skill + delta_time * worker_config.learn_rate).min(worker_config.skill_cap);
+                    }
+                }
+
                 if *progress >= 10.0 { // 10 seconds of work
                     worker.current_task = WorkerTask::Idle;
                     
                     task_events.send(TaskCompletedEvent {
                         worker: worker_entity,
                         task_type: "work".to_string(),
+                        destination: None,
                     });
                 }
             },
             
-            WorkerTask::Carrying { from: _, to, item, amount } => {
+            WorkerTask::Carrying { from: _, to, destination, item, amount, route: _ } => {
                 // Move to delivery location
                 if worker_pos.distance_to(to) < 1.0 {
-                    // Deliver the item
+                    // Deliver the item - the space reserved on arrival is now occupied
+                    reservations.release_in(*destination, *amount);
+                    reservations.release_target(*to, worker_entity);
+
+                    if let Ok((_, mut stockpile, _)) = stockpiles.get_mut(*destination) {
+                        stockpile.add_item(item.clone(), *amount);
+                    }
+                    if let Ok(mut ledger) = materials.get_mut(*destination) {
+                        ledger.deliver(item.as_str(), *amount);
+                    }
+
                     worker.carrying = None;
                     worker.current_task = WorkerTask::Idle;
-                    
+
                     task_events.send(TaskCompletedEvent {
                         worker: worker_entity,
                         task_type: "delivery".to_string(),
+                        destination: Some(*destination),
                     });
                 }
             },
@@ -117,11 +244,89 @@ pub fn worker_ai_system(
     }
 }
 
+crate::timed_system!(
+    worker_ai_system_timed,
+    worker_ai_system,
+    "worker_ai_system",
+    workers: Query<(Entity, &mut Worker, &mut Position)>,
+    buildings: Query<(Entity, &Building, &Position), Without<Worker>>,
+    producers: Query<&Producer>,
+    stockpiles: Query<(Entity, &mut Stockpile, &Position)>,
+    config: Res<GameConfig>,
+    tick: Res<GameTick>,
+    registry: Res<ConsiderationRegistry>,
+    task_events: EventWriter<TaskCompletedEvent>,
+    pathfinding_events: EventWriter<PathfindingRequestEvent>,
+    reservations: ResMut<Reservations>,
+    materials: Query<&mut ConstructionMaterials>,
+);
+
+/// Releases every reservation an abandoned (not normally completed) task is
+/// still holding: the claimed destination tile, plus whichever stockpile
+/// hold that task kind carries, plus - if the task was a construction-material
+/// fetch or delivery - the amount it had committed on `assigned_building`'s
+/// `ConstructionMaterials` ledger, plus - if `transport_system` dispatched
+/// this pickup - the incoming-space reservation its `PendingDelivery`
+/// claimed on the final destination. Shared by reassignment (a worker pulled
+/// off one job and onto another) and destination revalidation (a target that
+/// stopped being reachable mid-transit).
+fn abandon_task(
+    reservations: &mut Reservations,
+    materials: &mut Query<&mut ConstructionMaterials>,
+    commands: &mut Commands,
+    worker_entity: Entity,
+    assigned_building: Option<Entity>,
+    pending_delivery: Option<&PendingDelivery>,
+    task: &WorkerTask,
+) {
+    match task {
+        WorkerTask::MovingTo { target, purpose } => {
+            reservations.release_target(*target, worker_entity);
+            if let TaskPurpose::PickupResource { source, item, amount } = purpose {
+                reservations.release_out(*source, item, *amount);
+                release_construction_reservation(materials, assigned_building, item, *amount);
+            }
+            if let Some(pending) = pending_delivery {
+                reservations.release_in(pending.destination, pending.amount);
+                commands.entity(worker_entity).remove::<PendingDelivery>();
+            }
+        },
+        WorkerTask::Carrying { to, destination, item, amount, .. } => {
+            reservations.release_target(*to, worker_entity);
+            reservations.release_in(*destination, *amount);
+            release_construction_reservation(materials, Some(*destination), item, *amount);
+        },
+        WorkerTask::Idle | WorkerTask::Working { .. } => {},
+    }
+}
+
+/// Releases a construction-material reservation an aborted pickup/delivery
+/// was holding on `building`'s ledger, if it has one - a no-op for any
+/// building without a `ConstructionMaterials` component (nothing under
+/// construction) or already past the item in question.
+fn release_construction_reservation(
+    materials: &mut Query<&mut ConstructionMaterials>,
+    building: Option<Entity>,
+    item: &str,
+    amount: u32,
+) {
+    if let Some(building) = building {
+        if let Ok(mut ledger) = materials.get_mut(building) {
+            ledger.release_reservation(item, amount);
+        }
+    }
+}
+
 /// System that handles worker assignment events
 pub fn worker_assignment_system(
+    mut commands: Commands,
     mut events: EventReader<AssignWorkerEvent>,
     mut workers: Query<&mut Worker>,
     mut buildings: Query<&mut Building>,
+    config: Res<GameConfig>,
+    mut reservations: ResMut<Reservations>,
+    mut materials: Query<&mut ConstructionMaterials>,
+    pending_deliveries: Query<&PendingDelivery>,
 ) {
     for event in events.read() {
         if let Ok(mut worker) = workers.get_mut(event.worker) {
@@ -131,16 +336,35 @@ pub fn worker_assignment_system(
                     old_building_comp.assigned_workers = old_building_comp.assigned_workers.saturating_sub(1);
                 }
             }
-            
+
             // Get the new building
             if let Ok(mut building) = buildings.get_mut(event.building) {
-            
+
                 // Assign to new building
                 if building.assigned_workers < building.worker_capacity {
+                    abandon_task(
+                        &mut reservations,
+                        &mut materials,
+                        &mut commands,
+                        event.worker,
+                        worker.assigned_building,
+                        pending_deliveries.get(event.worker).ok(),
+                        &worker.current_task,
+                    );
                     worker.assigned_building = Some(event.building);
                     worker.current_task = WorkerTask::Idle;
                     building.assigned_workers += 1;
-                    
+
+                    // Reserve the building's full construction cost exactly once,
+                    // no matter how many builders end up assigned to it, so they
+                    // don't each independently commit to the same materials.
+                    if !building.is_constructed && reservations.reserved_in(event.building) == 0 {
+                        if let Some(building_config) = config.buildings.get(&building.building_type) {
+                            let total_cost: u32 = building_config.construction_cost.values().sum();
+                            reservations.reserve_in(event.building, total_cost);
+                        }
+                    }
+
                     log::info!("Worker {:?} assigned to building {:?}", event.worker, event.building);
                 } else {
                     log::warn!("Building {:?} is at full capacity", event.building);
@@ -150,33 +374,246 @@ pub fn worker_assignment_system(
     }
 }
 
+crate::timed_system!(
+    worker_assignment_system_timed,
+    worker_assignment_system,
+    "worker_assignment_system",
+    commands: Commands,
+    events: EventReader<AssignWorkerEvent>,
+    workers: Query<&mut Worker>,
+    buildings: Query<&mut Building>,
+    config: Res<GameConfig>,
+    reservations: ResMut<Reservations>,
+    materials: Query<&mut ConstructionMaterials>,
+    pending_deliveries: Query<&PendingDelivery>,
+);
+
+/// System that re-validates each `MovingTo`/`Carrying` worker's destination
+/// every tick: is the target tile still passable, does the building/
+/// stockpile it names still exist, and is there still *a* path there at
+/// all? A worker whose destination fails any of these checks (a building
+/// demolished out from under it, a map edit that walled off its route) has
+/// its reservations released and is sent back to `Idle` to reconsider,
+/// instead of walking forever toward a target that can never be reached.
+pub fn worker_destination_revalidation_system(
+    mut commands: Commands,
+    mut workers: Query<(Entity, &mut Worker, &Position)>,
+    buildings: Query<&Building>,
+    stockpiles: Query<&Stockpile>,
+    map: Res<MapData>,
+    config: Res<GameConfig>,
+    mut cache: ResMut<PathfindingCache>,
+    mut reservations: ResMut<Reservations>,
+    mut materials: Query<&mut ConstructionMaterials>,
+    pending_deliveries: Query<&PendingDelivery>,
+    spatial_index: Res<SpatialIndex>,
+    frame_allocator: Res<FrameAllocator>,
+) {
+    for (worker_entity, mut worker, worker_pos) in workers.iter_mut() {
+        // Carriers' destinations are flags, not stockpiles/buildings, and
+        // they're re-dispatched every tick by `systems::logistics` anyway.
+        if worker.assigned_road_segment.is_some() {
+            continue;
+        }
+
+        let target = match &worker.current_task {
+            WorkerTask::MovingTo { target, .. } => *target,
+            WorkerTask::Carrying { to, .. } => *to,
+            WorkerTask::Idle | WorkerTask::Working { .. } => continue,
+        };
+
+        let point_still_valid = match &worker.current_task {
+            WorkerTask::MovingTo { purpose: TaskPurpose::GoToWork, .. }
+            | WorkerTask::MovingTo { purpose: TaskPurpose::Construction, .. } => worker
+                .assigned_building
+                .map_or(false, |building| buildings.get(building).is_ok()),
+            WorkerTask::MovingTo { purpose: TaskPurpose::PickupResource { source, .. }, .. } => {
+                stockpiles.get(*source).is_ok()
+            },
+            WorkerTask::MovingTo { purpose: TaskPurpose::DeliverResource { .. }, .. } => true,
+            WorkerTask::Carrying { destination, .. } => stockpiles.get(*destination).is_ok(),
+            WorkerTask::Idle | WorkerTask::Working { .. } => unreachable!(),
+        };
+
+        let tile_passable = map
+            .get_tile(target.x, target.y)
+            .map_or(false, |tile| tile.is_passable());
+
+        let reachable = point_still_valid
+            && tile_passable
+            && path_exists(&mut cache, &map, &spatial_index, *worker_pos, target, config.grid_shape, &frame_allocator);
+
+        if !reachable {
+            log::warn!(
+                "Worker {:?}'s destination {:?} is no longer reachable; returning to idle",
+                worker_entity,
+                target
+            );
+            abandon_task(
+                &mut reservations,
+                &mut materials,
+                &mut commands,
+                worker_entity,
+                worker.assigned_building,
+                pending_deliveries.get(worker_entity).ok(),
+                &worker.current_task,
+            );
+            worker.current_task = WorkerTask::Idle;
+        }
+    }
+}
+
+crate::timed_system!(
+    worker_destination_revalidation_system_timed,
+    worker_destination_revalidation_system,
+    "worker_destination_revalidation_system",
+    commands: Commands,
+    workers: Query<(Entity, &mut Worker, &Position)>,
+    buildings: Query<&Building>,
+    stockpiles: Query<&Stockpile>,
+    map: Res<MapData>,
+    config: Res<GameConfig>,
+    cache: ResMut<PathfindingCache>,
+    reservations: ResMut<Reservations>,
+    materials: Query<&mut ConstructionMaterials>,
+    pending_deliveries: Query<&PendingDelivery>,
+    spatial_index: Res<SpatialIndex>,
+    frame_allocator: Res<FrameAllocator>,
+);
+
 /// System that spawns initial workers
+/// Stamps a starting settlement onto the map on tick 1: a handful of
+/// building plots from `map::generate_town` (already roaded to a central
+/// spine as it places them), spawned as real `Building`/`Stockpile` entities,
+/// with workers placed just outside each building's door to service it.
+/// Replaces the old hardcoded "5 workers in a row" spawn so every new game
+/// starts from a coherent, reachable base. Count/density/seed all come from
+/// `GameConfig::settlement` so scenarios and tests can reproduce a specific
+/// start.
 pub fn spawn_workers_system(
     mut commands: Commands,
     tick: Res<GameTick>,
+    mut map: ResMut<MapData>,
+    config: Res<GameConfig>,
 ) {
-    // Spawn some initial workers at the start
-    if tick.current == 1 {
-        for i in 0..5 {
-            commands.spawn((
-                Position::new(10 + i, 10),
-                Worker::new("worker".to_string()),
-            ));
+    if tick.current != 1 {
+        return;
+    }
+
+    let settlement = &config.settlement;
+    let plots = crate::map::generate_town(&mut map, settlement.seed, settlement.starting_buildings);
+
+    if plots.is_empty() {
+        log::warn!("Settlement generator produced no plots; no starting buildings or workers spawned");
+        return;
+    }
+
+    let mut worker_count = 0;
+
+    for plot in &plots {
+        let building_config = config.buildings.get(&plot.role);
+        let worker_capacity = building_config.map_or(1, |b| b.worker_capacity);
+        let stockpile_capacity = building_config.map_or(20, |b| b.stockpile_capacity);
+        let construction_cost = building_config.map_or_else(Default::default, |b| b.construction_cost.clone());
+
+        commands.spawn((
+            plot.position,
+            Building::new(plot.role.clone(), worker_capacity),
+            Stockpile::new(stockpile_capacity),
+            ConstructionMaterials::new(construction_cost),
+            Blocked,
+        ));
+
+        // Door on the south edge, matching `map::generate_town`'s own door
+        // placement, so workers start right where they'd enter the building.
+        let door = Position::new(
+            plot.position.x + plot.size.0 as i32 / 2,
+            plot.position.y + plot.size.1 as i32,
+        );
+
+        for i in 0..settlement.workers_per_building {
+            let worker = Worker::new("worker".to_string());
+            let speed = crate::components::MovementSpeed(worker.movement_speed);
+            commands.spawn((Position::new(door.x + i as i32, door.y), worker, speed));
+            worker_count += 1;
         }
-        log::info!("Spawned 5 initial workers");
     }
+
+    log::info!(
+        "Generated starting settlement: {} buildings, {} workers",
+        plots.len(),
+        worker_count
+    );
 }
 
+crate::timed_system!(
+    spawn_workers_system_timed,
+    spawn_workers_system,
+    "spawn_workers_system",
+    commands: Commands,
+    tick: Res<GameTick>,
+    map: ResMut<MapData>,
+    config: Res<GameConfig>,
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::components::Stockpile;
-    
+
+    #[test]
+    fn test_spawn_workers_system_generates_a_settlement_on_tick_one() {
+        let mut world = World::new();
+
+        let mut map = MapData::new(48, 48);
+        for y in 0..48 {
+            for x in 0..48 {
+                map.set_tile(x, y, crate::components::Tile {
+                    tile_type: crate::components::TileType::Grass,
+                    elevation: 100,
+                });
+            }
+        }
+        world.insert_resource(map);
+
+        let mut config = GameConfig::default();
+        config.settlement.starting_buildings = 3;
+        config.settlement.workers_per_building = 2;
+        world.insert_resource(config);
+        world.insert_resource(GameTick { current: 1, ..GameTick::new(20) });
+
+        let mut system = IntoSystem::into_system(spawn_workers_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        let building_count = world.query::<&Building>().iter(&world).count();
+        let worker_count = world.query::<&Worker>().iter(&world).count();
+        assert_eq!(building_count, 3);
+        assert_eq!(worker_count, 6);
+    }
+
+    #[test]
+    fn test_spawn_workers_system_does_nothing_after_tick_one() {
+        let mut world = World::new();
+        world.insert_resource(MapData::new(48, 48));
+        world.insert_resource(GameConfig::default());
+        world.insert_resource(GameTick { current: 2, ..GameTick::new(20) });
+
+        let mut system = IntoSystem::into_system(spawn_workers_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        assert_eq!(world.query::<&Building>().iter(&world).count(), 0);
+        assert_eq!(world.query::<&Worker>().iter(&world).count(), 0);
+    }
+
     #[test]
     fn test_worker_assignment() {
         let mut world = World::new();
         world.init_resource::<Events<AssignWorkerEvent>>();
-        
+        world.init_resource::<GameConfig>();
+        world.init_resource::<Reservations>();
+
         // Create a worker and building
         let worker_entity = world.spawn(Worker::new("test_worker".to_string())).id();
         let building_entity = world.spawn((
@@ -204,4 +641,227 @@ mod tests {
         assert_eq!(worker.assigned_building, Some(building_entity));
         assert_eq!(building.assigned_workers, 1);
     }
+
+    #[test]
+    fn test_worker_gains_skill_while_working_matching_recipe() {
+        let mut world = World::new();
+        world.init_resource::<Events<TaskCompletedEvent>>();
+        world.init_resource::<Events<PathfindingRequestEvent>>();
+        world.init_resource::<GameConfig>();
+        world.init_resource::<Reservations>();
+        world.init_resource::<ConsiderationRegistry>();
+        world.insert_resource(GameTick::new(10)); // delta_time = 0.1s
+
+        let building_entity = world
+            .spawn((
+                Building::new("sawmill".to_string(), 1),
+                Position::new(5, 5),
+                Producer::new("make_planks".to_string()),
+            ))
+            .id();
+
+        let mut worker = Worker::new("worker".to_string());
+        worker.current_task = WorkerTask::Working {
+            building: building_entity,
+            progress: 0.0,
+        };
+        let worker_entity = world
+            .spawn((worker, Position::new(5, 5)))
+            .id();
+
+        let mut system = IntoSystem::into_system(worker_ai_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        let worker = world.get::<Worker>(worker_entity).unwrap();
+        let skill = worker.skill_for("make_planks");
+        assert!(skill > 0.0, "expected skill to have grown, got {}", skill);
+
+        // Default learn rate is 0.01/s; one 0.1s tick should add ~0.001.
+        assert!((skill - 0.001).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_revalidation_sends_worker_home_when_target_becomes_unreachable() {
+        let mut world = World::new();
+        world.init_resource::<Reservations>();
+        world.init_resource::<GameConfig>();
+        world.insert_resource(crate::resources::MapData::new(10, 10));
+        world.init_resource::<crate::resources::PathfindingCache>();
+        world.init_resource::<SpatialIndex>();
+        world.init_resource::<FrameAllocator>();
+
+        let source_entity = world.spawn(Stockpile::new(10)).id();
+        let target = Position::new(5, 5);
+
+        // Flood the tile the worker is headed toward after it already committed.
+        world
+            .resource_mut::<crate::resources::MapData>()
+            .set_tile(5, 5, crate::components::Tile {
+                tile_type: crate::components::TileType::Water,
+                elevation: 0,
+            });
+
+        let mut worker = Worker::new("worker".to_string());
+        worker.current_task = WorkerTask::MovingTo {
+            target,
+            purpose: TaskPurpose::PickupResource {
+                source: source_entity,
+                item: "wood".to_string(),
+                amount: 3,
+            },
+        };
+        let worker_entity = world.spawn((worker, Position::new(0, 0))).id();
+
+        {
+            let mut reservations = world.resource_mut::<Reservations>();
+            reservations.reserve_out(source_entity, "wood", 3);
+            reservations.claim_target(target, worker_entity);
+        }
+
+        let mut system = IntoSystem::into_system(worker_destination_revalidation_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        let worker = world.get::<Worker>(worker_entity).unwrap();
+        assert!(matches!(worker.current_task, WorkerTask::Idle));
+
+        let reservations = world.resource::<Reservations>();
+        assert_eq!(reservations.reserved_out(source_entity, "wood"), 0);
+        assert_eq!(reservations.target_claimed_by(target), None);
+    }
+
+    #[test]
+    fn test_revalidation_leaves_a_still_reachable_target_alone() {
+        let mut world = World::new();
+        world.init_resource::<Reservations>();
+        world.init_resource::<GameConfig>();
+        world.insert_resource(crate::resources::MapData::new(10, 10));
+        world.init_resource::<crate::resources::PathfindingCache>();
+        world.init_resource::<SpatialIndex>();
+        world.init_resource::<FrameAllocator>();
+
+        let source_entity = world.spawn(Stockpile::new(10)).id();
+        let target = Position::new(5, 5);
+
+        let mut worker = Worker::new("worker".to_string());
+        worker.current_task = WorkerTask::MovingTo {
+            target,
+            purpose: TaskPurpose::PickupResource {
+                source: source_entity,
+                item: "wood".to_string(),
+                amount: 3,
+            },
+        };
+        let worker_entity = world.spawn((worker, Position::new(0, 0))).id();
+
+        let mut system = IntoSystem::into_system(worker_destination_revalidation_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        let worker = world.get::<Worker>(worker_entity).unwrap();
+        assert!(matches!(worker.current_task, WorkerTask::MovingTo { .. }));
+    }
+
+    #[test]
+    fn test_idle_worker_commits_a_construction_fetch_to_the_materials_ledger() {
+        let mut world = World::new();
+        world.init_resource::<Events<TaskCompletedEvent>>();
+        world.init_resource::<Events<PathfindingRequestEvent>>();
+        world.init_resource::<GameConfig>();
+        world.init_resource::<Reservations>();
+        world.init_resource::<ConsiderationRegistry>();
+        world.insert_resource(GameTick::new(10));
+
+        let mut required = std::collections::HashMap::new();
+        required.insert("wood".to_string(), 5u32);
+        let site_entity = world
+            .spawn((
+                Building::new("sawmill".to_string(), 1),
+                Position::new(5, 5),
+                Stockpile::new(20),
+                ConstructionMaterials::new(required),
+            ))
+            .id();
+
+        let source_entity = world
+            .spawn((Stockpile::new(20), Position::new(6, 5)))
+            .id();
+        world.get_mut::<Stockpile>(source_entity).unwrap().add_item("wood".to_string(), 10);
+
+        // Starting on the fetch source's own tile, one step from the
+        // construction site, so `PickupResource` outscores the
+        // always-available `Construction` candidate on distance alone.
+        let mut worker = Worker::new("worker".to_string());
+        worker.assigned_building = Some(site_entity);
+        let worker_entity = world.spawn((worker, Position::new(6, 5))).id();
+
+        let mut system = IntoSystem::into_system(worker_ai_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        let worker = world.get::<Worker>(worker_entity).unwrap();
+        assert!(matches!(
+            worker.current_task,
+            WorkerTask::MovingTo { purpose: TaskPurpose::PickupResource { .. }, .. }
+        ));
+
+        let ledger = world.get::<ConstructionMaterials>(site_entity).unwrap();
+        assert_eq!(ledger.reserved.get("wood").copied().unwrap_or(0), 5);
+    }
+
+    #[test]
+    fn test_construction_delivery_deposits_into_stockpile_and_ledger() {
+        let mut world = World::new();
+        world.init_resource::<Events<TaskCompletedEvent>>();
+        world.init_resource::<Events<PathfindingRequestEvent>>();
+        world.init_resource::<GameConfig>();
+        world.init_resource::<Reservations>();
+        world.init_resource::<ConsiderationRegistry>();
+        world.insert_resource(GameTick::new(10));
+
+        let mut required = std::collections::HashMap::new();
+        required.insert("wood".to_string(), 5u32);
+        let mut ledger = ConstructionMaterials::new(required);
+        ledger.reserve("wood", 5);
+
+        let site_pos = Position::new(5, 5);
+        let site_entity = world
+            .spawn((
+                Building::new("sawmill".to_string(), 1),
+                site_pos,
+                Stockpile::new(20),
+                ledger,
+            ))
+            .id();
+
+        let mut worker = Worker::new("worker".to_string());
+        worker.assigned_building = Some(site_entity);
+        worker.carrying = Some(("wood".to_string(), 5));
+        worker.current_task = WorkerTask::Carrying {
+            from: Position::new(0, 0),
+            to: site_pos,
+            destination: site_entity,
+            item: "wood".to_string(),
+            amount: 5,
+            route: None,
+        };
+        let worker_entity = world.spawn((worker, site_pos)).id();
+
+        let mut system = IntoSystem::into_system(worker_ai_system);
+        system.initialize(&mut world);
+        system.run((), &mut world);
+
+        let worker = world.get::<Worker>(worker_entity).unwrap();
+        assert!(matches!(worker.current_task, WorkerTask::Idle));
+        assert_eq!(worker.carrying, None);
+
+        let stockpile = world.get::<Stockpile>(site_entity).unwrap();
+        assert_eq!(stockpile.get_item_count("wood"), 5);
+
+        let ledger = world.get::<ConstructionMaterials>(site_entity).unwrap();
+        assert_eq!(ledger.delivered.get("wood").copied().unwrap_or(0), 5);
+        assert_eq!(ledger.reserved.get("wood").copied().unwrap_or(0), 0);
+        assert!(ledger.is_fulfilled());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,466 @@
+//! Utility-based ("DSE": Decision-Scoring Entity) task selection for idle
+//! workers, used by [`crate::systems::worker::worker_ai_system`] in place of
+//! the old "only ever go to the assigned building" branch.
+//!
+//! Each tick, an idle worker's candidate actions (go to work, fetch an input
+//! its workplace is running low on, fetch a construction material its
+//! unconstructed workplace still needs, deliver something it's already
+//! carrying, or help finish its unconstructed workplace) are each scored by
+//! a small, independent set of "considerations" - a raw input run through a
+//! response curve into `[0, 1]`. Considerations for a candidate are
+//! combined with a compensated product (see [`compensated_score`]) rather
+//! than a plain product, so a candidate backed by more considerations isn't
+//! unfairly punished relative to one backed by fewer. The highest-scoring
+//! candidate above [`SCORE_THRESHOLD`] wins; otherwise the worker stays idle.
+
+use crate::components::{Building, ConstructionMaterials, Position, Producer, Stockpile, Worker};
+use crate::resources::{GameConfig, Reservations};
+use bevy::prelude::*;
+
+/// Minimum combined score a candidate must clear to be acted on.
+const SCORE_THRESHOLD: f32 = 0.1;
+
+/// Beyond this distance a candidate's distance consideration bottoms out at 0.
+const MAX_CONSIDERATION_DISTANCE: f32 = 40.0;
+
+/// Stock level below which a building is considered running low on an input.
+const LOW_STOCK_THRESHOLD: f32 = 5.0;
+
+/// A worker's candidate action this tick, before scoring.
+#[derive(Debug, Clone)]
+pub enum Candidate {
+    GoToWork {
+        building: Entity,
+        position: Position,
+    },
+    PickupResource {
+        source: Entity,
+        position: Position,
+        item: String,
+        amount: u32,
+        /// The requesting building's current stock of `item` - or, for a
+        /// construction-material fetch, how much has already been delivered
+        /// - used by [`backlog_urgency`].
+        current_stock: u32,
+    },
+    DeliverResource {
+        destination: Entity,
+        position: Position,
+        item: String,
+        amount: u32,
+    },
+    Construction {
+        building: Entity,
+        position: Position,
+    },
+}
+
+impl Candidate {
+    fn position(&self) -> Position {
+        match self {
+            Candidate::GoToWork { position, .. }
+            | Candidate::PickupResource { position, .. }
+            | Candidate::DeliverResource { position, .. }
+            | Candidate::Construction { position, .. } => *position,
+        }
+    }
+}
+
+/// Inputs a consideration reads to produce its `[0, 1]` score, kept narrow
+/// so a new consideration doesn't need extra query access threaded through
+/// the whole scoring pipeline.
+pub struct ScoringContext<'a> {
+    pub worker: &'a Worker,
+    pub worker_pos: Position,
+    pub candidate: &'a Candidate,
+}
+
+pub type Consideration = fn(&ScoringContext) -> f32;
+
+/// Per-candidate-kind lists of considerations, exposed as a resource so a
+/// new building type (or a mod) can register additional considerations
+/// without editing the core scoring loop.
+#[derive(Resource)]
+pub struct ConsiderationRegistry {
+    pub go_to_work: Vec<Consideration>,
+    pub pickup_resource: Vec<Consideration>,
+    pub deliver_resource: Vec<Consideration>,
+    pub construction: Vec<Consideration>,
+}
+
+impl Default for ConsiderationRegistry {
+    fn default() -> Self {
+        Self {
+            go_to_work: vec![distance_falloff],
+            pickup_resource: vec![distance_falloff, backlog_urgency],
+            deliver_resource: vec![distance_falloff, carried_item_match_gate],
+            construction: vec![distance_falloff],
+        }
+    }
+}
+
+impl ConsiderationRegistry {
+    fn considerations_for(&self, candidate: &Candidate) -> &[Consideration] {
+        match candidate {
+            Candidate::GoToWork { .. } => &self.go_to_work,
+            Candidate::PickupResource { .. } => &self.pickup_resource,
+            Candidate::DeliverResource { .. } => &self.deliver_resource,
+            Candidate::Construction { .. } => &self.construction,
+        }
+    }
+}
+
+/// Linear falloff: `1.0` at zero distance, `0.0` at or beyond
+/// [`MAX_CONSIDERATION_DISTANCE`].
+fn distance_falloff(ctx: &ScoringContext) -> f32 {
+    let distance = ctx.worker_pos.distance_to(&ctx.candidate.position());
+    (1.0 - distance / MAX_CONSIDERATION_DISTANCE).clamp(0.0, 1.0)
+}
+
+/// Rising curve on how depleted the requesting building's stock of the
+/// needed item is: `0.0` at [`LOW_STOCK_THRESHOLD`] or above, `1.0` at zero
+/// stock.
+fn backlog_urgency(ctx: &ScoringContext) -> f32 {
+    match ctx.candidate {
+        Candidate::PickupResource { current_stock, .. } => {
+            (1.0 - (*current_stock as f32 / LOW_STOCK_THRESHOLD)).clamp(0.0, 1.0)
+        }
+        _ => 0.0,
+    }
+}
+
+/// `0`/`1` gate: a delivery only makes sense if the worker is actually
+/// carrying the item the candidate would deliver.
+fn carried_item_match_gate(ctx: &ScoringContext) -> f32 {
+    match (&ctx.worker.carrying, ctx.candidate) {
+        (Some((carried_item, _)), Candidate::DeliverResource { item, .. })
+            if carried_item == item =>
+        {
+            1.0
+        }
+        _ => 0.0,
+    }
+}
+
+/// Combines per-consideration `scores` into one candidate score using Dave
+/// Mark's compensated-product formula ("Building a Better Centaur", GDC): a
+/// plain product punishes a candidate for having more considerations than a
+/// rival, so the shortfall from `1.0` is partially made up, scaled by how
+/// many considerations contributed.
+fn compensated_score(scores: &[f32]) -> f32 {
+    if scores.is_empty() {
+        return 0.0;
+    }
+
+    let product: f32 = scores.iter().product();
+    let n = scores.len() as f32;
+    let modification_factor = 1.0 - (1.0 / n);
+    let make_up_value = (1.0 - product) * modification_factor;
+
+    (product + (make_up_value * product)).clamp(0.0, 1.0)
+}
+
+/// Scores every candidate and returns the highest-scoring one, if any
+/// clears [`SCORE_THRESHOLD`].
+pub fn pick_best_candidate(
+    registry: &ConsiderationRegistry,
+    worker: &Worker,
+    worker_pos: Position,
+    candidates: Vec<Candidate>,
+) -> Option<Candidate> {
+    candidates
+        .into_iter()
+        .map(|candidate| {
+            let ctx = ScoringContext {
+                worker,
+                worker_pos,
+                candidate: &candidate,
+            };
+            let scores: Vec<f32> = registry
+                .considerations_for(&candidate)
+                .iter()
+                .map(|consideration| consideration(&ctx))
+                .collect();
+            let score = compensated_score(&scores);
+            (candidate, score)
+        })
+        .filter(|(_, score)| *score >= SCORE_THRESHOLD)
+        .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+        .map(|(candidate, _)| candidate)
+}
+
+/// Enumerates every candidate action available to an idle `worker` this
+/// tick. `worker_entity` is only used to recognize the worker's own
+/// in-flight target claims (see [`find_pickup_source`]/
+/// [`find_delivery_destination`]) as non-conflicting, not to identify the
+/// worker among the passed-in queries.
+pub fn enumerate_candidates(
+    worker_entity: Entity,
+    worker: &Worker,
+    buildings: &Query<(Entity, &Building, &Position), Without<Worker>>,
+    producers: &Query<&Producer>,
+    stockpiles: &Query<(Entity, &mut Stockpile, &Position)>,
+    config: &GameConfig,
+    reservations: &Reservations,
+    materials: &Query<&mut ConstructionMaterials>,
+) -> Vec<Candidate> {
+    let mut candidates = Vec::new();
+
+    if let Some(building_entity) = worker.assigned_building {
+        if let Ok((_, building, building_pos)) = buildings.get(building_entity) {
+            if building.is_constructed {
+                // Not target-claimed: every worker assigned to this building
+                // is independently entitled to work here up to its
+                // `worker_capacity`, already enforced at assignment time.
+                candidates.push(Candidate::GoToWork {
+                    building: building_entity,
+                    position: *building_pos,
+                });
+
+                if let Ok(producer) = producers.get(building_entity) {
+                    if let Some(recipe) = config.recipes.get(&producer.recipe_id) {
+                        if let Ok((_, own_stockpile, _)) = stockpiles.get(building_entity) {
+                            for item in recipe.inputs.keys() {
+                                let current_stock = own_stockpile.get_item_count(item);
+                                if (current_stock as f32) >= LOW_STOCK_THRESHOLD {
+                                    continue;
+                                }
+
+                                if let Some((source, source_pos, available)) = find_pickup_source(
+                                    worker_entity,
+                                    building_entity,
+                                    item,
+                                    stockpiles,
+                                    reservations,
+                                ) {
+                                    let needed =
+                                        (LOW_STOCK_THRESHOLD as u32).saturating_sub(current_stock).max(1);
+                                    candidates.push(Candidate::PickupResource {
+                                        source,
+                                        position: source_pos,
+                                        item: item.clone(),
+                                        amount: available.min(needed),
+                                        current_stock,
+                                    });
+                                }
+                            }
+                        }
+                    }
+                }
+            } else {
+                // Not target-claimed: construction intentionally allows
+                // multiple simultaneous builders at the same site.
+                candidates.push(Candidate::Construction {
+                    building: building_entity,
+                    position: *building_pos,
+                });
+
+                // Fetch whatever the job's materials ledger still has unmet
+                // demand for, same shape as the producer-input fetch above,
+                // so a site already fully reserved (or delivered) stops
+                // pulling in more idle workers than it needs.
+                if let Ok(ledger) = materials.get(building_entity) {
+                    for item in ledger.required.keys() {
+                        let remaining = ledger.remaining_demand(item);
+                        if remaining == 0 {
+                            continue;
+                        }
+
+                        if let Some((source, source_pos, available)) = find_pickup_source(
+                            worker_entity,
+                            building_entity,
+                            item,
+                            stockpiles,
+                            reservations,
+                        ) {
+                            candidates.push(Candidate::PickupResource {
+                                source,
+                                position: source_pos,
+                                item: item.clone(),
+                                amount: available.min(remaining),
+                                current_stock: ledger.delivered.get(item).copied().unwrap_or(0),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some((item, amount)) = &worker.carrying {
+        // A construction job the worker is already assigned to, still short
+        // of this exact item, takes priority over the generic "any stockpile
+        // with room" fallback below - otherwise a hauled load could land in
+        // an unrelated building instead of the site that asked for it.
+        let construction_destination = worker.assigned_building.and_then(|building_entity| {
+            let (_, building, building_pos) = buildings.get(building_entity).ok()?;
+            if building.is_constructed {
+                return None;
+            }
+            let remaining = materials.get(building_entity).map_or(0, |m| m.remaining_demand(item));
+            (remaining > 0).then_some((building_entity, *building_pos, remaining))
+        });
+
+        if let Some((destination, dest_pos, remaining)) = construction_destination {
+            candidates.push(Candidate::DeliverResource {
+                destination,
+                position: dest_pos,
+                item: item.clone(),
+                amount: (*amount).min(remaining),
+            });
+        } else if let Some((destination, dest_pos)) =
+            find_delivery_destination(worker_entity, item, *amount, stockpiles, reservations)
+        {
+            candidates.push(Candidate::DeliverResource {
+                destination,
+                position: dest_pos,
+                item: item.clone(),
+                amount: *amount,
+            });
+        }
+    }
+
+    candidates
+}
+
+/// Finds the stockpile (other than `requesting_building`) with the most
+/// uncommitted `item` available to fetch, skipping any whose tile another
+/// worker has already claimed as a destination this tick.
+fn find_pickup_source(
+    worker_entity: Entity,
+    requesting_building: Entity,
+    item: &str,
+    stockpiles: &Query<(Entity, &mut Stockpile, &Position)>,
+    reservations: &Reservations,
+) -> Option<(Entity, Position, u32)> {
+    stockpiles
+        .iter()
+        .filter(|(entity, _, _)| *entity != requesting_building)
+        .filter(|(_, _, pos)| {
+            reservations
+                .target_claimed_by(**pos)
+                .map_or(true, |claimant| claimant == worker_entity)
+        })
+        .filter_map(|(entity, stockpile, pos)| {
+            let available = stockpile.effective_item_count(item, entity, reservations);
+            (available > 0).then_some((entity, *pos, available))
+        })
+        .max_by_key(|(_, _, available)| *available)
+}
+
+/// Finds a stockpile with enough uncommitted space to take `amount` of
+/// `item`, skipping any whose tile another worker has already claimed as a
+/// destination this tick.
+fn find_delivery_destination(
+    worker_entity: Entity,
+    item: &str,
+    amount: u32,
+    stockpiles: &Query<(Entity, &mut Stockpile, &Position)>,
+    reservations: &Reservations,
+) -> Option<(Entity, Position)> {
+    stockpiles
+        .iter()
+        .filter(|(_, _, pos)| {
+            reservations
+                .target_claimed_by(**pos)
+                .map_or(true, |claimant| claimant == worker_entity)
+        })
+        .find(|(entity, stockpile, _)| {
+            stockpile.effective_available_space(*entity, reservations) >= amount
+        })
+        .map(|(entity, _, pos)| (entity, *pos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn worker_at(x: i32, y: i32) -> (Worker, Position) {
+        (Worker::new("worker".to_string()), Position::new(x, y))
+    }
+
+    #[test]
+    fn test_distance_falloff_decreases_with_distance() {
+        let (worker, _) = worker_at(0, 0);
+        let close = Candidate::GoToWork {
+            building: Entity::from_raw(0),
+            position: Position::new(1, 0),
+        };
+        let far = Candidate::GoToWork {
+            building: Entity::from_raw(0),
+            position: Position::new(39, 0),
+        };
+
+        let close_score = distance_falloff(&ScoringContext {
+            worker: &worker,
+            worker_pos: Position::new(0, 0),
+            candidate: &close,
+        });
+        let far_score = distance_falloff(&ScoringContext {
+            worker: &worker,
+            worker_pos: Position::new(0, 0),
+            candidate: &far,
+        });
+
+        assert!(close_score > far_score);
+    }
+
+    #[test]
+    fn test_carried_item_match_gate() {
+        let (mut worker, pos) = worker_at(0, 0);
+        worker.carrying = Some(("wood".to_string(), 5));
+
+        let matching = Candidate::DeliverResource {
+            destination: Entity::from_raw(0),
+            position: pos,
+            item: "wood".to_string(),
+            amount: 5,
+        };
+        let mismatching = Candidate::DeliverResource {
+            destination: Entity::from_raw(0),
+            position: pos,
+            item: "stone".to_string(),
+            amount: 5,
+        };
+
+        assert_eq!(
+            carried_item_match_gate(&ScoringContext {
+                worker: &worker,
+                worker_pos: pos,
+                candidate: &matching,
+            }),
+            1.0
+        );
+        assert_eq!(
+            carried_item_match_gate(&ScoringContext {
+                worker: &worker,
+                worker_pos: pos,
+                candidate: &mismatching,
+            }),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_compensated_score_beats_plain_product_for_long_chains() {
+        let scores = vec![0.8, 0.8, 0.8];
+        let plain_product: f32 = scores.iter().product();
+        let compensated = compensated_score(&scores);
+
+        assert!(compensated > plain_product);
+    }
+
+    #[test]
+    fn test_pick_best_candidate_respects_threshold() {
+        let registry = ConsiderationRegistry::default();
+        let (worker, pos) = worker_at(0, 0);
+
+        let far_away = Candidate::GoToWork {
+            building: Entity::from_raw(0),
+            position: Position::new(1000, 1000),
+        };
+
+        let result = pick_best_candidate(&registry, &worker, pos, vec![far_away]);
+        assert!(result.is_none());
+    }
+}
@@ -0,0 +1,204 @@
+//! Configurable benchmark harness backing the `Benchmark` subcommand.
+//!
+//! Unlike a plain "run N ticks and average the TPS" loop, this harness runs
+//! for a wall-clock duration, optionally throttles to a target tick rate
+//! while reporting how close it got to the target, and attaches whichever
+//! named [`Profiler`]s were requested. The result for a scenario is emitted
+//! as a structured [`BenchmarkReport`] (RON or JSON) so two runs can be
+//! diffed to catch regressions across commits.
+
+mod profilers;
+
+use anyhow::{anyhow, Result};
+use oldtimes_core::SimulationApp;
+use profilers::{FlamegraphProfiler, MetricsProfiler, Profiler, SysMonitorProfiler};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+/// Options for one `Benchmark` invocation.
+pub struct BenchmarkConfig {
+    pub scenario: String,
+    pub bench_length_seconds: f32,
+    pub operations_per_second: Option<f32>,
+    pub profilers: Vec<String>,
+    pub report_format: ReportFormat,
+    pub report_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Ron,
+    Json,
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "ron" => Ok(Self::Ron),
+            "json" => Ok(Self::Json),
+            other => Err(anyhow!(
+                "unknown report format '{other}', expected 'ron' or 'json'"
+            )),
+        }
+    }
+}
+
+/// Structured result for one scenario run, meant to be diffed across commits.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    pub scenario: String,
+    pub requested_ops_per_second: Option<f32>,
+    pub achieved_ops_per_second: f32,
+    pub ticks_run: u64,
+    pub wall_time_seconds: f32,
+    pub final_entity_count: u32,
+    pub average_tick_time_ms: f32,
+    pub pathfinding_cache_hit_rate: f32,
+    pub profiler_outputs: Vec<ProfilerOutput>,
+}
+
+/// One profiler's contribution to a [`BenchmarkReport`].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProfilerOutput {
+    pub name: String,
+    /// Path to a file the profiler wrote, for profilers whose output
+    /// (folded-stack, pprof) isn't practical to embed inline.
+    pub output_path: Option<String>,
+    /// Inline time series, for profilers small enough to embed directly.
+    pub samples: Vec<ProfilerSample>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProfilerSample {
+    pub at_seconds: f32,
+    pub fields: BTreeMap<String, f32>,
+}
+
+/// Runs the demo simulation for `config.bench_length_seconds`, optionally
+/// throttled to `config.operations_per_second`, with the requested
+/// profilers attached, then writes the structured report to
+/// `config.report_path` (or stdout if unset). `config.scenario` only labels
+/// the report; every scenario name runs the same demo map.
+pub fn run_benchmark(config: BenchmarkConfig) -> Result<()> {
+    log::info!(
+        "Running benchmark: scenario={}, length={}s, target_ops={:?}, profilers={:?}",
+        config.scenario, config.bench_length_seconds, config.operations_per_second, config.profilers
+    );
+
+    let mut profilers = build_profilers(&config.profilers)?;
+
+    let mut sim = SimulationApp::new();
+    sim.initialize_demo();
+
+    for profiler in &mut profilers {
+        profiler.start();
+    }
+
+    let target_tick_duration = config
+        .operations_per_second
+        .filter(|ops| *ops > 0.0)
+        .map(|ops| Duration::from_secs_f32(1.0 / ops));
+
+    let run_start = Instant::now();
+    let bench_length = Duration::from_secs_f32(config.bench_length_seconds);
+    let mut ticks_run = 0u64;
+    let mut total_tick_time = Duration::ZERO;
+
+    while run_start.elapsed() < bench_length {
+        let tick_start = Instant::now();
+        sim.tick();
+        total_tick_time += tick_start.elapsed();
+        ticks_run += 1;
+
+        let elapsed = run_start.elapsed();
+        for profiler in &mut profilers {
+            profiler.sample(&sim, elapsed);
+        }
+
+        if let Some(target) = target_tick_duration {
+            let tick_elapsed = tick_start.elapsed();
+            if tick_elapsed < target {
+                std::thread::sleep(target - tick_elapsed);
+            }
+        }
+    }
+
+    let wall_time = run_start.elapsed();
+    let achieved_ops_per_second = ticks_run as f32 / wall_time.as_secs_f32();
+
+    let metrics = sim.get_metrics();
+    let average_tick_time_ms = total_tick_time.as_secs_f32() * 1000.0 / ticks_run.max(1) as f32;
+    let final_entity_count = metrics.entities_count;
+    let pathfinding_cache_hit_rate = sim
+        .get_resource::<oldtimes_core::PathfindingCache>()
+        .map(|cache| cache.hit_rate())
+        .unwrap_or(0.0);
+
+    let profiler_outputs = profilers
+        .into_iter()
+        .map(|profiler| {
+            let name = profiler.name().to_string();
+            let (samples, output_path) = profiler.finish();
+            ProfilerOutput {
+                name,
+                output_path,
+                samples,
+            }
+        })
+        .collect();
+
+    let report = BenchmarkReport {
+        scenario: config.scenario.clone(),
+        requested_ops_per_second: config.operations_per_second,
+        achieved_ops_per_second,
+        ticks_run,
+        wall_time_seconds: wall_time.as_secs_f32(),
+        final_entity_count,
+        average_tick_time_ms,
+        pathfinding_cache_hit_rate,
+        profiler_outputs,
+    };
+
+    log::info!(
+        "Benchmark '{}' done: {} ticks in {:.2}s ({:.1} ops/s, requested {:?})",
+        report.scenario,
+        report.ticks_run,
+        report.wall_time_seconds,
+        report.achieved_ops_per_second,
+        report.requested_ops_per_second,
+    );
+
+    let serialized = match config.report_format {
+        ReportFormat::Ron => {
+            ron::ser::to_string_pretty(&report, ron::ser::PrettyConfig::default())?
+        }
+        ReportFormat::Json => serde_json::to_string_pretty(&report)?,
+    };
+
+    match &config.report_path {
+        Some(path) => {
+            std::fs::write(path, &serialized)?;
+            log::info!("Benchmark report written to {}", path);
+        }
+        None => println!("{serialized}"),
+    }
+
+    Ok(())
+}
+
+fn build_profilers(names: &[String]) -> Result<Vec<Box<dyn Profiler>>> {
+    names
+        .iter()
+        .map(|name| match name.as_str() {
+            "sys_monitor" => Ok(Box::new(SysMonitorProfiler::new()) as Box<dyn Profiler>),
+            "metrics" => Ok(Box::new(MetricsProfiler::new()) as Box<dyn Profiler>),
+            "flamegraph" => Ok(Box::new(FlamegraphProfiler::new()) as Box<dyn Profiler>),
+            other => Err(anyhow!(
+                "unknown profiler '{other}', expected one of: sys_monitor, metrics, flamegraph"
+            )),
+        })
+        .collect()
+}
@@ -0,0 +1,204 @@
+use super::ProfilerSample;
+use oldtimes_core::SimulationApp;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use sysinfo::{Pid, System};
+
+/// A pluggable sampler attached to a benchmark run, selected by name via
+/// `--profilers`. `start`/`sample`/`finish` bracket the run; `sample` runs
+/// once per tick with the simulation and the elapsed wall time so far, and
+/// `finish` hands back whatever it collected for the `BenchmarkReport`.
+pub trait Profiler {
+    fn name(&self) -> &'static str;
+    fn start(&mut self) {}
+    fn sample(&mut self, sim: &SimulationApp, elapsed: Duration);
+    fn finish(self: Box<Self>) -> (Vec<ProfilerSample>, Option<String>);
+}
+
+/// Samples process CPU% and RSS on a fixed interval from a background
+/// thread, independent of tick rate, so a throttled `--operations-per-second`
+/// run still gets evenly spaced system samples.
+pub struct SysMonitorProfiler {
+    samples: Arc<Mutex<Vec<ProfilerSample>>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<std::thread::JoinHandle<()>>,
+    start: Option<std::time::Instant>,
+}
+
+impl SysMonitorProfiler {
+    const SAMPLE_INTERVAL: Duration = Duration::from_millis(200);
+
+    pub fn new() -> Self {
+        Self {
+            samples: Arc::new(Mutex::new(Vec::new())),
+            stop: Arc::new(AtomicBool::new(false)),
+            thread: None,
+            start: None,
+        }
+    }
+}
+
+impl Profiler for SysMonitorProfiler {
+    fn name(&self) -> &'static str {
+        "sys_monitor"
+    }
+
+    fn start(&mut self) {
+        let samples = Arc::clone(&self.samples);
+        let stop = Arc::clone(&self.stop);
+        let start = std::time::Instant::now();
+        self.start = Some(start);
+
+        self.thread = Some(std::thread::spawn(move || {
+            let pid = sysinfo::get_current_pid().unwrap_or(Pid::from(0));
+            let mut system = System::new_all();
+
+            while !stop.load(Ordering::Relaxed) {
+                system.refresh_cpu_usage();
+                system.refresh_memory();
+                system.refresh_process(pid);
+
+                let process = system.process(pid);
+                let cpu_percent = process.map(|process| process.cpu_usage()).unwrap_or(0.0);
+                let rss_bytes = process.map(|process| process.memory()).unwrap_or(0);
+
+                let mut fields = BTreeMap::new();
+                fields.insert("cpu_percent".to_string(), cpu_percent);
+                fields.insert("rss_bytes".to_string(), rss_bytes as f32);
+
+                samples.lock().unwrap().push(ProfilerSample {
+                    at_seconds: start.elapsed().as_secs_f32(),
+                    fields,
+                });
+
+                std::thread::sleep(Self::SAMPLE_INTERVAL);
+            }
+        }));
+    }
+
+    fn sample(&mut self, _sim: &SimulationApp, _elapsed: Duration) {
+        // Sampling happens on the background thread; nothing to do per tick.
+    }
+
+    fn finish(mut self: Box<Self>) -> (Vec<ProfilerSample>, Option<String>) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+
+        let samples = std::mem::take(&mut *self.samples.lock().unwrap());
+        (samples, None)
+    }
+}
+
+/// Dumps `SimulationApp::get_metrics` plus the pathfinding cache hit-rate as
+/// a time series, one sample per tick.
+pub struct MetricsProfiler {
+    samples: Vec<ProfilerSample>,
+    start: Option<std::time::Instant>,
+}
+
+impl MetricsProfiler {
+    pub fn new() -> Self {
+        Self {
+            samples: Vec::new(),
+            start: None,
+        }
+    }
+}
+
+impl Profiler for MetricsProfiler {
+    fn name(&self) -> &'static str {
+        "metrics"
+    }
+
+    fn start(&mut self) {
+        self.start = Some(std::time::Instant::now());
+    }
+
+    fn sample(&mut self, sim: &SimulationApp, elapsed: Duration) {
+        let metrics = sim.get_metrics();
+        let hit_rate = sim
+            .get_resource::<oldtimes_core::PathfindingCache>()
+            .map(|cache| cache.hit_rate())
+            .unwrap_or(0.0);
+
+        let mut fields = BTreeMap::new();
+        fields.insert("tick_time_ms".to_string(), metrics.tick_time);
+        fields.insert("entities_count".to_string(), metrics.entities_count as f32);
+        fields.insert(
+            "total_system_time_ms".to_string(),
+            metrics.get_total_system_time(),
+        );
+        fields.insert("pathfinding_cache_hit_rate".to_string(), hit_rate);
+
+        self.samples.push(ProfilerSample {
+            at_seconds: elapsed.as_secs_f32(),
+            fields,
+        });
+    }
+
+    fn finish(self: Box<Self>) -> (Vec<ProfilerSample>, Option<String>) {
+        (self.samples, None)
+    }
+}
+
+/// Records per-tick wall-clock durations and writes them out as a
+/// folded-stack file (`benchmark;tick_<index> <microseconds>` lines) that
+/// `flamegraph`/`samply` can render directly, since the simulation itself
+/// doesn't expose per-system call stacks outside of `profile_systems_system`.
+pub struct FlamegraphProfiler {
+    scenario: String,
+    tick_durations_us: Vec<u64>,
+    last_sample_at: Duration,
+    output_path: String,
+}
+
+impl FlamegraphProfiler {
+    pub fn new() -> Self {
+        Self::with_output("flamegraph.folded")
+    }
+
+    pub fn with_output(output_path: impl Into<String>) -> Self {
+        Self {
+            scenario: "benchmark".to_string(),
+            tick_durations_us: Vec::new(),
+            last_sample_at: Duration::ZERO,
+            output_path: output_path.into(),
+        }
+    }
+}
+
+impl Profiler for FlamegraphProfiler {
+    fn name(&self) -> &'static str {
+        "flamegraph"
+    }
+
+    fn sample(&mut self, _sim: &SimulationApp, elapsed: Duration) {
+        let tick_duration = elapsed.saturating_sub(self.last_sample_at);
+        self.last_sample_at = elapsed;
+        self.tick_durations_us.push(tick_duration.as_micros() as u64);
+    }
+
+    fn finish(self: Box<Self>) -> (Vec<ProfilerSample>, Option<String>) {
+        let mut folded = String::new();
+        for (tick, duration_us) in self.tick_durations_us.iter().enumerate() {
+            folded.push_str(&format!(
+                "{};tick_{} {}\n",
+                self.scenario, tick, duration_us
+            ));
+        }
+
+        if let Err(e) = std::fs::write(&self.output_path, folded) {
+            log::warn!(
+                "Failed to write flamegraph folded-stack file {}: {e}",
+                self.output_path
+            );
+            return (Vec::new(), None);
+        }
+
+        (Vec::new(), Some(self.output_path))
+    }
+}
@@ -1,6 +1,11 @@
+mod benchmark;
+mod scenario;
+mod timing_report;
+
 use clap::{Parser, Subcommand};
 use oldtimes_core::{SimulationApp, save::*, data::*};
 use anyhow::Result;
+use scenario::Scenario;
 use std::time::Instant;
 use ron::ser::{to_string_pretty, PrettyConfig};
 
@@ -35,16 +40,46 @@ enum Commands {
         /// Enable verbose logging
         #[arg(short, long)]
         verbose: bool,
+
+        /// Watch the data directory and hot-reload config between ticks
+        #[arg(long)]
+        watch: bool,
+
+        /// Write a self-contained HTML per-system tick-timing report here when the run finishes
+        #[arg(long)]
+        timing_report: Option<String>,
+
+        /// Mod directories to layer via ModLoader, in order
+        #[arg(long, value_delimiter = ',')]
+        mods: Vec<String>,
+
+        /// Load the full run description from a scenario file instead of the flags above
+        #[arg(long)]
+        config: Option<String>,
+
+        /// Serialize the settings this invocation resolved to (from --config or the flags above) to a scenario file and exit
+        #[arg(long)]
+        write_config: Option<String>,
     },
-    
+
+    /// Run a simulation from a previously captured scenario file
+    Scenario {
+        /// Scenario file to load
+        scenario_file: String,
+    },
+
     /// Replay a recorded session
     Replay {
         /// Replay file to load
         replay_file: String,
-        
+
         /// Verify determinism by comparing with fresh run
         #[arg(long)]
         verify: bool,
+
+        /// When replaying without --verify, log each tick's state digest
+        #[arg(long)]
+        dump_digests: bool,
     },
     
     /// Run performance benchmark
@@ -52,10 +87,27 @@ enum Commands {
         /// Benchmark scenario to run
         #[arg(long, default_value = "standard")]
         scenario: String,
-        
-        /// Number of iterations
-        #[arg(long, default_value = "5")]
-        iterations: u32,
+
+        /// Wall-clock length of the run
+        #[arg(long, default_value = "30")]
+        bench_length_seconds: f32,
+
+        /// Target tick rate to hold; achieved vs. requested is reported.
+        /// Unset means run as fast as possible.
+        #[arg(long)]
+        operations_per_second: Option<f32>,
+
+        /// Profilers to attach, e.g. `--profilers sys_monitor,metrics,flamegraph`
+        #[arg(long, value_delimiter = ',', default_value = "metrics")]
+        profilers: Vec<String>,
+
+        /// Report format: ron or json
+        #[arg(long, default_value = "ron")]
+        report_format: String,
+
+        /// File to write the structured report to (stdout if unset)
+        #[arg(long)]
+        report_out: Option<String>,
     },
     
     /// Generate a new map
@@ -89,17 +141,71 @@ fn main() -> Result<()> {
     let cli = Cli::parse();
     
     match &cli.command {
-        Commands::Run { map, ticks, record, tps, verbose } => {
+        Commands::Run {
+            map,
+            ticks,
+            record,
+            tps,
+            verbose,
+            watch,
+            timing_report,
+            mods,
+            config,
+            write_config,
+        } => {
             init_logging(*verbose);
-            run_simulation(map, *ticks, record.as_deref(), *tps)
+
+            let scenario = match config {
+                Some(config_path) => Scenario::load(config_path)?,
+                None => {
+                    let scenario = Scenario {
+                        map: map.clone(),
+                        ticks: *ticks,
+                        tps: *tps,
+                        mods: mods.clone(),
+                        record: record.clone(),
+                        timing_report: timing_report.clone(),
+                        watch: *watch,
+                    };
+                    scenario.validate()?;
+                    scenario
+                }
+            };
+
+            if let Some(write_config_path) = write_config {
+                scenario.save(write_config_path)?;
+                log::info!("Scenario written to {write_config_path}");
+                return Ok(());
+            }
+
+            run_simulation(&scenario)
+        },
+        Commands::Scenario { scenario_file } => {
+            init_logging(false);
+            let scenario = Scenario::load(scenario_file)?;
+            run_simulation(&scenario)
         },
-        Commands::Replay { replay_file, verify } => {
+        Commands::Replay { replay_file, verify, dump_digests } => {
             init_logging(true);
-            replay_simulation(replay_file, *verify)
+            replay_simulation(replay_file, *verify, *dump_digests)
         },
-        Commands::Benchmark { scenario, iterations } => {
+        Commands::Benchmark {
+            scenario,
+            bench_length_seconds,
+            operations_per_second,
+            profilers,
+            report_format,
+            report_out,
+        } => {
             init_logging(false);
-            run_benchmark(scenario, *iterations)
+            benchmark::run_benchmark(benchmark::BenchmarkConfig {
+                scenario: scenario.clone(),
+                bench_length_seconds: *bench_length_seconds,
+                operations_per_second: *operations_per_second,
+                profilers: profilers.clone(),
+                report_format: report_format.parse()?,
+                report_path: report_out.clone(),
+            })
         },
         Commands::GenerateMap { output, width, height, seed } => {
             init_logging(false);
@@ -124,42 +230,86 @@ fn init_logging(verbose: bool) {
         .init();
 }
 
-fn run_simulation(map_name: &str, ticks: u64, record_file: Option<&str>, tps: u32) -> Result<()> {
-    log::info!("Starting simulation: map={}, ticks={}, tps={}", map_name, ticks, tps);
-    
+fn run_simulation(scenario: &Scenario) -> Result<()> {
+    log::info!("Starting simulation from scenario: {scenario:?}");
+
+    let config_updates = if scenario.watch {
+        match DataLoader::watch_directory("assets/data") {
+            Ok(rx) => Some(rx),
+            Err(e) => {
+                log::warn!("Failed to start data-file watcher, continuing without hot-reload: {e}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
     let mut sim = SimulationApp::new();
-    
+
     // Set target TPS
     if let Some(mut tick_resource) = sim.get_resource_mut::<oldtimes_core::GameTick>() {
-        tick_resource.target_tps = tps;
+        tick_resource.target_tps = scenario.tps;
     }
-    
+
     // Initialize map
-    if map_name == "demo" {
+    if scenario.map == "demo" {
         sim.initialize_demo();
     } else {
         // In a full implementation, load map from file
         log::warn!("Custom map loading not implemented, using demo map");
         sim.initialize_demo();
     }
-    
+
+    // Layer mod directories on top of the base data, in order
+    for mods_dir in &scenario.mods {
+        let mod_config = ModLoader::load_mods(mods_dir)?;
+        if let Some(mut config) = sim.get_resource_mut::<oldtimes_core::GameConfig>() {
+            for (id, building) in mod_config.buildings {
+                config.buildings.insert(id, building);
+            }
+            for (id, recipe) in mod_config.recipes {
+                config.recipes.insert(id, recipe);
+            }
+            for (id, worker) in mod_config.workers {
+                config.workers.insert(id, worker);
+            }
+        }
+        log::info!("Layered mods from {mods_dir}");
+    }
+
     // Setup recording if requested
-    let mut recorder = record_file.map(|_| start_recording());
-    
+    let mut recorder = scenario.record.as_ref().map(|_| start_recording());
+
     // Run simulation
+    let ticks = scenario.ticks;
+    let tps = scenario.tps;
     let start_time = Instant::now();
     let target_tick_duration = std::time::Duration::from_secs_f32(1.0 / tps as f32);
-    
+
     for tick in 0..ticks {
         let tick_start = Instant::now();
-        
+
+        // Pick up the latest hot-reloaded config, if any, at this tick
+        // boundary rather than mid-tick.
+        if let Some(rx) = &config_updates {
+            if let Ok(new_config) = rx.try_recv() {
+                if let Some(mut config) = sim.get_resource_mut::<oldtimes_core::GameConfig>() {
+                    *config = new_config;
+                    log::info!("Hot-reloaded game config at tick {}", tick);
+                }
+            }
+        }
+
         sim.tick();
-        
-        // Record events if needed
+
+        // Record this tick's state digest for replay verification
         if let Some(ref mut rec) = recorder {
             // In a full implementation, capture and record events here
+            rec.record_digest(sim.calculate_state_digest());
         }
-        
+
+
         // Progress reporting
         if tick % (tps as u64 * 5) == 0 {
             let elapsed = start_time.elapsed().as_secs_f32();
@@ -191,75 +341,48 @@ fn run_simulation(map_name: &str, ticks: u64, record_file: Option<&str>, tps: u3
     }
     
     // Save recording if requested
-    if let (Some(recorder), Some(filename)) = (recorder, record_file) {
+    if let (Some(recorder), Some(filename)) = (recorder, &scenario.record) {
         recorder.save_replay(filename)?;
         log::info!("Replay saved to {}", filename);
     }
-    
+
+    if let Some(path) = &scenario.timing_report {
+        if let Some(history) = sim.get_resource::<oldtimes_core::resources::TickTimingHistory>() {
+            timing_report::write_report(history, path)?;
+        } else {
+            log::warn!("No TickTimingHistory resource found, skipping timing report");
+        }
+    }
+
     Ok(())
 }
 
-fn replay_simulation(replay_file: &str, verify: bool) -> Result<()> {
+fn replay_simulation(replay_file: &str, verify: bool, dump_digests: bool) -> Result<()> {
     log::info!("Replaying simulation from {}", replay_file);
-    
+
     if verify {
-        let is_deterministic = load_and_verify_replay(replay_file)?;
-        if is_deterministic {
-            log::info!("✓ Replay verification passed - simulation is deterministic");
-        } else {
-            log::error!("✗ Replay verification failed - simulation is not deterministic");
+        let verification = load_and_verify_replay(replay_file)?;
+        if let Some(divergence) = verification.divergence {
+            log::error!(
+                "✗ Replay verification failed - first diverged at tick {} in categories: {}",
+                divergence.tick,
+                divergence.categories.join(", ")
+            );
             std::process::exit(1);
+        } else {
+            log::info!(
+                "✓ Replay verification passed - simulation is deterministic ({} ticks checked)",
+                verification.ticks_checked
+            );
         }
     } else {
-        // Just replay without verification
-        log::info!("Replay playback (without verification) not fully implemented");
+        replay_playback(replay_file, dump_digests)?;
     }
-    
-    Ok(())
-}
 
-fn run_benchmark(scenario: &str, iterations: u32) -> Result<()> {
-    log::info!("Running benchmark: scenario={}, iterations={}", scenario, iterations);
-    
-    let mut total_time = 0.0;
-    let mut total_tps = 0.0;
-    
-    for i in 0..iterations {
-        log::info!("Benchmark iteration {}/{}", i + 1, iterations);
-        
-        let mut sim = SimulationApp::new();
-        sim.initialize_demo();
-        
-        let start_time = Instant::now();
-        let benchmark_ticks = match scenario {
-            "quick" => 100,
-            "standard" => 1000,
-            "long" => 10000,
-            _ => 1000,
-        };
-        
-        sim.run_ticks(benchmark_ticks);
-        
-        let elapsed = start_time.elapsed().as_secs_f32();
-        let tps = benchmark_ticks as f32 / elapsed;
-        
-        total_time += elapsed;
-        total_tps += tps;
-        
-        log::info!("Iteration {} completed: {:.2}s, {:.1} TPS", i + 1, elapsed, tps);
-    }
-    
-    let avg_time = total_time / iterations as f32;
-    let avg_tps = total_tps / iterations as f32;
-    
-    log::info!("Benchmark Results:");
-    log::info!("  Average time: {:.2}s", avg_time);
-    log::info!("  Average TPS: {:.1}", avg_tps);
-    log::info!("  Total time: {:.2}s", total_time);
-    
     Ok(())
 }
 
+
 fn generate_map(output: &str, width: u32, height: u32, seed: u64) -> Result<()> {
     log::info!("Generating map: {}x{}, seed={}", width, height, seed);
     
@@ -270,9 +393,12 @@ fn generate_map(output: &str, width: u32, height: u32, seed: u64) -> Result<()>
         stone_density: 0.1,
         water_patches: 3,
         seed,
+        octaves: 4,
+        lacunarity: 2.0,
+        gain: 0.5,
     };
     
-    let map = oldtimes_core::map::generate_map_from_config(&config);
+    let map = oldtimes_core::map::generate_map(&config);
     
     // Save map to file
     let serialized = to_string_pretty(&map, PrettyConfig::default())?;
@@ -0,0 +1,81 @@
+//! A complete, reproducible description of one headless run - map source,
+//! tick count, target TPS, mods to layer, and which recordings/reports to
+//! produce - loaded from a single RON file instead of scattered CLI flags.
+//! Captured via `Run --write-config` and replayed via `--config`/the
+//! `Scenario` subcommand, so an experiment or benchmark setup can be pinned
+//! down exactly and shared, rather than depending on an ad-hoc command line.
+
+use anyhow::{anyhow, Context, Result};
+use ron::ser::{to_string_pretty, PrettyConfig};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scenario {
+    /// Map name to load (`"demo"` for the generated map, otherwise a map file path)
+    pub map: String,
+
+    /// Number of ticks to run
+    pub ticks: u64,
+
+    /// Target ticks per second
+    pub tps: u32,
+
+    /// Mod directories to layer on top of the base data via `ModLoader`, in order
+    #[serde(default)]
+    pub mods: Vec<String>,
+
+    /// Replay recording output path
+    #[serde(default)]
+    pub record: Option<String>,
+
+    /// Per-system tick-timing HTML report output path
+    #[serde(default)]
+    pub timing_report: Option<String>,
+
+    /// Watch the data directory and hot-reload config between ticks
+    #[serde(default)]
+    pub watch: bool,
+}
+
+impl Scenario {
+    /// Loads and validates a scenario from `path`.
+    pub fn load(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read scenario file '{path}'"))?;
+        let scenario: Scenario = ron::from_str(&content)
+            .with_context(|| format!("Failed to parse scenario file '{path}'"))?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// Serializes this scenario to `path`.
+    pub fn save(&self, path: &str) -> Result<()> {
+        let serialized = to_string_pretty(self, PrettyConfig::default())?;
+        std::fs::write(path, serialized)
+            .with_context(|| format!("Failed to write scenario file '{path}'"))?;
+        Ok(())
+    }
+
+    /// Validates that every referenced mod directory exists and the map is
+    /// resolvable, so a bad scenario file fails fast instead of partway
+    /// through a long run.
+    pub fn validate(&self) -> Result<()> {
+        for mod_dir in &self.mods {
+            if !Path::new(mod_dir).is_dir() {
+                return Err(anyhow!(
+                    "Scenario references missing mod directory '{mod_dir}'"
+                ));
+            }
+        }
+
+        if self.map != "demo" && !Path::new(&self.map).is_file() {
+            return Err(anyhow!(
+                "Scenario references unresolvable map '{}'",
+                self.map
+            ));
+        }
+
+        Ok(())
+    }
+}
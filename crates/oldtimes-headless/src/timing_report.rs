@@ -0,0 +1,218 @@
+//! Renders `TickTimingHistory` as a self-contained `--timing-report` HTML
+//! page: a summary table of min/avg/max/p99 per system up top, a stacked-bar
+//! SVG timeline of every recorded tick below it (x-axis is tick index, each
+//! band within a column is one system's share of that tick), and the raw
+//! per-tick series embedded as downloadable JSON so two runs can be diffed.
+
+use anyhow::Result;
+use oldtimes_core::resources::{TickTimingHistory, TickTimingSample};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+const BAR_WIDTH_PX: f64 = 3.0;
+const CHART_HEIGHT_PX: f64 = 320.0;
+const TICK_LABEL_STRIDE: usize = 50;
+const COLORS: &[&str] = &[
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7",
+    "#9c755f", "#bab0ac",
+];
+
+struct SystemStats {
+    min_ms: f32,
+    avg_ms: f32,
+    max_ms: f32,
+    p99_ms: f32,
+}
+
+/// Writes the timing report for `history` to `path`.
+pub fn write_report(history: &TickTimingHistory, path: &str) -> Result<()> {
+    let samples: Vec<&TickTimingSample> = history.samples().collect();
+
+    let mut system_names: Vec<String> = Vec::new();
+    let mut durations_by_system: BTreeMap<String, Vec<f32>> = BTreeMap::new();
+
+    for sample in &samples {
+        for (name, duration_ms) in &sample.systems {
+            durations_by_system
+                .entry(name.clone())
+                .or_insert_with(|| {
+                    system_names.push(name.clone());
+                    Vec::new()
+                })
+                .push(*duration_ms);
+        }
+    }
+    system_names.sort();
+
+    let stats: BTreeMap<String, SystemStats> = durations_by_system
+        .iter()
+        .map(|(name, durations)| (name.clone(), compute_stats(durations)))
+        .collect();
+
+    let mut html = String::new();
+    write_head(&mut html);
+    write_summary_table(&mut html, &system_names, &stats);
+    write_timeline_svg(&mut html, &samples, &system_names);
+    write_raw_json(&mut html, &samples)?;
+    write_tail(&mut html);
+
+    std::fs::write(path, html)?;
+    log::info!("Timing report written to {path}");
+    Ok(())
+}
+
+fn compute_stats(durations: &[f32]) -> SystemStats {
+    let mut sorted = durations.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let min_ms = *sorted.first().unwrap_or(&0.0);
+    let max_ms = *sorted.last().unwrap_or(&0.0);
+    let avg_ms = sorted.iter().sum::<f32>() / sorted.len().max(1) as f32;
+    let p99_index = ((sorted.len() as f32 * 0.99) as usize).min(sorted.len().saturating_sub(1));
+    let p99_ms = sorted.get(p99_index).copied().unwrap_or(max_ms);
+
+    SystemStats {
+        min_ms,
+        avg_ms,
+        max_ms,
+        p99_ms,
+    }
+}
+
+fn write_head(html: &mut String) {
+    html.push_str(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Old Times - Tick Timing Report</title>
+<style>
+  body { font-family: -apple-system, sans-serif; margin: 2rem; background: #1e1e1e; color: #ddd; }
+  h1, h2 { font-weight: 600; }
+  table { border-collapse: collapse; margin-bottom: 2rem; }
+  th, td { padding: 0.3rem 0.8rem; text-align: right; border-bottom: 1px solid #444; }
+  th:first-child, td:first-child { text-align: left; }
+  .legend { display: flex; flex-wrap: wrap; gap: 0.8rem; margin-bottom: 0.5rem; font-size: 0.85rem; }
+  .swatch { display: inline-block; width: 0.8rem; height: 0.8rem; margin-right: 0.3rem; vertical-align: middle; }
+  .chart-wrap { overflow-x: auto; border: 1px solid #444; background: #141414; }
+  #download-link { color: #6fa8dc; }
+</style>
+</head>
+<body>
+<h1>Tick Timing Report</h1>
+"#,
+    );
+}
+
+fn write_summary_table(
+    html: &mut String,
+    system_names: &[String],
+    stats: &BTreeMap<String, SystemStats>,
+) {
+    html.push_str("<h2>Per-system summary (ms)</h2>\n<table>\n");
+    html.push_str("<tr><th>System</th><th>min</th><th>avg</th><th>p99</th><th>max</th></tr>\n");
+
+    for name in system_names {
+        if let Some(s) = stats.get(name) {
+            let _ = writeln!(
+                html,
+                "<tr><td>{name}</td><td>{:.3}</td><td>{:.3}</td><td>{:.3}</td><td>{:.3}</td></tr>",
+                s.min_ms, s.avg_ms, s.p99_ms, s.max_ms
+            );
+        }
+    }
+
+    html.push_str("</table>\n");
+}
+
+fn write_timeline_svg(html: &mut String, samples: &[&TickTimingSample], system_names: &[String]) {
+    html.push_str("<h2>Per-tick timeline</h2>\n<div class=\"legend\">\n");
+    for (i, name) in system_names.iter().enumerate() {
+        let color = COLORS[i % COLORS.len()];
+        let _ = writeln!(
+            html,
+            "<span><span class=\"swatch\" style=\"background:{color}\"></span>{name}</span>"
+        );
+    }
+    html.push_str("</div>\n<div class=\"chart-wrap\">\n");
+
+    let max_total_ms = samples
+        .iter()
+        .map(|s| s.systems.iter().map(|(_, d)| *d).sum::<f32>())
+        .fold(0.0f32, f32::max)
+        .max(0.001);
+    let px_per_ms = CHART_HEIGHT_PX / max_total_ms as f64;
+
+    let width = (samples.len() as f64 * BAR_WIDTH_PX).max(200.0);
+    let _ = writeln!(
+        html,
+        "<svg width=\"{width}\" height=\"{}\" xmlns=\"http://www.w3.org/2000/svg\">",
+        CHART_HEIGHT_PX + 20.0
+    );
+
+    for (tick_index, sample) in samples.iter().enumerate() {
+        let x = tick_index as f64 * BAR_WIDTH_PX;
+        let mut y_offset = CHART_HEIGHT_PX;
+
+        for (name, duration_ms) in &sample.systems {
+            let color_index = system_names
+                .iter()
+                .position(|n| n == name)
+                .unwrap_or(0);
+            let color = COLORS[color_index % COLORS.len()];
+            let bar_height = (*duration_ms as f64 * px_per_ms).max(0.0);
+            y_offset -= bar_height;
+
+            let _ = writeln!(
+                html,
+                "<rect x=\"{x:.1}\" y=\"{y_offset:.1}\" width=\"{BAR_WIDTH_PX}\" height=\"{bar_height:.1}\" fill=\"{color}\"><title>tick {} - {name}: {duration_ms:.3}ms</title></rect>",
+                sample.tick
+            );
+        }
+
+        if tick_index % TICK_LABEL_STRIDE == 0 {
+            let _ = writeln!(
+                html,
+                "<text x=\"{x:.1}\" y=\"{}\" font-size=\"10\" fill=\"#888\">{}</text>",
+                CHART_HEIGHT_PX + 14.0,
+                sample.tick
+            );
+        }
+    }
+
+    html.push_str("</svg>\n</div>\n");
+}
+
+fn write_raw_json(html: &mut String, samples: &[&TickTimingSample]) -> Result<()> {
+    let json = serde_json::to_string(samples)?;
+    let _ = writeln!(
+        html,
+        r#"<h2>Raw series</h2>
+<a id="download-link" download="tick_timing.json" href="data:application/json;charset=utf-8,{}">Download raw JSON</a>
+<script type="application/json" id="tick-timing-data">{json}</script>"#,
+        urlencode(&json)
+    );
+    Ok(())
+}
+
+fn write_tail(html: &mut String) {
+    html.push_str("</body>\n</html>\n");
+}
+
+/// Minimal percent-encoding sufficient for embedding JSON in a `data:` URI
+/// (the JSON body is ASCII aside from string contents, which are already
+/// escaped by the serializer).
+fn urlencode(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => {
+                let _ = write!(out, "%{byte:02X}");
+            }
+        }
+    }
+    out
+}